@@ -39,4 +39,11 @@ export class UserRouter {
 
     return user;
   }
-}
\ No newline at end of file
+
+  @Query({
+    input: z.object({ userId: z.string() }),
+    output: userSchema,
+  })
+  findUser = async (input: { userId: string }): Promise<User> =>
+    this.userService.getUser(input.userId);
+}
@@ -51,5 +51,15 @@ describe('tRPC Integration (e2e)', () => {
 
       expect(response.body).toHaveProperty('error');
     });
+
+    it('GET /trpc/users.findUser should call the property procedure', async () => {
+      const input = JSON.stringify({ userId: '1' });
+      const response = await request(app.getHttpServer())
+        .get(`/trpc/users.findUser?input=${encodeURIComponent(input)}`)
+        .expect(200);
+
+      expect(response.body.result.data).toHaveProperty('name');
+      expect(response.body.result.data).toHaveProperty('email');
+    });
   });
 });
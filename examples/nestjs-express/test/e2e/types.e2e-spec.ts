@@ -16,6 +16,7 @@ describe('Generated Types (e2e)', () => {
   it('AppRouter has expected router structure', () => {
     type UsersRouter = AppRouter['users'];
     type GetUserById = UsersRouter['getUserById'];
+    type FindUser = UsersRouter['findUser'];
 
     expect(true).toBe(true);
   });
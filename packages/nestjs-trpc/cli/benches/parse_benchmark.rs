@@ -1,8 +1,9 @@
 #![allow(clippy::unwrap_used, clippy::expect_used)]
 
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use nestjs_trpc::{parse_typescript_file, FileScanner, TsParser};
-use std::path::PathBuf;
+use nestjs_trpc::{parse_typescript_file, run_generation, FileScanner, TsParser};
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
 
 fn bench_fixtures_directory() -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("benches/fixtures")
@@ -56,6 +57,62 @@ fn bench_parse_multiple_routers(criterion: &mut Criterion) {
     group.finish();
 }
 
+fn bench_parse_sequential_vs_parallel(criterion: &mut Criterion) {
+    let routers_directory = bench_fixtures_directory().join("large-project/routers");
+
+    let scanner = FileScanner::new(&routers_directory).expect("Scanner initialization failed");
+    let router_files = scanner.scan("*.router.ts").expect("Scan failed");
+
+    let mut group = criterion.benchmark_group("parse_50_routers");
+
+    group.bench_function("sequential", |bencher| {
+        let parser = TsParser::new();
+        bencher.iter(|| parse_all_files(&parser, &router_files));
+    });
+
+    group.bench_function("parallel", |bencher| {
+        let parser = TsParser::new();
+        bencher.iter(|| parser.parse_files(black_box(&router_files)));
+    });
+
+    group.finish();
+}
+
+fn generate_project(project_directory: &Path, output_directory: &Path) {
+    run_generation(
+        black_box(project_directory),
+        output_directory,
+        "**/*.router.ts",
+        None,
+        false,
+    )
+    .expect("Generation failed");
+}
+
+fn bench_full_generation(criterion: &mut Criterion) {
+    let project_directory = bench_fixtures_directory().join("large-project");
+    let output_directory = TempDir::new().expect("Failed to create temp directory");
+    let single_thread_pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(1)
+        .build()
+        .expect("Failed to build thread pool");
+
+    let mut group = criterion.benchmark_group("generation_50_routers");
+
+    group.bench_function("single_thread", |bencher| {
+        bencher.iter(|| {
+            single_thread_pool
+                .install(|| generate_project(&project_directory, output_directory.path()));
+        });
+    });
+
+    group.bench_function("thread_pool", |bencher| {
+        bencher.iter(|| generate_project(&project_directory, output_directory.path()));
+    });
+
+    group.finish();
+}
+
 fn bench_parser_initialization(criterion: &mut Criterion) {
     criterion.bench_function("parser_initialization", |bencher| {
         bencher.iter(|| black_box(TsParser::new()));
@@ -77,6 +134,8 @@ criterion_group!(
     benches,
     bench_parse_single_router,
     bench_parse_multiple_routers,
+    bench_parse_sequential_vs_parallel,
+    bench_full_generation,
     bench_parser_initialization,
     bench_file_scanning,
 );
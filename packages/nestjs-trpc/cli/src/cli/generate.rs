@@ -1,5 +1,5 @@
 use std::fs;
-use std::path::PathBuf;
+use std::path::Path;
 use std::process::ExitCode;
 
 use anyhow::{Context, Result};
@@ -7,21 +7,52 @@ use console::style;
 use tracing::info;
 
 use nestjs_trpc::{
-    compute_diff, config, discover_root_module, extract_trpc_options, find_tsc,
-    resolve_transformer_import, run_tsc_validation, DiffResult, TransformerInfo, TsParser,
+    compute_diff, compute_removal_diff, config, default_cache_directory, extract_trpc_options,
+    find_tsc, generate_in_memory, resolve_transformer_import, run_generation_with_options,
+    run_tsc_validation, server_file_path, DiffResult, GeneratedFile, GeneratedOutput,
+    GenerationOptions, TransformerInfo, TsParser,
 };
 
-use super::output::{DiffSummary, DryRunOutput, ValidationError};
-use super::{ImportExtensionValue, DEFAULT_OUTPUT_PATH, DEFAULT_ROUTER_PATTERN};
+use super::options::{resolve_options, CliOverrides};
+use super::output::{ChangedFile, DiffSummary, DryRunOutput, ValidationError};
+use super::ImportExtensionValue;
 
 const MAX_ERRORS_DISPLAYED: usize = 10;
 const EXIT_SUCCESS: u8 = 0;
 const EXIT_VALIDATION_ERROR: u8 = 1;
+const EXIT_STALE_OUTPUT: u8 = 3;
+
+/// What `generate` does with the generated files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GenerateMode {
+    /// Write the generated files to the output path.
+    Write,
+    /// Generate into a temporary directory and report the diff against the output path.
+    DryRun,
+    /// Like `DryRun`, but exit with a dedicated code when the output on disk is stale.
+    Check,
+}
+
+impl GenerateMode {
+    #[must_use]
+    pub const fn from_flags(dry_run: bool, check: bool) -> Self {
+        if check {
+            Self::Check
+        } else if dry_run {
+            Self::DryRun
+        } else {
+            Self::Write
+        }
+    }
+}
 
-fn resolve_import_extension(
-    value: Option<&ImportExtensionValue>,
-    base_directory: &std::path::Path,
-) -> bool {
+/// Diff between one file on disk and what generation would write there.
+struct OutputFileDiff {
+    display_path: String,
+    diff: DiffResult,
+}
+
+fn resolve_import_extension(value: Option<&ImportExtensionValue>, base_directory: &Path) -> bool {
     match value {
         Some(ImportExtensionValue::Js) => true,
         Some(ImportExtensionValue::None) => false,
@@ -32,62 +63,40 @@ fn resolve_import_extension(
 }
 
 pub fn run_generate(
-    entrypoint_override: Option<&str>,
-    output_override: Option<&str>,
-    router_pattern_override: Option<&str>,
-    dry_run: bool,
+    overrides: &CliOverrides<'_>,
+    mode: GenerateMode,
     json_output: bool,
+    no_cache: bool,
     import_extension: Option<&ImportExtensionValue>,
 ) -> Result<ExitCode> {
-    let current_directory = std::env::current_dir().context("Failed to get current directory")?;
-
-    let root_module_path = if let Some(entrypoint) = entrypoint_override {
-        let path = PathBuf::from(entrypoint);
-        if path.is_absolute() {
-            path
-        } else {
-            current_directory.join(path)
-        }
-    } else {
-        discover_root_module(&current_directory).context(
-            "Failed to discover TRPCModule. Use --entrypoint to specify the module path.",
-        )?
-    };
-
-    info!(
-        root_module = %root_module_path.display(),
-        "Found root module"
-    );
-
-    let (output_path, router_pattern) =
-        build_generation_config(output_override, router_pattern_override)?;
-
-    let base_directory = root_module_path.parent().unwrap_or(&current_directory);
+    let options = resolve_options(overrides)?;
+    let base_directory = options.base_directory.as_path();
 
     let should_add_js = resolve_import_extension(import_extension, base_directory);
-    let transformer = extract_transformer_from_module(&root_module_path);
-
-    if dry_run {
-        run_dry_run_generation(
-            base_directory,
-            &output_path,
-            &router_pattern,
-            json_output,
-            transformer.as_ref(),
-            should_add_js,
-        )
-    } else {
-        run_normal_generation(
-            base_directory,
-            &output_path,
-            &router_pattern,
-            transformer.as_ref(),
-            should_add_js,
-        )
+    let transformer = extract_transformer_from_module(&options.root_module_path);
+
+    let generation_options = GenerationOptions::new(
+        base_directory,
+        &options.output_path,
+        &options.router_pattern,
+    )
+    .with_transformer(transformer)
+    .with_import_extension(should_add_js)
+    .with_root_module(Some(options.root_module_path.clone()))
+    .with_procedure_decorators(options.procedure_decorators.clone())
+    .with_untyped_output(options.untyped_output)
+    .with_meta_type(options.meta_type.clone())
+    .with_cache_directory((!no_cache).then(|| default_cache_directory(base_directory)));
+
+    match mode {
+        GenerateMode::Write => run_normal_generation(&generation_options),
+        GenerateMode::DryRun | GenerateMode::Check => {
+            run_dry_run_generation(&generation_options, mode, json_output)
+        }
     }
 }
 
-fn extract_transformer_from_module(root_module_path: &std::path::Path) -> Option<TransformerInfo> {
+fn extract_transformer_from_module(root_module_path: &Path) -> Option<TransformerInfo> {
     let parser = TsParser::new();
     let parsed = parser.parse_file(root_module_path).ok()?;
     let options = extract_trpc_options(&parsed)?;
@@ -95,23 +104,11 @@ fn extract_transformer_from_module(root_module_path: &std::path::Path) -> Option
     resolve_transformer_import(&parsed, &transformer_identifier)
 }
 
-fn run_normal_generation(
-    base_directory: &std::path::Path,
-    output_path: &std::path::Path,
-    router_pattern: &str,
-    transformer: Option<&TransformerInfo>,
-    import_extension: bool,
-) -> Result<ExitCode> {
-    let generation_result = nestjs_trpc::run_generation(
-        base_directory,
-        output_path,
-        router_pattern,
-        transformer,
-        import_extension,
-    )?;
+fn run_normal_generation(generation_options: &GenerationOptions) -> Result<ExitCode> {
+    let generation_result = run_generation_with_options(generation_options)?;
 
     print_summary(
-        output_path,
+        &generation_options.output_path,
         generation_result.router_count,
         generation_result.procedure_count,
     );
@@ -119,63 +116,114 @@ fn run_normal_generation(
 }
 
 fn run_dry_run_generation(
-    base_directory: &std::path::Path,
-    output_path: &std::path::Path,
-    router_pattern: &str,
+    generation_options: &GenerationOptions,
+    mode: GenerateMode,
     json_output: bool,
-    transformer: Option<&TransformerInfo>,
-    import_extension: bool,
 ) -> Result<ExitCode> {
-    let temp_directory = tempfile::tempdir().context("Failed to create temporary directory")?;
-    let temp_output_path = temp_directory.path().join("@generated");
-
-    let generation_result = nestjs_trpc::run_generation(
-        base_directory,
-        &temp_output_path,
-        router_pattern,
-        transformer,
-        import_extension,
-    )?;
-
-    let generated_server_path = temp_output_path.join("server.ts");
-    let generated_content =
-        fs::read_to_string(&generated_server_path).context("Failed to read generated server.ts")?;
-
-    let existing_server_path = if output_path
-        .extension()
-        .is_some_and(|extension| extension == "ts" || extension == "tsx")
-    {
-        output_path.to_path_buf()
-    } else {
-        output_path.join("server.ts")
-    };
-    let existing_content = fs::read_to_string(&existing_server_path).ok();
-
-    let diff_result = compute_diff(existing_content.as_deref(), &generated_content, "server.ts");
-
-    let tsc_result = run_tsc_validation_if_available(base_directory, &temp_output_path);
+    let GeneratedOutput {
+        result: generation_result,
+        files,
+        removed_files,
+    } = generate_in_memory(generation_options)?;
+    let base_directory = generation_options.base_directory.as_path();
+
+    let file_diffs: Vec<OutputFileDiff> = files
+        .iter()
+        .map(diff_output_file)
+        .chain(
+            removed_files
+                .iter()
+                .filter_map(|path| diff_removed_file(path)),
+        )
+        .collect();
+    let tsc_result = validate_generated_files(base_directory, &files)?;
 
     let dry_run_output =
-        build_dry_run_output(&generation_result, &diff_result, tsc_result.as_ref());
+        build_dry_run_output(&generation_result, &file_diffs, tsc_result.as_ref(), mode);
+    let is_stale = mode == GenerateMode::Check && has_changes(&file_diffs);
 
-    let exit_code = if dry_run_output.success {
-        EXIT_SUCCESS
-    } else {
+    let exit_code = if has_validation_errors(&dry_run_output.validation_errors) {
         EXIT_VALIDATION_ERROR
+    } else if is_stale {
+        EXIT_STALE_OUTPUT
+    } else {
+        EXIT_SUCCESS
     };
 
     if json_output {
         println!("{}", dry_run_output.to_json());
     } else {
-        print_dry_run_human_output(&dry_run_output, &diff_result);
+        print_dry_run_human_output(&dry_run_output, &file_diffs, mode);
     }
 
     Ok(ExitCode::from(exit_code))
 }
 
+/// Compares a generated file with what is on disk at its target path.
+fn diff_output_file(file: &GeneratedFile) -> OutputFileDiff {
+    let display_path = display_path(&file.path);
+    let existing_content = fs::read_to_string(&file.path).ok();
+    let diff = compute_diff(existing_content.as_deref(), &file.content, &display_path);
+
+    OutputFileDiff { display_path, diff }
+}
+
+/// The deletion of a file an earlier run generated and this one no longer does.
+fn diff_removed_file(path: &Path) -> Option<OutputFileDiff> {
+    let display_path = display_path(path);
+    let existing_content = fs::read_to_string(path).ok()?;
+    let diff = compute_removal_diff(&existing_content, &display_path);
+
+    Some(OutputFileDiff { display_path, diff })
+}
+
+fn display_path(path: &Path) -> String {
+    std::env::current_dir()
+        .ok()
+        .and_then(|current_directory| pathdiff::diff_paths(path, current_directory))
+        .unwrap_or_else(|| path.to_path_buf())
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// Writes the generated files to a scratch directory for `tsc`, which runs only if one is found.
+fn validate_generated_files(
+    base_directory: &Path,
+    files: &[GeneratedFile],
+) -> Result<Option<nestjs_trpc::TscResult>> {
+    let temp_directory = tempfile::tempdir().context("Failed to create temporary directory")?;
+    let temp_output_path = temp_directory.path().join("@generated");
+    fs::create_dir_all(&temp_output_path).context("Failed to create temporary directory")?;
+
+    for file in files {
+        let Some(file_name) = file.path.file_name() else {
+            continue;
+        };
+        fs::write(temp_output_path.join(file_name), &file.content)
+            .context("Failed to write generated file to temporary directory")?;
+    }
+
+    Ok(run_tsc_validation_if_available(
+        base_directory,
+        &temp_output_path,
+    ))
+}
+
+fn has_changes(file_diffs: &[OutputFileDiff]) -> bool {
+    file_diffs
+        .iter()
+        .any(|file_diff| file_diff.diff.has_changes)
+}
+
+fn has_validation_errors(validation_errors: &[ValidationError]) -> bool {
+    validation_errors
+        .iter()
+        .any(|error| error.severity == "error")
+}
+
 fn run_tsc_validation_if_available(
-    base_directory: &std::path::Path,
-    temp_output_path: &std::path::Path,
+    base_directory: &Path,
+    temp_output_path: &Path,
 ) -> Option<nestjs_trpc::TscResult> {
     let tsc_binary = find_tsc(base_directory)?;
 
@@ -191,8 +239,9 @@ fn run_tsc_validation_if_available(
 
 fn build_dry_run_output(
     generation_result: &nestjs_trpc::GenerationResult,
-    diff_result: &DiffResult,
+    file_diffs: &[OutputFileDiff],
     tsc_result: Option<&nestjs_trpc::TscResult>,
+    mode: GenerateMode,
 ) -> DryRunOutput {
     let validation_errors: Vec<ValidationError> = tsc_result
         .map(|result| {
@@ -211,32 +260,58 @@ fn build_dry_run_output(
         })
         .unwrap_or_default();
 
-    let has_validation_errors =
-        !validation_errors.is_empty() && validation_errors.iter().any(|e| e.severity == "error");
+    let changed_files: Vec<ChangedFile> = file_diffs
+        .iter()
+        .filter(|file_diff| file_diff.diff.has_changes)
+        .map(|file_diff| ChangedFile {
+            file: file_diff.display_path.clone(),
+            lines_added: file_diff.diff.lines_added,
+            lines_removed: file_diff.diff.lines_removed,
+            unified_diff: file_diff.diff.unified_diff.clone(),
+        })
+        .collect();
+
+    let is_stale = mode == GenerateMode::Check && !changed_files.is_empty();
 
     DryRunOutput {
-        success: !has_validation_errors,
+        success: !has_validation_errors(&validation_errors) && !is_stale,
         router_count: generation_result.router_count,
         procedure_count: generation_result.procedure_count,
         diff: Some(DiffSummary {
-            has_changes: diff_result.has_changes,
-            files_changed: usize::from(diff_result.has_changes),
-            lines_added: diff_result.lines_added,
-            lines_removed: diff_result.lines_removed,
+            has_changes: !changed_files.is_empty(),
+            files_changed: changed_files.len(),
+            lines_added: changed_files.iter().map(|file| file.lines_added).sum(),
+            lines_removed: changed_files.iter().map(|file| file.lines_removed).sum(),
         }),
+        changed_files,
         validation_errors,
         parse_errors: vec![],
     }
 }
 
-fn print_dry_run_human_output(output: &DryRunOutput, diff_result: &DiffResult) {
+fn print_dry_run_human_output(
+    output: &DryRunOutput,
+    file_diffs: &[OutputFileDiff],
+    mode: GenerateMode,
+) {
     println!();
 
-    if output.success {
+    let is_check = mode == GenerateMode::Check;
+    if output.success && is_check {
+        println!(
+            "{} Generated output is up to date",
+            style("✓").green().bold()
+        );
+    } else if output.success {
         println!(
             "{} Dry run completed successfully",
             style("✓").green().bold()
         );
+    } else if is_check && has_changes(file_diffs) {
+        println!(
+            "{} Generated output is out of date",
+            style("✗").red().bold()
+        );
     } else {
         println!("{} Dry run found issues", style("✗").red().bold());
     }
@@ -247,15 +322,23 @@ fn print_dry_run_human_output(output: &DryRunOutput, diff_result: &DiffResult) {
     println!();
 
     if let Some(diff_summary) = &output.diff {
-        print_diff_summary(diff_summary, diff_result);
+        print_diff_summary(diff_summary, file_diffs);
     }
 
     if !output.validation_errors.is_empty() {
         print_validation_errors(&output.validation_errors);
     }
+
+    if is_check && has_changes(file_diffs) {
+        println!(
+            "  Run {} and commit the result.",
+            style("nestjs-trpc generate").cyan()
+        );
+        println!();
+    }
 }
 
-fn print_diff_summary(summary: &DiffSummary, diff_result: &DiffResult) {
+fn print_diff_summary(summary: &DiffSummary, file_diffs: &[OutputFileDiff]) {
     if !summary.has_changes {
         println!("  {} No changes detected", style("•").dim());
         return;
@@ -269,8 +352,11 @@ fn print_diff_summary(summary: &DiffSummary, diff_result: &DiffResult) {
     );
     println!();
 
-    if !diff_result.unified_diff.is_empty() {
-        print_colored_diff(&diff_result.unified_diff);
+    for file_diff in file_diffs
+        .iter()
+        .filter(|file_diff| file_diff.diff.has_changes)
+    {
+        print_colored_diff(&file_diff.diff.unified_diff);
     }
 }
 
@@ -324,35 +410,8 @@ fn print_validation_errors(errors: &[ValidationError]) {
     println!();
 }
 
-fn build_generation_config(
-    output_override: Option<&str>,
-    router_pattern_override: Option<&str>,
-) -> Result<(PathBuf, String)> {
-    let output_path = if let Some(output) = output_override {
-        if PathBuf::from(output).is_absolute() {
-            PathBuf::from(output)
-        } else {
-            std::env::current_dir()?.join(output)
-        }
-    } else {
-        std::env::current_dir()?.join(DEFAULT_OUTPUT_PATH)
-    };
-
-    let router_pattern =
-        router_pattern_override.map_or_else(|| DEFAULT_ROUTER_PATTERN.to_string(), String::from);
-
-    Ok((output_path, router_pattern))
-}
-
-fn print_summary(output_path: &std::path::Path, router_count: usize, procedure_count: usize) {
-    let server_path = if output_path
-        .extension()
-        .is_some_and(|extension| extension == "ts" || extension == "tsx")
-    {
-        output_path.to_path_buf()
-    } else {
-        output_path.join("server.ts")
-    };
+fn print_summary(output_path: &Path, router_count: usize, procedure_count: usize) {
+    let server_path = server_file_path(output_path);
 
     println!("✓ Generated server.ts successfully!");
     println!();
@@ -1,8 +1,10 @@
 mod generate;
+mod options;
 mod output;
 mod watch;
 
-pub use generate::run_generate;
+pub use generate::{run_generate, GenerateMode};
+pub use options::CliOverrides;
 pub use watch::run_watch;
 
 use clap::{Parser, Subcommand, ValueEnum};
@@ -22,10 +24,13 @@ pub enum ImportExtensionValue {
 #[command(author, version, about)]
 #[command(long_about = "A CLI tool that generates tRPC router definitions from \
 NestJS router classes decorated with @Router, @Query, and @Mutation.\n\n\
-Configuration is automatically extracted from TRPCModule.forRoot() in your NestJS module.")]
+Configuration is automatically extracted from TRPCModule.forRoot() in your NestJS module \
+and from nestjs-trpc.config.ts when present. Command-line flags take precedence over the config file.")]
 #[command(after_help = "EXAMPLES:
     nestjs-trpc generate                    Generate router types
     nestjs-trpc generate --dry-run          Validate without writing files
+    nestjs-trpc generate --check            Fail if committed output is stale
+    nestjs-trpc generate --no-cache         Regenerate without the on-disk cache
     nestjs-trpc watch                       Watch mode for development
 
 Learn more: https://nestjs-trpc.io")]
@@ -48,8 +53,14 @@ pub enum Commands {
     #[command(after_help = "EXAMPLES:
     nestjs-trpc generate
     nestjs-trpc generate --entrypoint src/app.module.ts
-    nestjs-trpc generate --dry-run --json")]
+    nestjs-trpc generate --config packages/api/nestjs-trpc.config.ts
+    nestjs-trpc generate --dry-run --json
+    nestjs-trpc generate --check --json")]
     Generate {
+        /// Path to `nestjs-trpc.config.ts` (searched upward from the current directory if not specified)
+        #[arg(short, long, value_name = "PATH", help_heading = "Input")]
+        config: Option<String>,
+
         /// Path to `NestJS` module entry point (auto-discovered if not specified)
         #[arg(short, long, value_name = "PATH", help_heading = "Input")]
         entrypoint: Option<String>,
@@ -70,6 +81,14 @@ pub enum Commands {
         #[arg(long, help_heading = "Validation")]
         dry_run: bool,
 
+        /// Exit with code 3 when the output on disk is not what generation would write (implies --dry-run)
+        #[arg(long, help_heading = "Validation")]
+        check: bool,
+
+        /// Regenerate every router instead of reusing `node_modules/.cache/nestjs-trpc`
+        #[arg(long, help_heading = "Output")]
+        no_cache: bool,
+
         /// Add .js extension to local import paths
         ///
         /// `auto` (default) reads the nearest tsconfig.json and enables .js
@@ -89,6 +108,10 @@ pub enum Commands {
     nestjs-trpc watch --entrypoint src/app.module.ts
     nestjs-trpc watch -v")]
     Watch {
+        /// Path to `nestjs-trpc.config.ts` (searched upward from the current directory if not specified)
+        #[arg(short, long, value_name = "PATH", help_heading = "Input")]
+        config: Option<String>,
+
         /// Path to `NestJS` module entry point (auto-discovered if not specified)
         #[arg(short, long, value_name = "PATH", help_heading = "Input")]
         entrypoint: Option<String>,
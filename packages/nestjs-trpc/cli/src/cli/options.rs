@@ -0,0 +1,174 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use tracing::{debug, info};
+
+use nestjs_trpc::{
+    config, discover_root_module, Config, ConfigError, ProcedureDecoratorAlias, UntypedOutput,
+};
+
+use super::{DEFAULT_OUTPUT_PATH, DEFAULT_ROUTER_PATTERN};
+
+/// Command-line flags that take precedence over `nestjs-trpc.config.ts`.
+#[derive(Debug, Default)]
+pub struct CliOverrides<'a> {
+    pub config: Option<&'a str>,
+    pub entrypoint: Option<&'a str>,
+    pub output: Option<&'a str>,
+    pub router_pattern: Option<&'a str>,
+}
+
+/// Inputs for a generation run after merging CLI flags with the config file.
+#[derive(Debug)]
+pub struct ResolvedOptions {
+    pub root_module_path: PathBuf,
+    pub base_directory: PathBuf,
+    pub output_path: PathBuf,
+    pub router_pattern: String,
+    pub procedure_decorators: BTreeMap<String, ProcedureDecoratorAlias>,
+    pub untyped_output: UntypedOutput,
+    pub meta_type: Option<String>,
+}
+
+pub fn resolve_options(overrides: &CliOverrides<'_>) -> Result<ResolvedOptions> {
+    let current_directory = std::env::current_dir().context("Failed to get current directory")?;
+
+    let options = match load_config(overrides.config, &current_directory)? {
+        Some((config_directory, config)) => {
+            resolve_with_config(overrides, &current_directory, &config_directory, config)?
+        }
+        None => resolve_without_config(overrides, &current_directory)?,
+    };
+
+    info!(
+        root_module = %options.root_module_path.display(),
+        "Found root module"
+    );
+
+    debug!(
+        output_path = %options.output_path.display(),
+        router_pattern = %options.router_pattern,
+        base_directory = %options.base_directory.display(),
+        "Resolved generation options"
+    );
+
+    Ok(options)
+}
+
+fn load_config(
+    explicit_path: Option<&str>,
+    current_directory: &Path,
+) -> Result<Option<(PathBuf, Config)>> {
+    let config_path = match explicit_path {
+        Some(path) => absolutize(path, current_directory),
+        None => match config::find_config_file(current_directory) {
+            Some(path) => path,
+            None => return Ok(None),
+        },
+    };
+
+    let config_directory = config_path
+        .parent()
+        .unwrap_or(current_directory)
+        .to_path_buf();
+    let config = Config::load(&config_path)?.resolve_paths(&config_directory);
+
+    info!(config = %config_path.display(), "Loaded configuration file");
+
+    Ok(Some((config_directory, config)))
+}
+
+fn resolve_with_config(
+    overrides: &CliOverrides<'_>,
+    current_directory: &Path,
+    config_directory: &Path,
+    config: Config,
+) -> Result<ResolvedOptions> {
+    let root_module_path = match (overrides.entrypoint, config.generation.root_module) {
+        (Some(entrypoint), _) => absolutize(entrypoint, current_directory),
+        (None, Some(root_module)) if root_module.is_file() => root_module,
+        (None, Some(root_module)) => {
+            return Err(ConfigError::RootModuleNotFound { path: root_module }.into());
+        }
+        (None, None) => default_root_module(config_directory)?,
+    };
+
+    let output_path = overrides
+        .output
+        .map_or(config.generation.output_path, |output| {
+            absolutize(output, current_directory)
+        });
+
+    let router_pattern = overrides
+        .router_pattern
+        .map_or(config.generation.router_pattern, String::from);
+
+    Ok(ResolvedOptions {
+        root_module_path,
+        base_directory: config_directory.to_path_buf(),
+        output_path,
+        router_pattern,
+        procedure_decorators: config.parsing.procedure_decorators,
+        untyped_output: config.generation.untyped_output,
+        meta_type: config.generation.meta_type,
+    })
+}
+
+fn default_root_module(config_directory: &Path) -> Result<PathBuf> {
+    let default_path = config_directory.join(config::DEFAULT_ROOT_MODULE);
+    if default_path.is_file() {
+        return Ok(default_path);
+    }
+
+    discover_root_module(config_directory).context(
+        "Failed to discover TRPCModule. Set `generation.rootModule` in the config file \
+        or use --entrypoint to specify the module path.",
+    )
+}
+
+fn resolve_without_config(
+    overrides: &CliOverrides<'_>,
+    current_directory: &Path,
+) -> Result<ResolvedOptions> {
+    let root_module_path = if let Some(entrypoint) = overrides.entrypoint {
+        absolutize(entrypoint, current_directory)
+    } else {
+        discover_root_module(current_directory).context(
+            "Failed to discover TRPCModule. Use --entrypoint to specify the module path.",
+        )?
+    };
+
+    let output_path = absolutize(
+        overrides.output.unwrap_or(DEFAULT_OUTPUT_PATH),
+        current_directory,
+    );
+
+    let router_pattern = overrides
+        .router_pattern
+        .map_or_else(|| DEFAULT_ROUTER_PATTERN.to_string(), String::from);
+
+    let base_directory = root_module_path
+        .parent()
+        .unwrap_or(current_directory)
+        .to_path_buf();
+
+    Ok(ResolvedOptions {
+        root_module_path,
+        base_directory,
+        output_path,
+        router_pattern,
+        procedure_decorators: BTreeMap::new(),
+        untyped_output: UntypedOutput::default(),
+        meta_type: None,
+    })
+}
+
+fn absolutize(path: &str, current_directory: &Path) -> PathBuf {
+    let path = PathBuf::from(path);
+    if path.is_absolute() {
+        path
+    } else {
+        current_directory.join(path)
+    }
+}
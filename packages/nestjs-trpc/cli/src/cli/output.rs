@@ -9,6 +9,8 @@ pub struct DryRunOutput {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub diff: Option<DiffSummary>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub changed_files: Vec<ChangedFile>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub validation_errors: Vec<ValidationError>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub parse_errors: Vec<ParseError>,
@@ -23,6 +25,15 @@ pub struct DiffSummary {
     pub lines_removed: usize,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangedFile {
+    pub file: String,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    pub unified_diff: String,
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ValidationError {
@@ -68,6 +79,7 @@ mod tests {
             router_count: 2,
             procedure_count: 5,
             diff: None,
+            changed_files: vec![],
             validation_errors: vec![],
             parse_errors: vec![],
         };
@@ -77,6 +89,7 @@ mod tests {
         assert!(json.contains("\"routerCount\": 2"));
         assert!(json.contains("\"procedureCount\": 5"));
         assert!(!json.contains("diff"));
+        assert!(!json.contains("changedFiles"));
         assert!(!json.contains("validationErrors"));
         assert!(!json.contains("parseErrors"));
     }
@@ -93,6 +106,12 @@ mod tests {
                 lines_added: 10,
                 lines_removed: 5,
             }),
+            changed_files: vec![ChangedFile {
+                file: "src/@generated/server.ts".to_string(),
+                lines_added: 10,
+                lines_removed: 5,
+                unified_diff: "--- a/src/@generated/server.ts".to_string(),
+            }],
             validation_errors: vec![],
             parse_errors: vec![],
         };
@@ -102,6 +121,8 @@ mod tests {
         assert!(json.contains("\"filesChanged\": 1"));
         assert!(json.contains("\"linesAdded\": 10"));
         assert!(json.contains("\"linesRemoved\": 5"));
+        assert!(json.contains("\"changedFiles\""));
+        assert!(json.contains("\"unifiedDiff\""));
     }
 
     #[test]
@@ -111,6 +132,7 @@ mod tests {
             router_count: 0,
             procedure_count: 0,
             diff: None,
+            changed_files: vec![],
             validation_errors: vec![ValidationError {
                 file: "src/app.ts".to_string(),
                 line: 10,
@@ -139,6 +161,7 @@ mod tests {
             router_count: 1,
             procedure_count: 1,
             diff: None,
+            changed_files: vec![],
             validation_errors: vec![],
             parse_errors: vec![],
         };
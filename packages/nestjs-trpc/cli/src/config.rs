@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -9,11 +10,20 @@ use swc_ecma_ast::{
 use tracing::{debug, trace};
 
 use crate::error::ConfigError;
-use crate::parser::TsParser;
+use crate::parser::{ProcedureDecoratorAlias, TsParser};
+use crate::ProcedureType;
 
 const DEFAULT_OUTPUT_PATH: &str = "./src/@generated";
 const DEFAULT_ROUTER_PATTERN: &str = "src/**/*.router.ts";
-const DEFAULT_ROOT_MODULE: &str = "src/app.module.ts";
+/// Root module used when `generation.rootModule` is unset and this file exists.
+pub const DEFAULT_ROOT_MODULE: &str = "src/app.module.ts";
+
+/// File names searched for by [`find_config_file`], in priority order.
+pub const CONFIG_FILE_NAMES: &[&str] = &[
+    "nestjs-trpc.config.ts",
+    "nestjs-trpc.config.mts",
+    "nestjs-trpc.config.js",
+];
 
 #[derive(Debug, Clone, Default)]
 pub struct Config {
@@ -25,12 +35,45 @@ pub struct Config {
 pub struct GenerationConfig {
     pub output_path: PathBuf,
     pub router_pattern: String,
-    pub root_module: PathBuf,
+    /// [`DEFAULT_ROOT_MODULE`], or discovered from the project, when unset.
+    pub root_module: Option<PathBuf>,
+    pub untyped_output: UntypedOutput,
+    /// Type of procedure `meta` in `server.ts`, e.g. `{ roles: string[] }`; when unset,
+    /// `Record<string, unknown>` is used once a procedure declares `meta`.
+    pub meta_type: Option<String>,
+}
+
+/// What a procedure's output is typed as in `server.ts` when it has no `output` schema and its
+/// router's type can't be imported to infer it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UntypedOutput {
+    /// `any`, which turns off type checking of the output on the client.
+    #[default]
+    Any,
+    /// `unknown`, which the client has to narrow before using.
+    Unknown,
+    /// Generation fails.
+    Error,
+}
+
+impl UntypedOutput {
+    /// Parses the `generation.untypedOutput` setting: `'any'`, `'unknown'` or `'error'`.
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "any" => Some(Self::Any),
+            "unknown" => Some(Self::Unknown),
+            "error" => Some(Self::Error),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct ParsingConfig {
     pub decorators: bool,
+    /// Custom decorators that declare procedures, e.g. ones composed with `applyDecorators`.
+    pub procedure_decorators: BTreeMap<String, ProcedureDecoratorAlias>,
 }
 
 impl Default for GenerationConfig {
@@ -38,14 +81,19 @@ impl Default for GenerationConfig {
         Self {
             output_path: PathBuf::from(DEFAULT_OUTPUT_PATH),
             router_pattern: DEFAULT_ROUTER_PATTERN.to_string(),
-            root_module: PathBuf::from(DEFAULT_ROOT_MODULE),
+            root_module: None,
+            untyped_output: UntypedOutput::default(),
+            meta_type: None,
         }
     }
 }
 
 impl Default for ParsingConfig {
     fn default() -> Self {
-        Self { decorators: true }
+        Self {
+            decorators: true,
+            procedure_decorators: BTreeMap::new(),
+        }
     }
 }
 
@@ -224,6 +272,26 @@ impl Config {
             "outputPath" => Self::set_output_path(config, value),
             "routerPattern" => Self::set_router_pattern(config, value),
             "rootModule" => Self::set_root_module(config, value),
+            "untypedOutput" => {
+                config.untyped_output = Self::extract_string_literal(value)
+                    .as_deref()
+                    .and_then(UntypedOutput::parse)
+                    .ok_or_else(|| ConfigError::InvalidValue {
+                        path: path.to_path_buf(),
+                        field: "generation.untypedOutput".to_string(),
+                        message: "expected 'any', 'unknown' or 'error'".to_string(),
+                    })?;
+            }
+            "metaType" => {
+                let meta_type = Self::extract_string_literal(value)
+                    .filter(|meta_type| !meta_type.trim().is_empty())
+                    .ok_or_else(|| ConfigError::InvalidValue {
+                        path: path.to_path_buf(),
+                        field: "generation.metaType".to_string(),
+                        message: "expected a TypeScript type as a string".to_string(),
+                    })?;
+                config.meta_type = Some(meta_type);
+            }
             _ => {
                 return Err(ConfigError::InvalidSyntax {
                     path: path.to_path_buf(),
@@ -248,7 +316,7 @@ impl Config {
 
     fn set_root_module(config: &mut GenerationConfig, value: &Expr) {
         if let Some(string_value) = Self::extract_string_literal(value) {
-            config.root_module = PathBuf::from(string_value);
+            config.root_module = Some(PathBuf::from(string_value));
         }
     }
 
@@ -270,6 +338,9 @@ impl Config {
     ) -> Result<(), ConfigError> {
         match key {
             "decorators" => Self::set_decorators(config, value),
+            "procedureDecorators" => {
+                config.procedure_decorators = Self::parse_procedure_decorators(value, path)?;
+            }
             _ => {
                 return Err(ConfigError::InvalidSyntax {
                     path: path.to_path_buf(),
@@ -286,6 +357,103 @@ impl Config {
         }
     }
 
+    fn parse_procedure_decorators(
+        value: &Expr,
+        path: &Path,
+    ) -> Result<BTreeMap<String, ProcedureDecoratorAlias>, ConfigError> {
+        let Expr::Object(object) = value else {
+            return Err(ConfigError::InvalidValue {
+                path: path.to_path_buf(),
+                field: "parsing.procedureDecorators".to_string(),
+                message: "expected an object mapping decorator names to procedure types"
+                    .to_string(),
+            });
+        };
+
+        Self::iterate_key_value_properties(object)
+            .map(|(name, definition)| {
+                let field = format!("parsing.procedureDecorators.{name}");
+                let alias = Self::parse_procedure_decorator(definition, &field, path)?;
+                Ok((name, alias))
+            })
+            .collect()
+    }
+
+    /// Accepts a procedure type (`'mutation'`) or `{ type: 'mutation', middlewares: [AdminGuard] }`.
+    fn parse_procedure_decorator(
+        definition: &Expr,
+        field: &str,
+        path: &Path,
+    ) -> Result<ProcedureDecoratorAlias, ConfigError> {
+        let invalid_value = |message: &str| ConfigError::InvalidValue {
+            path: path.to_path_buf(),
+            field: field.to_string(),
+            message: message.to_string(),
+        };
+
+        let (procedure_type, middlewares) = match definition {
+            Expr::Object(object) => Self::parse_procedure_decorator_object(object, path, field)?,
+            _ => (Self::extract_string_literal(definition), Some(Vec::new())),
+        };
+
+        let procedure_type = procedure_type
+            .as_deref()
+            .and_then(parse_procedure_type)
+            .ok_or_else(|| invalid_value("expected type 'query', 'mutation' or 'subscription'"))?;
+        let middlewares = middlewares
+            .ok_or_else(|| invalid_value("expected `middlewares` to be an array of names"))?;
+
+        Ok(ProcedureDecoratorAlias {
+            procedure_type,
+            middlewares,
+        })
+    }
+
+    fn parse_procedure_decorator_object(
+        object: &ObjectLit,
+        path: &Path,
+        field: &str,
+    ) -> Result<(Option<String>, Option<Vec<String>>), ConfigError> {
+        let mut procedure_type = None;
+        let mut middlewares = Some(Vec::new());
+
+        for (key, value) in Self::iterate_key_value_properties(object) {
+            match key.as_str() {
+                "type" => procedure_type = Self::extract_string_literal(value),
+                "middlewares" => middlewares = Self::extract_name_list(value),
+                _ => return Err(Self::unknown_field_error(path, field, &key)),
+            }
+        }
+
+        Ok((procedure_type, middlewares))
+    }
+
+    fn unknown_field_error(path: &Path, section: &str, key: &str) -> ConfigError {
+        ConfigError::InvalidSyntax {
+            path: path.to_path_buf(),
+            message: format!("Unknown field in {section}: {key}"),
+        }
+    }
+
+    /// Names from an array of identifiers or string literals, e.g. `[AdminGuard, 'AuditMiddleware']`.
+    fn extract_name_list(expression: &Expr) -> Option<Vec<String>> {
+        let Expr::Array(array) = expression else {
+            return None;
+        };
+
+        array
+            .elems
+            .iter()
+            .map(
+                |element| match element.as_ref().map(|element| &*element.expr) {
+                    Some(Expr::Ident(identifier)) => Some(identifier.sym.to_string()),
+                    Some(value) => Self::extract_string_literal(value),
+                    None => None,
+                },
+            )
+            .collect()
+    }
+
     fn iterate_key_value_properties(object: &ObjectLit) -> impl Iterator<Item = (String, &Expr)> {
         object.props.iter().filter_map(Self::extract_key_value_pair)
     }
@@ -330,14 +498,63 @@ impl Config {
             self.generation.output_path = base.join(&self.generation.output_path);
         }
 
-        if self.generation.root_module.is_relative() {
-            self.generation.root_module = base.join(&self.generation.root_module);
-        }
+        // Joining onto an absolute path keeps it as is.
+        self.generation.root_module = self
+            .generation
+            .root_module
+            .map(|root_module| base.join(root_module));
 
         self
     }
 }
 
+fn parse_procedure_type(value: &str) -> Option<ProcedureType> {
+    match value {
+        "query" => Some(ProcedureType::Query),
+        "mutation" => Some(ProcedureType::Mutation),
+        "subscription" => Some(ProcedureType::Subscription),
+        _ => None,
+    }
+}
+
+/// Finds the nearest `nestjs-trpc.config.{ts,mts,js}` file.
+///
+/// Walks upward from `start_directory` through ancestor directories, stopping at the
+/// repository or workspace root so a config file outside the project is never picked up.
+#[must_use]
+pub fn find_config_file(start_directory: &Path) -> Option<PathBuf> {
+    let mut current = Some(start_directory.to_path_buf());
+    while let Some(directory) = current.take() {
+        let found = CONFIG_FILE_NAMES
+            .iter()
+            .map(|name| directory.join(name))
+            .find(|candidate| candidate.is_file());
+
+        if let Some(config_path) = found {
+            debug!(path = %config_path.display(), "Found configuration file");
+            return Some(config_path);
+        }
+
+        if is_project_root(&directory) {
+            trace!(directory = %directory.display(), "Stopped config search at project root");
+            break;
+        }
+        current = directory.parent().map(Path::to_path_buf);
+    }
+    None
+}
+
+/// A directory holding `.git`, `pnpm-workspace.yaml` or a `package.json` that declares
+/// `workspaces`.
+fn is_project_root(directory: &Path) -> bool {
+    directory.join(".git").exists()
+        || directory.join("pnpm-workspace.yaml").is_file()
+        || fs::read_to_string(directory.join("package.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+            .is_some_and(|manifest| manifest.get("workspaces").is_some())
+}
+
 /// Detects whether `.js` extensions should be added to local import paths
 /// by resolving the full `extends` chain and inspecting the merged
 /// `compilerOptions.module` and `compilerOptions.moduleResolution`.
@@ -397,7 +614,7 @@ export default {
         assert_eq!(config.generation.router_pattern, "lib/**/*.router.ts");
         assert_eq!(
             config.generation.root_module,
-            PathBuf::from("lib/app.module.ts")
+            Some(PathBuf::from("lib/app.module.ts"))
         );
         assert!(config.parsing.decorators);
     }
@@ -416,10 +633,7 @@ export default {
 
         assert_eq!(config.generation.output_path, PathBuf::from("./output"));
         assert_eq!(config.generation.router_pattern, "src/**/*.router.ts");
-        assert_eq!(
-            config.generation.root_module,
-            PathBuf::from("src/app.module.ts")
-        );
+        assert_eq!(config.generation.root_module, None);
         assert!(config.parsing.decorators);
     }
 
@@ -434,10 +648,7 @@ export default {
             PathBuf::from("./src/@generated")
         );
         assert_eq!(config.generation.router_pattern, "src/**/*.router.ts");
-        assert_eq!(
-            config.generation.root_module,
-            PathBuf::from("src/app.module.ts")
-        );
+        assert_eq!(config.generation.root_module, None);
         assert!(config.parsing.decorators);
     }
 
@@ -456,6 +667,106 @@ export default {
         assert!(!config.parsing.decorators);
     }
 
+    #[test]
+    fn test_parse_procedure_decorators() {
+        let ts = r"
+import { AdminGuard } from './admin.guard';
+
+export default {
+  parsing: {
+    procedureDecorators: {
+      AdminMutation: { type: 'mutation', middlewares: [AdminGuard, 'AuditMiddleware'] },
+      CachedQuery: 'query'
+    }
+  }
+}
+";
+
+        let config = Config::parse(ts, "test.config.ts").expect("Failed to parse config");
+
+        assert_eq!(
+            config.parsing.procedure_decorators.get("AdminMutation"),
+            Some(&ProcedureDecoratorAlias {
+                procedure_type: ProcedureType::Mutation,
+                middlewares: vec!["AdminGuard".to_string(), "AuditMiddleware".to_string()],
+            })
+        );
+        assert_eq!(
+            config.parsing.procedure_decorators.get("CachedQuery"),
+            Some(&ProcedureDecoratorAlias {
+                procedure_type: ProcedureType::Query,
+                middlewares: Vec::new(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_procedure_decorator_unknown_type() {
+        let ts = r"
+export default {
+  parsing: {
+    procedureDecorators: { AdminMutation: { type: 'action' } }
+  }
+}
+";
+
+        let error = Config::parse(ts, "test.config.ts").unwrap_err();
+
+        match error {
+            ConfigError::InvalidValue { field, .. } => {
+                assert_eq!(field, "parsing.procedureDecorators.AdminMutation");
+            }
+            _ => panic!("Expected InvalidValue error"),
+        }
+    }
+
+    #[test]
+    fn test_parse_untyped_output() {
+        let ts = r"
+export default {
+  generation: { untypedOutput: 'error' }
+}
+";
+
+        let config = Config::parse(ts, "test.config.ts").expect("Failed to parse config");
+        assert_eq!(config.generation.untyped_output, UntypedOutput::Error);
+
+        let error = Config::parse(
+            "export default { generation: { untypedOutput: 'never' } }",
+            "test.config.ts",
+        )
+        .unwrap_err();
+        assert!(matches!(
+            error,
+            ConfigError::InvalidValue { field, .. } if field == "generation.untypedOutput"
+        ));
+    }
+
+    #[test]
+    fn test_parse_meta_type() {
+        let ts = r"
+export default {
+  generation: { metaType: '{ roles: string[] }' }
+}
+";
+
+        let config = Config::parse(ts, "test.config.ts").expect("Failed to parse config");
+        assert_eq!(
+            config.generation.meta_type.as_deref(),
+            Some("{ roles: string[] }")
+        );
+
+        let error = Config::parse(
+            "export default { generation: { metaType: 42 } }",
+            "test.config.ts",
+        )
+        .unwrap_err();
+        assert!(matches!(
+            error,
+            ConfigError::InvalidValue { field, .. } if field == "generation.metaType"
+        ));
+    }
+
     #[test]
     fn test_parse_invalid_syntax() {
         let ts = "export default { invalid typescript";
@@ -759,6 +1070,101 @@ export default {
         assert_eq!(result, Some(false));
     }
 
+    #[test]
+    fn test_find_config_file_in_ancestor_directory() {
+        let temporary_directory = TempDir::new().unwrap();
+        let root = temporary_directory.path();
+        fs::write(root.join("nestjs-trpc.config.ts"), "export default {}").unwrap();
+
+        let nested = root.join("src/modules");
+        fs::create_dir_all(&nested).unwrap();
+
+        let found = find_config_file(&nested);
+        assert_eq!(found, Some(root.join("nestjs-trpc.config.ts")));
+    }
+
+    #[test]
+    fn test_find_config_file_prefers_ts_over_js() {
+        let temporary_directory = TempDir::new().unwrap();
+        let root = temporary_directory.path();
+        fs::write(root.join("nestjs-trpc.config.js"), "export default {}").unwrap();
+        fs::write(root.join("nestjs-trpc.config.ts"), "export default {}").unwrap();
+
+        let found = find_config_file(root);
+        assert_eq!(found, Some(root.join("nestjs-trpc.config.ts")));
+    }
+
+    #[test]
+    fn test_find_config_file_stops_at_repository_root() {
+        let temporary_directory = TempDir::new().unwrap();
+        let root = temporary_directory.path();
+        fs::write(root.join("nestjs-trpc.config.ts"), "export default {}").unwrap();
+
+        let repository = root.join("repository");
+        fs::create_dir_all(repository.join(".git")).unwrap();
+        let nested = repository.join("src");
+        fs::create_dir_all(&nested).unwrap();
+
+        assert_eq!(find_config_file(&nested), None);
+    }
+
+    #[test]
+    fn test_find_config_file_stops_at_workspace_root() {
+        let temporary_directory = TempDir::new().unwrap();
+        let root = temporary_directory.path();
+        fs::write(root.join("nestjs-trpc.config.ts"), "export default {}").unwrap();
+
+        let workspace = root.join("workspace");
+        let package = workspace.join("packages/api");
+        fs::create_dir_all(&package).unwrap();
+        fs::write(
+            workspace.join("package.json"),
+            r#"{ "private": true, "workspaces": ["packages/*"] }"#,
+        )
+        .unwrap();
+        fs::write(package.join("package.json"), r#"{ "name": "api" }"#).unwrap();
+
+        assert_eq!(find_config_file(&package), None);
+
+        fs::write(workspace.join("nestjs-trpc.config.ts"), "export default {}").unwrap();
+        assert_eq!(
+            find_config_file(&package),
+            Some(workspace.join("nestjs-trpc.config.ts"))
+        );
+    }
+
+    #[test]
+    fn test_find_config_file_missing() {
+        let temporary_directory = TempDir::new().unwrap();
+
+        assert_eq!(find_config_file(temporary_directory.path()), None);
+    }
+
+    #[test]
+    fn test_resolve_paths_anchors_to_base() {
+        let ts = r"
+export default defineConfig({
+  generation: {
+    outputPath: './generated',
+    rootModule: 'app/app.module.ts'
+  }
+})
+";
+
+        let config = Config::parse(ts, "nestjs-trpc.config.ts")
+            .expect("Failed to parse config")
+            .resolve_paths("/project");
+
+        assert_eq!(
+            config.generation.output_path,
+            PathBuf::from("/project/generated")
+        );
+        assert_eq!(
+            config.generation.root_module,
+            Some(PathBuf::from("/project/app/app.module.ts"))
+        );
+    }
+
     #[test]
     fn test_default_config() {
         let config = Config::default();
@@ -768,10 +1174,7 @@ export default {
             PathBuf::from("./src/@generated")
         );
         assert_eq!(config.generation.router_pattern, "src/**/*.router.ts");
-        assert_eq!(
-            config.generation.root_module,
-            PathBuf::from("src/app.module.ts")
-        );
+        assert_eq!(config.generation.root_module, None);
         assert!(config.parsing.decorators);
     }
 }
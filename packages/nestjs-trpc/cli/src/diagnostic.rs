@@ -80,13 +80,13 @@ impl SyntaxDiagnostic {
 /// Diagnostic error for invalid decorator usage.
 #[derive(Error, Debug, Diagnostic)]
 #[error("Invalid @{decorator} decorator")]
-#[diagnostic(
-    code(nestjs_trpc::invalid_decorator),
-    help("Decorators must be @Router, @Query, or @Mutation with valid arguments")
-)]
+#[diagnostic(code(nestjs_trpc::invalid_decorator))]
 pub struct DecoratorDiagnostic {
     pub decorator: String,
 
+    #[help]
+    pub help: String,
+
     #[source_code]
     src: NamedSource<String>,
 
@@ -106,10 +106,19 @@ impl DecoratorDiagnostic {
     ) -> Self {
         Self {
             decorator,
+            help: "Decorators must be @Router, @Query, or @Mutation with valid arguments"
+                .to_string(),
             src: NamedSource::new(path.display().to_string(), content.to_string()),
             span: SourceSpan::new(offset.into(), length),
         }
     }
+
+    /// Replaces the generic help with advice specific to what is wrong with the decorator.
+    #[must_use]
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = help.into();
+        self
+    }
 }
 
 /// Diagnostic error for unresolved imports.
@@ -181,6 +190,46 @@ impl SchemaDiagnostic {
     }
 }
 
+/// Diagnostic for a router whose procedures can't have their outputs inferred because
+/// `server.ts` can't import its class.
+#[derive(Error, Debug, Diagnostic)]
+#[error("Router {class_name} is not exported, so its procedures' outputs can't be typed")]
+#[diagnostic(code(nestjs_trpc::unexported_router))]
+pub struct UnexportedRouterDiagnostic {
+    pub class_name: String,
+
+    #[help]
+    pub help: String,
+
+    #[source_code]
+    src: NamedSource<String>,
+
+    #[label("not exported")]
+    span: SourceSpan,
+}
+
+impl UnexportedRouterDiagnostic {
+    /// Creates an unexported router diagnostic pointing at the class name.
+    #[must_use]
+    pub fn new(
+        path: &Path,
+        content: &str,
+        class_name: String,
+        offset: usize,
+        length: usize,
+    ) -> Self {
+        Self {
+            help: format!(
+                "Export the class (`export class {class_name}`) so `server.ts` can infer the \
+                outputs of {class_name}'s procedures, or give each of them an `output` schema"
+            ),
+            class_name,
+            src: NamedSource::new(path.display().to_string(), content.to_string()),
+            span: SourceSpan::new(offset.into(), length),
+        }
+    }
+}
+
 /// Finds a reasonable error length for highlighting.
 /// Tries to highlight the token at the error position.
 fn find_error_length(content: &str, offset: usize) -> usize {
@@ -302,4 +351,18 @@ mod tests {
 
         assert_eq!(diagnostic.name, "unknownSchema");
     }
+
+    #[test]
+    fn test_unexported_router_diagnostic_creation() {
+        let diagnostic = UnexportedRouterDiagnostic::new(
+            Path::new("users.router.ts"),
+            "@Router()\nclass UsersRouter {}",
+            "UsersRouter".to_string(),
+            16,
+            11,
+        );
+
+        assert_eq!(diagnostic.class_name, "UsersRouter");
+        assert!(diagnostic.help.contains("export class UsersRouter"));
+    }
 }
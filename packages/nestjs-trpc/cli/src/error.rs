@@ -63,6 +63,12 @@ pub enum ConfigError {
     #[error("Could not find TRPCModule.forRoot() in any of the following locations:\n{}", .searched_paths.iter().map(|p| format!("  - {}", p.display())).collect::<Vec<_>>().join("\n"))]
     #[diagnostic(help("Use --entrypoint to specify the path to your NestJS module containing TRPCModule.forRoot()"))]
     ModuleNotFound { searched_paths: Vec<PathBuf> },
+
+    #[error("Configured `generation.rootModule` '{path}' does not exist")]
+    #[diagnostic(help(
+        "Fix the path in the configuration file, or remove `rootModule` to discover the module"
+    ))]
+    RootModuleNotFound { path: PathBuf },
 }
 
 #[derive(Error, Debug, Diagnostic)]
@@ -185,6 +191,57 @@ pub enum GeneratorError {
         message: String,
     },
 
+    #[error("'{path}' is both a procedure of {routers} and a sub-router")]
+    #[diagnostic(help(
+        "Rename the procedure, or change the alias of the routers nested under '{path}'"
+    ))]
+    RouterPathConflict { path: String, routers: String },
+
+    #[error("'{path}' is declared by both {first_router} and {second_router}")]
+    #[diagnostic(help(
+        "Rename one of the procedures, or give the routers different aliases so they are not merged"
+    ))]
+    DuplicateProcedure {
+        path: String,
+        first_router: String,
+        second_router: String,
+    },
+
+    #[error(
+        "'{first_path}' imports Zod from '{first_entry_point}', but '{second_path}' imports it from '{second_entry_point}'"
+    )]
+    #[diagnostic(help(
+        "Import Zod from one entry point across the app so `server.ts` binds every schema to the same Zod API"
+    ))]
+    MixedZodEntryPoints {
+        first_path: PathBuf,
+        first_entry_point: String,
+        second_path: PathBuf,
+        second_entry_point: String,
+    },
+
+    #[error(
+        "'{first_path}' imports `{name}` from '{first_module}', but '{second_path}' binds it to a different import from '{second_module}'"
+    )]
+    #[diagnostic(help(
+        "Import each validator binding the same way across the app, since `server.ts` declares it once"
+    ))]
+    ConflictingValidatorImports {
+        name: String,
+        first_path: PathBuf,
+        first_module: String,
+        second_path: PathBuf,
+        second_module: String,
+    },
+
+    #[error("'{path}' imports Zod from '{entry_point}', which the installed zod {version} doesn't provide")]
+    #[diagnostic(help("Upgrade zod to 3.25 or later, or import from 'zod'"))]
+    UnsupportedZodEntryPoint {
+        path: PathBuf,
+        entry_point: String,
+        version: String,
+    },
+
     #[error("Unresolved schema reference '{name}' in '{path}'. The variable was not found.")]
     #[diagnostic(help("Ensure the schema variable is defined and exported"))]
     UnresolvedSchema { path: PathBuf, name: String },
@@ -0,0 +1,107 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use swc_ecma_ast::{ModuleDecl, ModuleItem};
+
+use super::FlattenContext;
+use crate::parser::imports::module_path::normalize_path;
+use crate::parser::imports::{extract_import_paths, package_manifest};
+use crate::parser::procedure::find_class_by_name;
+use crate::{DeclarationType, ParsedFile, ResolvedImport, RouterMetadata};
+
+/// Files whose edits can change the generated output for `parsed_file`.
+///
+/// That is its direct imports (which covers barrels), the files declaring everything it imports,
+/// and everything reachable from the files declaring the constants, middlewares and base router
+/// classes it uses, along with the `package.json` of every package those files import.
+pub(super) fn collect_router_dependencies(
+    context: &FlattenContext<'_>,
+    parsed_file: &ParsedFile,
+    imports_map: &HashMap<String, ResolvedImport>,
+    routers: &[RouterMetadata],
+) -> HashSet<PathBuf> {
+    let mut dependencies: HashSet<PathBuf> = import_paths(parsed_file)
+        .into_iter()
+        .chain(
+            imports_map
+                .values()
+                .map(|resolved| normalize_path(&resolved.source_file)),
+        )
+        .collect();
+    dependencies.extend(package_manifests(parsed_file));
+
+    let middleware_files = routers
+        .iter()
+        .flat_map(|router| &router.procedures)
+        .flat_map(|procedure| &procedure.middlewares)
+        .filter_map(|name| imports_map.get(name));
+    let constant_files = imports_map.values().filter(|resolved| {
+        matches!(
+            resolved.declaration_type,
+            DeclarationType::Variable | DeclarationType::Enum | DeclarationType::Namespace
+        )
+    });
+    let base_class_files = routers
+        .iter()
+        .filter_map(|router| find_class_by_name(&parsed_file.module.body, &router.name))
+        .filter_map(|class| class.super_class.as_deref()?.unwrap_parens().as_ident())
+        .filter_map(|super_class| imports_map.get(super_class.sym.as_ref()));
+
+    let mut pending: Vec<PathBuf> = constant_files
+        .chain(middleware_files)
+        .chain(base_class_files)
+        .map(|resolved| normalize_path(&resolved.source_file))
+        .collect();
+    let mut expanded = HashSet::new();
+
+    while let Some(path) = pending.pop() {
+        let parsed = expanded
+            .insert(path.clone())
+            .then(|| {
+                context
+                    .parse_cache
+                    .get_or_parse(context.typescript_parser, &path)
+            })
+            .and_then(Result::ok);
+        if let Some(parsed) = parsed {
+            pending.extend(import_paths(&parsed));
+            dependencies.extend(package_manifests(&parsed));
+        }
+    }
+
+    dependencies.extend(expanded);
+    dependencies
+}
+
+/// Manifests of the installed packages `parsed_file` imports; their `exports`, `types` and `main`
+/// decide which source files those imports resolve to.
+fn package_manifests(parsed_file: &ParsedFile) -> Vec<PathBuf> {
+    let source_directory = parsed_file
+        .file_path
+        .parent()
+        .unwrap_or_else(|| Path::new(""));
+    parsed_file
+        .module
+        .body
+        .iter()
+        .filter_map(|item| match item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(import_declaration)) => {
+                Some(import_declaration.src.value.to_string_lossy())
+            }
+            _ => None,
+        })
+        .filter_map(|specifier| package_manifest(source_directory, &specifier))
+        .collect()
+}
+
+/// Normalized paths of the local files `parsed_file` imports or re-exports from.
+pub(super) fn import_paths(parsed_file: &ParsedFile) -> Vec<PathBuf> {
+    let source_directory = parsed_file
+        .file_path
+        .parent()
+        .unwrap_or_else(|| Path::new(""));
+    extract_import_paths(parsed_file, source_directory)
+        .iter()
+        .map(|path| normalize_path(path))
+        .collect()
+}
@@ -0,0 +1,348 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+use xxhash_rust::xxh3::xxh3_64;
+
+use super::GenerationOptions;
+use crate::parser::imports::module_path::normalize_path;
+use crate::tsconfig::tsconfig_files;
+use crate::{ResolvedImport, RouterMetadata};
+
+const CACHE_DIRECTORY: &str = "node_modules/.cache/nestjs-trpc";
+
+/// `node_modules/.cache/nestjs-trpc` in the closest ancestor of `base_directory` that has a
+/// `node_modules` directory, or in `base_directory` itself.
+#[must_use]
+pub fn default_cache_directory(base_directory: &Path) -> PathBuf {
+    base_directory
+        .ancestors()
+        .find(|directory| directory.join("node_modules").is_dir())
+        .unwrap_or(base_directory)
+        .join(CACHE_DIRECTORY)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheFile {
+    key: String,
+    files: BTreeMap<PathBuf, CachedRouterFile>,
+}
+
+/// Extraction and flattening results for one router file, valid while neither it nor any of its
+/// dependencies change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct CachedRouterFile {
+    content_hash: u64,
+    /// `None` records a dependency that did not exist, so creating it invalidates the entry.
+    dependency_hashes: BTreeMap<PathBuf, Option<u64>>,
+    importable_identifiers_hash: u64,
+    pub(super) imports_map: HashMap<String, ResolvedImport>,
+    pub(super) routers: Vec<RouterMetadata>,
+}
+
+impl CachedRouterFile {
+    pub(super) fn new(
+        file_path: &Path,
+        dependencies: &HashSet<PathBuf>,
+        importable_identifiers_hash: u64,
+        imports_map: HashMap<String, ResolvedImport>,
+        routers: Vec<RouterMetadata>,
+    ) -> Option<Self> {
+        Some(Self {
+            content_hash: hash_file(file_path)?,
+            dependency_hashes: dependencies
+                .iter()
+                .map(|dependency| (dependency.clone(), hash_file(dependency)))
+                .collect(),
+            importable_identifiers_hash,
+            imports_map,
+            routers,
+        })
+    }
+
+    /// Flattening inlines whatever `server.ts` cannot import, so results only carry over while
+    /// the importable identifiers are the same.
+    pub(super) const fn is_flattened_against(&self, importable_identifiers_hash: u64) -> bool {
+        self.importable_identifiers_hash == importable_identifiers_hash
+    }
+}
+
+/// Router extraction results persisted between CLI invocations.
+///
+/// Entries are keyed by router file and validated against content hashes of the file and its
+/// dependencies; the whole cache is discarded when the CLI version or output settings change.
+#[derive(Debug, Default)]
+pub(super) struct GenerationCache {
+    path: Option<PathBuf>,
+    key: String,
+    previous: BTreeMap<PathBuf, CachedRouterFile>,
+    current: BTreeMap<PathBuf, CachedRouterFile>,
+    file_hashes: HashMap<PathBuf, Option<u64>>,
+}
+
+impl GenerationCache {
+    /// Loads the cache for `options`; disabled when no cache directory is configured.
+    pub(super) fn load(options: &GenerationOptions) -> Self {
+        let Some(cache_directory) = options.cache_directory.as_deref() else {
+            return Self::default();
+        };
+
+        let key = cache_key(options);
+        let path = cache_directory.join(cache_file_name(options));
+        let previous = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<CacheFile>(&content).ok())
+            .filter(|cache_file| cache_file.key == key)
+            .map(|cache_file| cache_file.files)
+            .unwrap_or_default();
+
+        debug!(path = ?path, entries = previous.len(), "Loaded generation cache");
+
+        Self {
+            path: Some(path),
+            key,
+            previous,
+            current: BTreeMap::new(),
+            file_hashes: HashMap::new(),
+        }
+    }
+
+    pub(super) const fn is_enabled(&self) -> bool {
+        self.path.is_some()
+    }
+
+    /// Takes the entry for `file_path` if neither it nor any of its dependencies changed.
+    pub(super) fn take_fresh(&mut self, file_path: &Path) -> Option<CachedRouterFile> {
+        let entry = self.previous.remove(file_path)?;
+
+        let is_fresh = self.hash(file_path) == Some(entry.content_hash)
+            && entry
+                .dependency_hashes
+                .iter()
+                .all(|(dependency, hash)| self.hash(dependency) == *hash);
+
+        is_fresh.then_some(entry)
+    }
+
+    /// Keeps `entry` for the next run; entries not inserted again are dropped on save.
+    pub(super) fn insert(&mut self, file_path: PathBuf, entry: CachedRouterFile) {
+        if self.is_enabled() {
+            self.current.insert(file_path, entry);
+        }
+    }
+
+    /// Writes the cache, logging instead of failing since generation already succeeded.
+    pub(super) fn save(self) {
+        let Some(path) = self.path else {
+            return;
+        };
+
+        let cache_file = CacheFile {
+            key: self.key,
+            files: self.current,
+        };
+        if let Err(error) = write_cache_file(&path, &cache_file) {
+            warn!(path = ?path, "Failed to write generation cache: {error}");
+        }
+    }
+
+    fn hash(&mut self, path: &Path) -> Option<u64> {
+        *self
+            .file_hashes
+            .entry(path.to_path_buf())
+            .or_insert_with(|| hash_file(path))
+    }
+}
+
+/// Hash of the sorted identifier set, stable across runs.
+pub(super) fn hash_identifiers(identifiers: &HashSet<String>) -> u64 {
+    let mut sorted: Vec<&str> = identifiers.iter().map(String::as_str).collect();
+    sorted.sort_unstable();
+    xxh3_64(sorted.join("\n").as_bytes())
+}
+
+fn hash_file(path: &Path) -> Option<u64> {
+    fs::read(path).ok().map(|content| xxh3_64(&content))
+}
+
+/// Everything outside the router files that changes cached results or the files written from them.
+///
+/// That includes every `tsconfig.json` path aliases are loaded from, since aliases decide where
+/// imports resolve for all router files at once.
+fn cache_key(options: &GenerationOptions) -> String {
+    let tsconfig_hashes: Vec<(PathBuf, Option<u64>)> = tsconfig_files(&options.base_directory)
+        .into_iter()
+        .map(|path| {
+            let hash = hash_file(&path);
+            (path, hash)
+        })
+        .collect();
+    let tsconfig_hash = xxh3_64(format!("{tsconfig_hashes:?}").as_bytes());
+    format!(
+        "{}|import_extension={}|transformer={:?}|procedure_decorators={:?}|tsconfig={tsconfig_hash:?}",
+        env!("CARGO_PKG_VERSION"),
+        options.import_extension,
+        options.transformer,
+        options.procedure_decorators,
+    )
+}
+
+/// One cache file per project and router pattern, so workspaces sharing `node_modules` don't collide.
+fn cache_file_name(options: &GenerationOptions) -> String {
+    let scope = format!(
+        "{}|{}",
+        normalize_path(&options.base_directory).display(),
+        options.router_pattern
+    );
+    format!("{:016x}.json", xxh3_64(scope.as_bytes()))
+}
+
+fn write_cache_file(path: &Path, cache_file: &CacheFile) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    // Written next to the target and renamed into place so concurrent runs never read a partial file.
+    let temporary_path = path.with_extension(format!("{}.tmp", std::process::id()));
+    fs::write(&temporary_path, serde_json::to_vec(cache_file)?)?;
+    fs::rename(&temporary_path, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn options(base: &Path) -> GenerationOptions {
+        GenerationOptions::new(base, base.join("@generated"), "**/*.router.ts")
+            .with_cache_directory(Some(base.join("cache")))
+    }
+
+    fn entry(file_path: &Path, dependency: &Path) -> CachedRouterFile {
+        CachedRouterFile::new(
+            file_path,
+            &HashSet::from([dependency.to_path_buf()]),
+            0,
+            HashMap::new(),
+            Vec::new(),
+        )
+        .unwrap()
+    }
+
+    fn save_and_reload(base: &Path, file_path: &Path, dependency: &Path) -> GenerationCache {
+        let mut cache = GenerationCache::load(&options(base));
+        cache.insert(file_path.to_path_buf(), entry(file_path, dependency));
+        cache.save();
+        GenerationCache::load(&options(base))
+    }
+
+    #[test]
+    fn test_default_directory_uses_nearest_node_modules() {
+        let temporary_directory = TempDir::new().unwrap();
+        let root = temporary_directory.path();
+        fs::create_dir_all(root.join("node_modules")).unwrap();
+        fs::create_dir_all(root.join("apps/api")).unwrap();
+
+        assert_eq!(
+            default_cache_directory(&root.join("apps/api")),
+            root.join("node_modules/.cache/nestjs-trpc")
+        );
+    }
+
+    #[test]
+    fn test_unchanged_entry_is_reused() {
+        let temporary_directory = TempDir::new().unwrap();
+        let base = temporary_directory.path();
+        let router = base.join("users.router.ts");
+        let schema = base.join("user.schema.ts");
+        fs::write(&router, "export class UsersRouter {}").unwrap();
+        fs::write(&schema, "export const userSchema = 1;").unwrap();
+
+        let mut cache = save_and_reload(base, &router, &schema);
+
+        assert!(cache.take_fresh(&router).is_some());
+    }
+
+    #[test]
+    fn test_changed_dependency_invalidates_entry() {
+        let temporary_directory = TempDir::new().unwrap();
+        let base = temporary_directory.path();
+        let router = base.join("users.router.ts");
+        let schema = base.join("user.schema.ts");
+        fs::write(&router, "export class UsersRouter {}").unwrap();
+        fs::write(&schema, "export const userSchema = 1;").unwrap();
+
+        let mut cache = save_and_reload(base, &router, &schema);
+        fs::write(&schema, "export const userSchema = 2;").unwrap();
+
+        assert!(cache.take_fresh(&router).is_none());
+    }
+
+    #[test]
+    fn test_created_dependency_invalidates_entry() {
+        let temporary_directory = TempDir::new().unwrap();
+        let base = temporary_directory.path();
+        let router = base.join("users.router.ts");
+        let missing = base.join("index.ts");
+        fs::write(&router, "export class UsersRouter {}").unwrap();
+
+        let mut cache = save_and_reload(base, &router, &missing);
+        fs::write(&missing, "export {};").unwrap();
+
+        assert!(cache.take_fresh(&router).is_none());
+    }
+
+    #[test]
+    fn test_changed_settings_discard_cache() {
+        let temporary_directory = TempDir::new().unwrap();
+        let base = temporary_directory.path();
+        let router = base.join("users.router.ts");
+        fs::write(&router, "export class UsersRouter {}").unwrap();
+
+        save_and_reload(base, &router, &router);
+        let mut cache = GenerationCache::load(&options(base).with_import_extension(true));
+
+        assert!(cache.take_fresh(&router).is_none());
+    }
+
+    #[test]
+    fn test_changed_extended_tsconfig_discards_cache() {
+        let temporary_directory = TempDir::new().unwrap();
+        let root = temporary_directory.path();
+        let base = root.join("apps/api");
+        let router = base.join("users.router.ts");
+        fs::create_dir_all(&base).unwrap();
+        fs::write(&router, "export class UsersRouter {}").unwrap();
+        fs::write(
+            base.join("tsconfig.json"),
+            r#"{ "extends": "../../tsconfig.base.json" }"#,
+        )
+        .unwrap();
+        fs::write(root.join("tsconfig.base.json"), "{}").unwrap();
+
+        save_and_reload(&base, &router, &router);
+        fs::write(
+            root.join("tsconfig.base.json"),
+            r#"{ "compilerOptions": { "paths": { "@shared/*": ["libs/shared/*"] } } }"#,
+        )
+        .unwrap();
+        let mut cache = GenerationCache::load(&options(&base));
+
+        assert!(cache.take_fresh(&router).is_none());
+    }
+
+    #[test]
+    fn test_disabled_cache_writes_nothing() {
+        let temporary_directory = TempDir::new().unwrap();
+        let base = temporary_directory.path();
+        let options = options(base).with_cache_directory(None);
+
+        let cache = GenerationCache::load(&options);
+        assert!(!cache.is_enabled());
+        cache.save();
+
+        assert!(!base.join("cache").exists());
+    }
+}
@@ -0,0 +1,635 @@
+//! Names the enums and schemas hoisted into `server.ts` while flattening schemas.
+//!
+//! Workers flatten routers independently, so each hoisted declaration is first referred to by a
+//! placeholder unique to its source file. Once every router is flattened, schemas used only once
+//! are inlined back, and each remaining placeholder is given the declaration's own name, or
+//! `{name}_{n}` when that name is already taken by another hoisted declaration or by something
+//! `server.ts` declares or imports. Routers and procedures are visited in source order, so names
+//! stay the same from one run to the next.
+//!
+//! TypeScript can't infer the type of a schema that refers to itself, so recursive schemas are
+//! declared with a type alias built from the flattened schema: `type Category = { ... }` and
+//! `const categorySchema: z.ZodType<Category> = ...`. Schemas that accept something other than
+//! they return, through `.default()` or `.transform()`, also get a `CategoryInput` alias.
+
+use super::schema_type::{SchemaTypes, Side};
+use crate::generator::server::{procedure_builder_name, SUBSCRIPTION_EVENT_TYPE};
+use crate::parser::schema::validator::expression_root;
+use crate::parser::schema::{count_identifier, replace_identifier};
+use crate::parser::{parse_typescript_source, ParsedFile};
+use crate::{
+    HoistedDeclaration, HoistedKind, InputType, ProcedureMetadata, RouterMetadata, SchemaType,
+    ValidatorImport, ZodVersion,
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+use swc_ecma_ast::Expr;
+use tracing::warn;
+
+/// Names `server.ts` declares itself, whatever the routers contain.
+const GENERATED_NAMES: [&str; 6] = [
+    "t",
+    "initTRPC",
+    "publicProcedure",
+    "appRouter",
+    "AppRouter",
+    SUBSCRIPTION_EVENT_TYPE,
+];
+
+pub(super) fn name_hoisted_declarations(
+    routers: &mut [RouterMetadata],
+    schema_locations: &HashMap<String, PathBuf>,
+    validator_imports: &[ValidatorImport],
+    installed_zod: Option<ZodVersion>,
+    transformer_name: Option<&str>,
+) {
+    inline_unshared_schemas(routers);
+
+    let mut taken = reserved_names(routers, schema_locations, validator_imports);
+    taken.extend(transformer_name.map(str::to_string));
+
+    let mut names: BTreeMap<String, String> = BTreeMap::new();
+    for declaration in routers
+        .iter()
+        .flat_map(|router| &router.procedures)
+        .flat_map(|procedure| &procedure.hoisted_declarations)
+    {
+        if names.contains_key(&declaration.placeholder) {
+            continue;
+        }
+        let name = available_name(&declaration.name, &taken);
+        taken.insert(name.clone());
+        names.insert(declaration.placeholder.clone(), name);
+    }
+
+    if names.is_empty() {
+        return;
+    }
+
+    for procedure in routers.iter_mut().flat_map(|router| &mut router.procedures) {
+        rename_placeholders(procedure, &names);
+    }
+    annotate_recursive_schemas(routers, validator_imports, installed_zod, &mut taken);
+}
+
+/// Puts schemas referenced only once back in place, since a declaration wouldn't save anything.
+fn inline_unshared_schemas(routers: &mut [RouterMetadata]) {
+    let mut uses: HashMap<String, usize> = HashMap::new();
+    for procedure in routers.iter().flat_map(|router| &router.procedures) {
+        for declaration in &procedure.hoisted_declarations {
+            let count = schema_texts(procedure)
+                .map(|text| count_identifier(text, &declaration.placeholder))
+                .sum::<usize>();
+            *uses.entry(declaration.placeholder.clone()).or_default() += count;
+        }
+    }
+
+    for procedure in routers.iter_mut().flat_map(|router| &mut router.procedures) {
+        let (unshared, kept) = std::mem::take(&mut procedure.hoisted_declarations)
+            .into_iter()
+            .partition::<Vec<_>, _>(|declaration| {
+                declaration.kind == HoistedKind::Schema && uses[&declaration.placeholder] < 2
+            });
+        procedure.hoisted_declarations = kept;
+
+        for declaration in unshared {
+            inline_schema(procedure, &declaration);
+        }
+    }
+}
+
+fn inline_schema(procedure: &mut ProcedureMetadata, declaration: &HoistedDeclaration) {
+    let texts = [
+        &mut procedure.input_schema,
+        &mut procedure.output_schema,
+        &mut procedure.meta,
+    ];
+    for text in texts.into_iter().flatten() {
+        replace_identifier_in(text, &declaration.placeholder, &declaration.declaration);
+    }
+    procedure
+        .schema_identifiers
+        .retain(|identifier| *identifier != declaration.placeholder);
+}
+
+fn schema_texts(procedure: &ProcedureMetadata) -> impl Iterator<Item = &String> {
+    [
+        &procedure.input_schema,
+        &procedure.output_schema,
+        &procedure.meta,
+    ]
+    .into_iter()
+    .flatten()
+}
+
+fn reserved_names(
+    routers: &[RouterMetadata],
+    schema_locations: &HashMap<String, PathBuf>,
+    validator_imports: &[ValidatorImport],
+) -> HashSet<String> {
+    let procedures = routers.iter().flat_map(|router| &router.procedures);
+
+    let imported_schemas = procedures
+        .clone()
+        .flat_map(|procedure| {
+            [&procedure.input_schema_ref, &procedure.output_schema_ref]
+                .into_iter()
+                .flatten()
+                .chain(&procedure.schema_identifiers)
+        })
+        .filter(|name| schema_locations.contains_key(name.as_str()))
+        .cloned();
+    let router_classes = procedures
+        .clone()
+        .filter_map(|procedure| procedure.output_inference.as_ref())
+        .map(|inference| inference.router_class_name.clone());
+    let procedure_builders = procedures
+        .filter(|procedure| !procedure.middlewares.is_empty())
+        .map(|procedure| procedure_builder_name(&procedure.middlewares));
+
+    GENERATED_NAMES
+        .iter()
+        .map(ToString::to_string)
+        .chain(imported_schemas)
+        .chain(router_classes)
+        .chain(procedure_builders)
+        .chain(
+            validator_imports
+                .iter()
+                .map(|import| import.local_name.clone()),
+        )
+        .collect()
+}
+
+fn available_name(name: &str, taken: &HashSet<String>) -> String {
+    if !taken.contains(name) {
+        return name.to_string();
+    }
+    let mut suffix = 2;
+    loop {
+        let candidate = format!("{name}_{suffix}");
+        if !taken.contains(&candidate) {
+            return candidate;
+        }
+        suffix += 1;
+    }
+}
+
+fn annotate_recursive_schemas(
+    routers: &mut [RouterMetadata],
+    validator_imports: &[ValidatorImport],
+    installed_zod: Option<ZodVersion>,
+    taken: &mut HashSet<String>,
+) {
+    let mut recursive: Vec<(String, String)> = Vec::new();
+    for declaration in routers
+        .iter()
+        .flat_map(|router| &router.procedures)
+        .flat_map(|procedure| &procedure.hoisted_declarations)
+        .filter(|declaration| declaration.kind == HoistedKind::RecursiveSchema)
+    {
+        if recursive.iter().all(|(name, _)| *name != declaration.name) {
+            recursive.push((declaration.name.clone(), declaration.declaration.clone()));
+        }
+    }
+    if recursive.is_empty() {
+        return;
+    }
+
+    let mut aliases: HashMap<String, String> = HashMap::new();
+    for (name, _) in &recursive {
+        let alias = available_name(&type_alias_name(name), taken);
+        taken.insert(alias.clone());
+        aliases.insert(name.clone(), alias);
+    }
+
+    let schemas: Vec<RecursiveSchema> = recursive
+        .iter()
+        .filter_map(|(name, schema)| RecursiveSchema::parse(name, schema, validator_imports))
+        .collect();
+    let input_aliases = input_aliases(&schemas, &aliases, taken);
+    let schema_types: HashMap<&str, SchemaType> = schemas
+        .iter()
+        .filter_map(|schema| {
+            let schema_type = schema.schema_type(&aliases, &input_aliases, installed_zod)?;
+            Some((schema.name.as_str(), schema_type))
+        })
+        .collect();
+
+    for declaration in routers
+        .iter_mut()
+        .flat_map(|router| &mut router.procedures)
+        .flat_map(|procedure| &mut procedure.hoisted_declarations)
+    {
+        declaration.schema_type = schema_types.get(declaration.name.as_str()).cloned();
+    }
+}
+
+/// The alias each recursive schema's input type is declared with: `{alias}Input` when it differs
+/// from the output type, which it also does when it refers to a schema whose input differs, and
+/// the output alias otherwise.
+fn input_aliases(
+    schemas: &[RecursiveSchema],
+    aliases: &HashMap<String, String>,
+    taken: &mut HashSet<String>,
+) -> HashMap<String, String> {
+    let output_types: HashMap<&str, String> = schemas
+        .iter()
+        .map(|schema| (schema.name.as_str(), schema.type_of(Side::Output, aliases)))
+        .collect();
+    let mut input_aliases = aliases.clone();
+    loop {
+        let differing: Vec<&str> = schemas
+            .iter()
+            .filter(|schema| input_aliases[&schema.name] == aliases[&schema.name])
+            .filter(|schema| {
+                schema.type_of(Side::Input, &input_aliases) != output_types[schema.name.as_str()]
+            })
+            .map(|schema| schema.name.as_str())
+            .collect();
+        if differing.is_empty() {
+            return input_aliases;
+        }
+        for name in differing {
+            let alias = available_name(&format!("{}Input", aliases[name]), taken);
+            taken.insert(alias.clone());
+            input_aliases.insert(name.to_string(), alias);
+        }
+    }
+}
+
+/// A recursive schema built with Zod's namespace, whose `ZodType` it is annotated with.
+struct RecursiveSchema {
+    name: String,
+    parsed_file: ParsedFile,
+    validator_import: ValidatorImport,
+}
+
+impl RecursiveSchema {
+    fn parse(name: &str, schema: &str, validator_imports: &[ValidatorImport]) -> Option<Self> {
+        let parsed_file = parse_typescript_source(&format!("({schema});")).ok()?;
+        let root = expression_root(schema_expression(&parsed_file)?)?;
+        let validator_import = validator_imports
+            .iter()
+            .find(|validator_import| validator_import.local_name == root)
+            .cloned()
+            .or_else(|| (root == "z").then(ValidatorImport::zod))?;
+        Some(Self {
+            name: name.to_string(),
+            parsed_file,
+            validator_import,
+        })
+    }
+
+    fn type_of(&self, side: Side, aliases: &HashMap<String, String>) -> String {
+        let Some(expression) = schema_expression(&self.parsed_file) else {
+            return "unknown".to_string();
+        };
+        let zod = &self.validator_import.local_name;
+        SchemaTypes::new(&self.parsed_file, zod, aliases, side).type_of(expression)
+    }
+
+    /// `None` when the binding isn't Zod's namespace, or when the input type differs and the
+    /// installed Zod release, which decides where it goes in the annotation, isn't known.
+    fn schema_type(
+        &self,
+        aliases: &HashMap<String, String>,
+        input_aliases: &HashMap<String, String>,
+        installed_zod: Option<ZodVersion>,
+    ) -> Option<SchemaType> {
+        let name = &aliases[&self.name];
+        let input_name = &input_aliases[&self.name];
+        let input = (input_name != name).then(|| InputType {
+            name: input_name.clone(),
+            definition: self.type_of(Side::Input, input_aliases),
+        });
+
+        let input_type = input.as_ref().map(|input| input.name.as_str());
+        let annotation = self
+            .validator_import
+            .zod_schema_type(name, input_type, installed_zod);
+        let is_namespace = self
+            .validator_import
+            .zod_schema_type(name, None, None)
+            .is_some();
+        if annotation.is_none() && input.is_some() && is_namespace {
+            warn!(
+                schema = %self.name,
+                "Declaring a recursive schema without a type, since its input type differs \
+                 from its output and the installed Zod version is unknown"
+            );
+        }
+        Some(SchemaType {
+            name: name.clone(),
+            annotation: annotation?,
+            definition: self.type_of(Side::Output, aliases),
+            input,
+        })
+    }
+}
+
+fn schema_expression(parsed_file: &ParsedFile) -> Option<&Expr> {
+    Some(&parsed_file.module.body.first()?.as_stmt()?.as_expr()?.expr)
+}
+
+/// `categorySchema_flat` becomes `Category`.
+fn type_alias_name(schema_name: &str) -> String {
+    let name = schema_name.strip_suffix("_flat").unwrap_or(schema_name);
+    let name = name
+        .strip_suffix("Schema")
+        .filter(|stem| !stem.is_empty())
+        .unwrap_or(name);
+    let mut characters = name.chars();
+    characters.next().map_or_else(String::new, |first| {
+        first.to_uppercase().chain(characters).collect()
+    })
+}
+
+/// Placeholders are replaced as whole identifiers, in the map's order, so runs are reproducible.
+fn rename_placeholders(procedure: &mut ProcedureMetadata, names: &BTreeMap<String, String>) {
+    let rename = |text: &mut String| {
+        for (placeholder, name) in names {
+            replace_identifier_in(text, placeholder, name);
+        }
+    };
+
+    let texts = [
+        &mut procedure.input_schema,
+        &mut procedure.output_schema,
+        &mut procedure.meta,
+    ];
+    texts.into_iter().flatten().for_each(rename);
+    for declaration in &mut procedure.hoisted_declarations {
+        rename(&mut declaration.declaration);
+        if let Some(name) = names.get(&declaration.placeholder) {
+            declaration.name.clone_from(name);
+        }
+    }
+    procedure
+        .schema_identifiers
+        .retain(|identifier| !names.contains_key(identifier));
+}
+
+fn replace_identifier_in(text: &mut String, identifier: &str, replacement: &str) {
+    if let Some(replaced) = replace_identifier(text, identifier, replacement) {
+        *text = replaced;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProcedureType;
+    use std::collections::BTreeSet;
+
+    fn hoisted(name: &str, file: &str) -> HoistedDeclaration {
+        let placeholder = format!("$hoisted${name}${file}");
+        HoistedDeclaration {
+            name: name.to_string(),
+            source_file: PathBuf::from(file),
+            declaration: format!("enum {placeholder} {{ A = \"a\" }}"),
+            placeholder,
+            kind: HoistedKind::Enum,
+            schema_type: None,
+        }
+    }
+
+    fn procedure(input: &str, hoisted_declarations: Vec<HoistedDeclaration>) -> ProcedureMetadata {
+        ProcedureMetadata {
+            name: "create".to_string(),
+            procedure_type: ProcedureType::Mutation,
+            input_schema: Some(input.to_string()),
+            output_schema: None,
+            input_schema_ref: None,
+            output_schema_ref: None,
+            schema_identifiers: vec![input.to_string()],
+            meta: None,
+            middlewares: Vec::new(),
+            output_inference: None,
+            hoisted_declarations,
+            schema_sources: BTreeSet::new(),
+        }
+    }
+
+    fn router(procedures: Vec<ProcedureMetadata>) -> RouterMetadata {
+        RouterMetadata {
+            name: "UserRouter".to_string(),
+            alias: None,
+            file_path: PathBuf::from("user.router.ts"),
+            procedures,
+        }
+    }
+
+    #[test]
+    fn test_same_named_enums_from_different_files_get_distinct_names() {
+        let first = hoisted("Role", "a.ts");
+        let second = hoisted("Role", "b.ts");
+        let mut routers = vec![router(vec![
+            procedure(&first.placeholder.clone(), vec![first]),
+            procedure(&second.placeholder.clone(), vec![second]),
+        ])];
+
+        name_hoisted_declarations(&mut routers, &HashMap::new(), &[], None, None);
+
+        let procedures = &routers[0].procedures;
+        assert_eq!(procedures[0].input_schema.as_deref(), Some("Role"));
+        assert_eq!(procedures[1].input_schema.as_deref(), Some("Role_2"));
+        assert_eq!(
+            procedures[1].hoisted_declarations[0].declaration,
+            "enum Role_2 { A = \"a\" }"
+        );
+        assert!(procedures[1].schema_identifiers.is_empty());
+    }
+
+    #[test]
+    fn test_placeholders_are_renamed_as_whole_identifiers() {
+        let first = hoisted("Role", "a");
+        let second = hoisted("Role", "ab");
+        let input = format!("z.tuple([{}, {}])", first.placeholder, second.placeholder);
+        let mut routers = vec![router(vec![procedure(&input, vec![first, second])])];
+
+        name_hoisted_declarations(&mut routers, &HashMap::new(), &[], None, None);
+
+        let procedure = &routers[0].procedures[0];
+        assert_eq!(
+            procedure.input_schema.as_deref(),
+            Some("z.tuple([Role, Role_2])")
+        );
+    }
+
+    #[test]
+    fn test_hoisted_enums_avoid_imported_and_generated_names() {
+        let role = hoisted("Role", "a.ts");
+        let app_router = hoisted("appRouter", "a.ts");
+        let mut imported = procedure("Role", Vec::new());
+        imported.input_schema_ref = Some("Role".to_string());
+        let mut routers = vec![router(vec![
+            imported,
+            procedure(&role.placeholder.clone(), vec![role]),
+            procedure(&app_router.placeholder.clone(), vec![app_router]),
+        ])];
+        let schema_locations = HashMap::from([("Role".to_string(), PathBuf::from("role.ts"))]);
+
+        name_hoisted_declarations(&mut routers, &schema_locations, &[], None, None);
+
+        let procedures = &routers[0].procedures;
+        assert_eq!(procedures[1].input_schema.as_deref(), Some("Role_2"));
+        assert_eq!(procedures[2].input_schema.as_deref(), Some("appRouter_2"));
+    }
+
+    #[test]
+    fn test_schemas_used_once_are_inlined_and_shared_ones_named() {
+        let shared = HoistedDeclaration {
+            name: "userSchema_flat".to_string(),
+            source_file: PathBuf::from("user.ts"),
+            placeholder: "$hoisted$userSchema_flat$a".to_string(),
+            declaration: "z.object({ id: z.string() })".to_string(),
+            kind: HoistedKind::Schema,
+            schema_type: None,
+        };
+        let single = HoistedDeclaration {
+            name: "auditSchema_flat".to_string(),
+            placeholder: "$hoisted$auditSchema_flat$a".to_string(),
+            declaration: "z.object({ at: z.date() })".to_string(),
+            ..shared.clone()
+        };
+        let mut first = procedure("z.array($hoisted$userSchema_flat$a)", vec![shared.clone()]);
+        first.output_schema = Some("$hoisted$auditSchema_flat$a".to_string());
+        first.hoisted_declarations.push(single);
+        let second = procedure("$hoisted$userSchema_flat$a", vec![shared]);
+        let mut routers = vec![router(vec![first, second])];
+
+        name_hoisted_declarations(&mut routers, &HashMap::new(), &[], None, None);
+
+        let procedures = &routers[0].procedures;
+        assert_eq!(
+            procedures[0].input_schema.as_deref(),
+            Some("z.array(userSchema_flat)")
+        );
+        assert_eq!(
+            procedures[0].output_schema.as_deref(),
+            Some("z.object({ at: z.date() })")
+        );
+        assert_eq!(procedures[0].hoisted_declarations.len(), 1);
+        assert_eq!(
+            procedures[1].hoisted_declarations[0].name,
+            "userSchema_flat"
+        );
+    }
+
+    #[test]
+    fn test_schema_uses_are_counted_as_whole_identifiers() {
+        let single = HoistedDeclaration {
+            name: "userSchema_flat".to_string(),
+            source_file: PathBuf::from("a.ts"),
+            placeholder: "$hoisted$userSchema_flat$a".to_string(),
+            declaration: "z.object({ id: z.string() })".to_string(),
+            kind: HoistedKind::Schema,
+            schema_type: None,
+        };
+        let shared = HoistedDeclaration {
+            source_file: PathBuf::from("ab.ts"),
+            placeholder: "$hoisted$userSchema_flat$ab".to_string(),
+            declaration: "z.object({ name: z.string() })".to_string(),
+            ..single.clone()
+        };
+        let mut routers = vec![router(vec![procedure(
+            "z.tuple([$hoisted$userSchema_flat$a, $hoisted$userSchema_flat$ab, $hoisted$userSchema_flat$ab])",
+            vec![single, shared],
+        )])];
+
+        name_hoisted_declarations(&mut routers, &HashMap::new(), &[], None, None);
+
+        let procedure = &routers[0].procedures[0];
+        assert_eq!(
+            procedure.input_schema.as_deref(),
+            Some("z.tuple([z.object({ id: z.string() }), userSchema_flat, userSchema_flat])")
+        );
+        assert_eq!(procedure.hoisted_declarations.len(), 1);
+    }
+
+    #[test]
+    fn test_recursive_schemas_are_kept_and_typed() {
+        let recursive = HoistedDeclaration {
+            name: "treeSchema_flat".to_string(),
+            source_file: PathBuf::from("tree.ts"),
+            placeholder: "$hoisted$treeSchema_flat$a".to_string(),
+            declaration:
+                "z.object({ children: z.lazy(() => z.array($hoisted$treeSchema_flat$a)) })"
+                    .to_string(),
+            kind: HoistedKind::RecursiveSchema,
+            schema_type: None,
+        };
+        let mut routers = vec![router(vec![procedure(
+            "$hoisted$treeSchema_flat$a",
+            vec![recursive],
+        )])];
+
+        name_hoisted_declarations(
+            &mut routers,
+            &HashMap::new(),
+            &[ValidatorImport::zod()],
+            None,
+            None,
+        );
+
+        let declaration = &routers[0].procedures[0].hoisted_declarations[0];
+        assert_eq!(
+            declaration.declaration,
+            "z.object({ children: z.lazy(() => z.array(treeSchema_flat)) })"
+        );
+        assert_eq!(
+            declaration.schema_type,
+            Some(SchemaType {
+                name: "Tree".to_string(),
+                definition: "{ children: Array<Tree> }".to_string(),
+                input: None,
+                annotation: "z.ZodType<Tree>".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_recursive_schemas_with_defaults_are_typed_with_their_input() {
+        let recursive = HoistedDeclaration {
+            name: "treeSchema_flat".to_string(),
+            source_file: PathBuf::from("tree.ts"),
+            placeholder: "$hoisted$treeSchema_flat$a".to_string(),
+            declaration: "z.object({ label: z.string().default('root'), children: z.lazy(() => z.array($hoisted$treeSchema_flat$a)) })"
+                .to_string(),
+            kind: HoistedKind::RecursiveSchema,
+            schema_type: None,
+        };
+        let mut routers = vec![router(vec![procedure(
+            "$hoisted$treeSchema_flat$a",
+            vec![recursive],
+        )])];
+
+        name_hoisted_declarations(
+            &mut routers,
+            &HashMap::new(),
+            &[ValidatorImport::zod()],
+            Some(ZodVersion {
+                major: 3,
+                minor: 23,
+            }),
+            None,
+        );
+
+        let declaration = &routers[0].procedures[0].hoisted_declarations[0];
+        assert_eq!(
+            declaration.schema_type,
+            Some(SchemaType {
+                name: "Tree".to_string(),
+                definition: "{ label: Exclude<string, undefined>; children: Array<Tree> }"
+                    .to_string(),
+                input: Some(InputType {
+                    name: "TreeInput".to_string(),
+                    definition: "{ label?: string | undefined; children: Array<TreeInput> }"
+                        .to_string(),
+                }),
+                annotation: "z.ZodType<Tree, z.ZodTypeDef, TreeInput>".to_string(),
+            })
+        );
+    }
+}
@@ -0,0 +1,625 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+
+use anyhow::Result;
+use rayon::prelude::*;
+use tracing::{debug, info};
+
+use super::dependencies::{collect_router_dependencies, import_paths};
+use super::{
+    build_file_imports_map, build_imports_maps, check_untyped_outputs, ensure_routers_found,
+    extract_router_file, generation_result, merge_schema_locations, merge_validator_imports,
+    parse_router_files, remove_stale_files, render_outputs, scan_router_files, stale_output_files,
+    write_generated_files, FlattenContext, GenerationOptions, GenerationResult,
+};
+use crate::parser::cache::ParseCache;
+use crate::parser::imports::module_path::normalize_path;
+use crate::{
+    DecoratorParser, FileScanner, ParsedFile, ResolvedImport, RouterMetadata, RouterTree, TsParser,
+};
+
+/// Generation state kept between watch-mode rebuilds.
+///
+/// A dependency graph maps every router file to the schema, barrel and middleware files it
+/// imports, so a change only re-parses and re-flattens the routers it can affect.
+#[derive(Debug)]
+pub struct IncrementalGeneration {
+    options: GenerationOptions,
+    typescript_parser: TsParser,
+    parse_cache: ParseCache,
+    parsed_files: Vec<ParsedFile>,
+    imports_maps: Vec<HashMap<String, ResolvedImport>>,
+    routers: Vec<Vec<RouterMetadata>>,
+    /// Router files whose last extraction failed; retried on every update until they succeed.
+    pending: BTreeSet<usize>,
+    router_indices: HashMap<PathBuf, usize>,
+    importable_identifiers: HashSet<String>,
+    graph: DependencyGraph,
+    module_dependencies: HashSet<PathBuf>,
+}
+
+/// A router file's routers and the files they depend on.
+type ExtractedRouterFile = (Vec<RouterMetadata>, HashSet<PathBuf>);
+
+/// What a batch of changed paths touches, by router file index.
+#[derive(Debug, Default)]
+struct ChangeSet {
+    modified_routers: BTreeSet<usize>,
+    dependent_routers: BTreeSet<usize>,
+    module_changed: bool,
+}
+
+impl ChangeSet {
+    fn is_empty(&self) -> bool {
+        self.modified_routers.is_empty()
+            && self.dependent_routers.is_empty()
+            && !self.module_changed
+    }
+}
+
+impl IncrementalGeneration {
+    #[must_use]
+    pub fn new(options: GenerationOptions) -> Self {
+        Self {
+            options,
+            typescript_parser: TsParser::new(),
+            parse_cache: ParseCache::new(),
+            parsed_files: Vec::new(),
+            imports_maps: Vec::new(),
+            routers: Vec::new(),
+            pending: BTreeSet::new(),
+            router_indices: HashMap::new(),
+            importable_identifiers: HashSet::new(),
+            graph: DependencyGraph::default(),
+            module_dependencies: HashSet::new(),
+        }
+    }
+
+    /// Scans, parses and generates every router from scratch, rebuilding the dependency graph.
+    pub fn rebuild(&mut self) -> Result<GenerationResult> {
+        let start_time = Instant::now();
+        *self = Self::new(self.options.clone());
+
+        let router_files =
+            scan_router_files(&self.options.base_directory, &self.options.router_pattern)?;
+        let (typescript_parser, parsed_files) = parse_router_files(&router_files)?;
+
+        self.typescript_parser = typescript_parser;
+        self.imports_maps = build_imports_maps(
+            &self.typescript_parser,
+            &self.parse_cache,
+            &parsed_files,
+            &self.options.base_directory,
+        );
+        self.router_indices = parsed_files
+            .iter()
+            .enumerate()
+            .map(|(index, parsed_file)| (normalize_path(&parsed_file.file_path), index))
+            .collect();
+        self.routers = vec![Vec::new(); parsed_files.len()];
+        self.parsed_files = parsed_files;
+        self.module_dependencies = self.collect_module_dependencies();
+
+        let all_files = (0..self.parsed_files.len()).collect();
+        self.regenerate(&all_files, start_time)
+    }
+
+    /// Regenerates after `changed_paths` were modified; `None` when no output depends on them.
+    ///
+    /// Added or removed router files fall back to a full [`Self::rebuild`].
+    pub fn update(&mut self, changed_paths: &[PathBuf]) -> Result<Option<GenerationResult>> {
+        if self.parsed_files.is_empty() {
+            return self.rebuild().map(Some);
+        }
+
+        let start_time = Instant::now();
+        for path in changed_paths {
+            self.parse_cache.invalidate(path);
+        }
+
+        let Some(changes) = self.classify_changes(changed_paths)? else {
+            info!("Router files added or removed, rebuilding");
+            return self.rebuild().map(Some);
+        };
+
+        if changes.is_empty() {
+            debug!(paths = ?changed_paths, "No generated output depends on the changed files");
+            return Ok(None);
+        }
+
+        let reparsed: Result<Vec<(usize, ParsedFile)>, _> = changes
+            .modified_routers
+            .iter()
+            .map(|&index| {
+                let path = &self.parsed_files[index].file_path;
+                self.typescript_parser
+                    .parse_file(path)
+                    .map(|parsed_file| (index, parsed_file))
+            })
+            .collect();
+        let Ok(reparsed) = reparsed else {
+            // A full rebuild reports the syntax error and skips the broken file.
+            return self.rebuild().map(Some);
+        };
+        for (index, parsed_file) in reparsed {
+            self.parsed_files[index] = parsed_file;
+        }
+
+        // Routers that failed to extract keep their error until they are extracted successfully.
+        let affected: BTreeSet<usize> = changes
+            .modified_routers
+            .iter()
+            .chain(&changes.dependent_routers)
+            .chain(&self.pending)
+            .copied()
+            .collect();
+
+        let imports_maps: Vec<(usize, HashMap<String, ResolvedImport>)> = affected
+            .par_iter()
+            .map(|&index| {
+                let imports_map = build_file_imports_map(
+                    &self.typescript_parser,
+                    &self.parse_cache,
+                    &self.parsed_files[index],
+                    &self.options.base_directory,
+                );
+                (index, imports_map)
+            })
+            .collect();
+        for (index, imports_map) in imports_maps {
+            self.imports_maps[index] = imports_map;
+        }
+
+        if changes.module_changed {
+            self.module_dependencies = self.collect_module_dependencies();
+        }
+
+        info!(
+            modified = changes.modified_routers.len(),
+            dependents = changes.dependent_routers.len(),
+            "Regenerating affected routers"
+        );
+
+        self.regenerate(&affected, start_time).map(Some)
+    }
+
+    /// Returns `None` when the set of router files itself changed.
+    fn classify_changes(&self, changed_paths: &[PathBuf]) -> Result<Option<ChangeSet>> {
+        let scanner = FileScanner::new(&self.options.base_directory)?;
+        let paths: Vec<PathBuf> = changed_paths
+            .iter()
+            .map(|path| normalize_path(path))
+            .collect();
+
+        if paths
+            .iter()
+            .any(|path| self.changes_router_set(&scanner, path))
+        {
+            return Ok(None);
+        }
+
+        let mut changes = ChangeSet::default();
+        for path in &paths {
+            changes
+                .modified_routers
+                .extend(self.router_indices.get(path).copied());
+            changes.dependent_routers.extend(
+                self.graph
+                    .dependents_of(path)
+                    .filter_map(|router_file| self.router_indices.get(router_file).copied()),
+            );
+            changes.module_changed |= self.module_dependencies.contains(path);
+        }
+
+        Ok(Some(changes))
+    }
+
+    /// Whether `path` is a router file that was deleted, or a new file matching the router pattern.
+    fn changes_router_set(&self, scanner: &FileScanner, path: &Path) -> bool {
+        let exists = path.exists();
+        if self.router_indices.contains_key(path) {
+            return !exists;
+        }
+        // The pattern already scanned successfully during the last rebuild.
+        exists
+            && scanner
+                .matches(path, &self.options.router_pattern)
+                .unwrap_or(false)
+    }
+
+    /// Re-extracts and re-flattens `affected` router files, then rewrites any output that changed.
+    fn regenerate(
+        &mut self,
+        affected: &BTreeSet<usize>,
+        start_time: Instant,
+    ) -> Result<GenerationResult> {
+        let base_directory = self.options.base_directory.as_path();
+        let schema_locations =
+            merge_schema_locations(&self.parsed_files, &self.imports_maps, base_directory);
+        let flatten_context = FlattenContext::new(
+            &self.typescript_parser,
+            &self.parse_cache,
+            base_directory,
+            &schema_locations,
+        );
+
+        // Importable identifiers decide what gets inlined, so changing them invalidates every router.
+        let targets: Vec<usize> =
+            if flatten_context.importable_identifiers == self.importable_identifiers {
+                affected.iter().copied().collect()
+            } else {
+                (0..self.parsed_files.len()).collect()
+            };
+
+        debug!(files = targets.len(), "Re-extracting router files");
+
+        let decorator_parser = self.options.decorator_parser();
+        let extracted: Vec<(usize, Result<ExtractedRouterFile>)> = targets
+            .par_iter()
+            .map(|&index| {
+                let extracted = extract_with_dependencies(
+                    &flatten_context,
+                    &decorator_parser,
+                    &self.parsed_files[index],
+                    &self.imports_maps[index],
+                );
+                (index, extracted)
+            })
+            .collect();
+
+        let importable_identifiers = flatten_context.importable_identifiers;
+        self.store_extractions(extracted)?;
+        self.importable_identifiers = importable_identifiers;
+
+        let base_directory = self.options.base_directory.as_path();
+        let routers: Vec<RouterMetadata> = self.routers.iter().flatten().cloned().collect();
+        ensure_routers_found(&routers, self.parsed_files.len())?;
+        RouterTree::from_routers(&routers).check_conflicts()?;
+        check_untyped_outputs(self.options.untyped_output, &routers, &self.parsed_files)?;
+        let validator_imports = merge_validator_imports(
+            &self.typescript_parser,
+            &self.parse_cache,
+            &routers,
+            base_directory,
+        )?;
+
+        let result = generation_result(&routers, start_time);
+        let files = render_outputs(
+            &self.options,
+            &self.typescript_parser,
+            &self.parsed_files,
+            routers,
+            &schema_locations,
+            &validator_imports,
+        );
+        write_generated_files(&files)?;
+        remove_stale_files(&stale_output_files(&self.options, &files))?;
+
+        Ok(result)
+    }
+
+    /// Stores every successful extraction and returns the first failure, keeping failed router
+    /// files pending so the next update retries them.
+    fn store_extractions(
+        &mut self,
+        extracted: Vec<(usize, Result<ExtractedRouterFile>)>,
+    ) -> Result<()> {
+        let mut failures = Vec::new();
+        for (index, extracted) in extracted {
+            match extracted {
+                Ok(extracted) => self.store_extracted(index, extracted),
+                Err(error) => failures.push((index, error)),
+            }
+        }
+
+        self.pending
+            .extend(failures.iter().map(|(index, _)| *index));
+        failures
+            .into_iter()
+            .next()
+            .map_or(Ok(()), |(_, error)| Err(error))
+    }
+
+    fn store_extracted(&mut self, index: usize, (routers, dependencies): ExtractedRouterFile) {
+        let router_file = normalize_path(&self.parsed_files[index].file_path);
+        self.graph.set_dependencies(router_file, dependencies);
+        self.routers[index] = routers;
+        self.pending.remove(&index);
+    }
+
+    fn parse_cached(&self, path: &Path) -> Option<Arc<ParsedFile>> {
+        self.parse_cache
+            .get_or_parse(&self.typescript_parser, path)
+            .ok()
+    }
+
+    fn collect_module_dependencies(&self) -> HashSet<PathBuf> {
+        let Some(root_module) = self.options.root_module.as_deref() else {
+            return HashSet::new();
+        };
+
+        let mut dependencies: HashSet<PathBuf> = self
+            .parse_cached(root_module)
+            .map(|parsed| import_paths(&parsed))
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+        dependencies.insert(normalize_path(root_module));
+        dependencies
+    }
+}
+
+fn extract_with_dependencies(
+    context: &FlattenContext<'_>,
+    decorator_parser: &DecoratorParser,
+    parsed_file: &ParsedFile,
+    imports_map: &HashMap<String, ResolvedImport>,
+) -> Result<ExtractedRouterFile> {
+    let (routers, _) =
+        extract_router_file(context, decorator_parser, parsed_file, imports_map, None)?;
+    let dependencies = collect_router_dependencies(context, parsed_file, imports_map, &routers);
+    Ok((routers, dependencies))
+}
+
+/// Router files and the files they depend on, indexed in both directions.
+#[derive(Debug, Default)]
+struct DependencyGraph {
+    dependencies: HashMap<PathBuf, HashSet<PathBuf>>,
+    dependents: HashMap<PathBuf, HashSet<PathBuf>>,
+}
+
+impl DependencyGraph {
+    fn set_dependencies(&mut self, router_file: PathBuf, dependencies: HashSet<PathBuf>) {
+        for previous in self.dependencies.remove(&router_file).unwrap_or_default() {
+            self.remove_dependent(&previous, &router_file);
+        }
+
+        for dependency in &dependencies {
+            self.dependents
+                .entry(dependency.clone())
+                .or_default()
+                .insert(router_file.clone());
+        }
+        self.dependencies.insert(router_file, dependencies);
+    }
+
+    fn remove_dependent(&mut self, dependency: &Path, router_file: &Path) {
+        let Some(dependents) = self.dependents.get_mut(dependency) else {
+            return;
+        };
+        dependents.remove(router_file);
+        if dependents.is_empty() {
+            self.dependents.remove(dependency);
+        }
+    }
+
+    fn dependents_of<'a>(&'a self, path: &Path) -> impl Iterator<Item = &'a PathBuf> + 'a {
+        self.dependents.get(path).into_iter().flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    const USER_SCHEMA: &str =
+        "import { z } from 'zod';\nexport const userSchema = z.object({ id: z.string() });\n";
+
+    const USERS_ROUTER: &str = r"
+        import { Router, Query } from 'nestjs-trpc';
+        import { userSchema } from '../schemas';
+
+        @Router({ alias: 'users' })
+        export class UsersRouter {
+            @Query({ output: userSchema })
+            me() { return {} as any; }
+        }
+    ";
+
+    const POSTS_ROUTER: &str = r"
+        import { Router, Query } from 'nestjs-trpc';
+        import { z } from 'zod';
+
+        @Router({ alias: 'posts' })
+        export class PostsRouter {
+            @Query({ output: z.string() })
+            latest() { return ''; }
+        }
+    ";
+
+    fn write_file(directory: &Path, name: &str, content: &str) -> PathBuf {
+        let path = directory.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn setup_project() -> (TempDir, IncrementalGeneration) {
+        let temporary_directory = TempDir::new().unwrap();
+        let base = temporary_directory.path();
+        write_file(base, "schemas/user.schema.ts", USER_SCHEMA);
+        write_file(
+            base,
+            "schemas/index.ts",
+            "export { userSchema } from './user.schema';\n",
+        );
+        write_file(base, "routers/users.router.ts", USERS_ROUTER);
+        write_file(base, "routers/posts.router.ts", POSTS_ROUTER);
+        write_file(base, "unrelated.ts", "export const unrelated = 1;\n");
+
+        let options = GenerationOptions::new(base, base.join("@generated"), "**/*.router.ts");
+        let mut generation = IncrementalGeneration::new(options);
+        generation.rebuild().unwrap();
+
+        (temporary_directory, generation)
+    }
+
+    fn read_server(base: &Path) -> String {
+        fs::read_to_string(base.join("@generated/server.ts")).unwrap()
+    }
+
+    #[test]
+    fn test_graph_tracks_schema_and_barrel_files() {
+        let (temporary_directory, generation) = setup_project();
+        let base = temporary_directory.path();
+
+        let users_router = base.join("routers/users.router.ts");
+        for dependency in ["schemas/index.ts", "schemas/user.schema.ts"] {
+            let dependents: Vec<_> = generation
+                .graph
+                .dependents_of(&base.join(dependency))
+                .collect();
+            assert_eq!(dependents, vec![&users_router], "{dependency}");
+        }
+    }
+
+    #[test]
+    fn test_graph_tracks_package_manifests() {
+        let (temporary_directory, mut generation) = setup_project();
+        let base = temporary_directory.path();
+
+        let manifest = write_file(
+            base,
+            "node_modules/@acme/contracts/package.json",
+            r#"{ "name": "@acme/contracts", "types": "./index.ts" }"#,
+        );
+        write_file(base, "node_modules/@acme/contracts/index.ts", USER_SCHEMA);
+        let users_router = write_file(
+            base,
+            "routers/users.router.ts",
+            &USERS_ROUTER.replace("'../schemas'", "'@acme/contracts'"),
+        );
+        generation
+            .update(std::slice::from_ref(&users_router))
+            .unwrap();
+
+        let dependents: Vec<_> = generation.graph.dependents_of(&manifest).collect();
+        assert_eq!(dependents, vec![&users_router]);
+    }
+
+    #[test]
+    fn test_unrelated_change_is_skipped() {
+        let (temporary_directory, mut generation) = setup_project();
+        let base = temporary_directory.path();
+
+        let unrelated = write_file(base, "unrelated.ts", "export const unrelated = 2;\n");
+
+        assert!(generation.update(&[unrelated]).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_schema_change_regenerates_dependent_router() {
+        let (temporary_directory, mut generation) = setup_project();
+        let base = temporary_directory.path();
+
+        let schema = write_file(
+            base,
+            "schemas/user.schema.ts",
+            "import { z } from 'zod';\nexport const userSchema = z.object({ id: z.number() });\n",
+        );
+        let changes = generation
+            .classify_changes(std::slice::from_ref(&schema))
+            .unwrap()
+            .unwrap();
+        assert_eq!(changes.dependent_routers.len(), 1);
+        assert!(changes.modified_routers.is_empty());
+
+        let result = generation.update(&[schema]).unwrap().unwrap();
+        assert_eq!(result.router_count, 2);
+    }
+
+    #[test]
+    fn test_router_edit_updates_output() {
+        let (temporary_directory, mut generation) = setup_project();
+        let base = temporary_directory.path();
+
+        let router = write_file(
+            base,
+            "routers/posts.router.ts",
+            &POSTS_ROUTER.replace("latest()", "newest()"),
+        );
+        generation.update(&[router]).unwrap().unwrap();
+
+        let server = read_server(base);
+        assert!(server.contains("newest:"), "{server}");
+        assert!(!server.contains("latest:"), "{server}");
+    }
+
+    #[test]
+    fn test_unchanged_output_is_not_rewritten() {
+        let (temporary_directory, mut generation) = setup_project();
+        let base = temporary_directory.path();
+        let server_file = base.join("@generated/server.ts");
+        let modified_before = fs::metadata(&server_file).unwrap().modified().unwrap();
+
+        let router = write_file(base, "routers/posts.router.ts", POSTS_ROUTER);
+        generation.update(&[router]).unwrap().unwrap();
+
+        let modified_after = fs::metadata(&server_file).unwrap().modified().unwrap();
+        assert_eq!(modified_before, modified_after);
+    }
+
+    #[test]
+    fn test_failed_router_is_retried_on_unrelated_change() {
+        let (temporary_directory, mut generation) = setup_project();
+        let base = temporary_directory.path();
+
+        let users_router = write_file(
+            base,
+            "routers/users.router.ts",
+            &USERS_ROUTER.replace("'users'", "buildAlias()"),
+        );
+        assert!(generation.update(&[users_router]).is_err());
+
+        let posts_router = write_file(
+            base,
+            "routers/posts.router.ts",
+            &POSTS_ROUTER.replace("latest()", "newest()"),
+        );
+        assert!(generation.update(&[posts_router]).is_err());
+        assert!(!read_server(base).contains("newest:"));
+
+        let users_router = write_file(base, "routers/users.router.ts", USERS_ROUTER);
+        generation.update(&[users_router]).unwrap().unwrap();
+        assert!(read_server(base).contains("newest:"));
+    }
+
+    #[test]
+    fn test_new_router_file_triggers_rebuild() {
+        let (temporary_directory, mut generation) = setup_project();
+        let base = temporary_directory.path();
+
+        let router = write_file(
+            base,
+            "routers/comments.router.ts",
+            &POSTS_ROUTER
+                .replace("posts", "comments")
+                .replace("PostsRouter", "CommentsRouter"),
+        );
+        let result = generation.update(&[router]).unwrap().unwrap();
+
+        assert_eq!(result.router_count, 3);
+        assert!(read_server(base).contains("comments: t.router"));
+    }
+
+    #[test]
+    fn test_set_dependencies_replaces_previous_edges() {
+        let mut graph = DependencyGraph::default();
+        let router = PathBuf::from("/project/users.router.ts");
+        let old_schema = PathBuf::from("/project/old.schema.ts");
+        let new_schema = PathBuf::from("/project/new.schema.ts");
+
+        graph.set_dependencies(router.clone(), HashSet::from([old_schema.clone()]));
+        graph.set_dependencies(router.clone(), HashSet::from([new_schema.clone()]));
+
+        assert_eq!(graph.dependents_of(&old_schema).count(), 0);
+        assert_eq!(
+            graph.dependents_of(&new_schema).collect::<Vec<_>>(),
+            vec![&router]
+        );
+    }
+}
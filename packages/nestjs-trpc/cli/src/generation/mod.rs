@@ -0,0 +1,1377 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use rayon::prelude::*;
+use tracing::{debug, info, warn};
+
+use crate::generator::GENERATED_FILE_MARKER;
+use crate::parser::cache::ParseCache;
+use crate::parser::decorator::collect_schema_identifiers;
+use crate::parser::imports::DEFAULT_EXPORT;
+use crate::parser::module::TransformerInfo;
+use crate::parser::schema::replace_identifier;
+use crate::{
+    check_zod_entry_points, detect_composed_decorators, detect_validator_imports,
+    extract_procedures_from_hierarchy, merge_validator_bindings, BaseClassResolver,
+    ConstantEvaluator, DecoratorDiagnostic, DecoratorParser, FileScanner, HoistedDeclaration,
+    ImportResolver, OutputInference, ParsedFile, ParserError, PathAliases, ProcedureDecoratorAlias,
+    ProcedureMetadata, ResolvedImport, ResolverContext, RouterExportKind, RouterInfo,
+    RouterMetadata, RouterParser, RouterTree, ServerGenerator, StaticGenerator, SyntaxDiagnostic,
+    TsParser, UnexportedRouterDiagnostic, UntypedOutput, ValidatorImport, ZodFlattener, ZodVersion,
+};
+use std::collections::HashSet;
+use swc_common::Spanned;
+use swc_ecma_ast::{Decl, ModuleDecl, ModuleItem, Stmt};
+
+mod dependencies;
+mod disk_cache;
+mod hoisting;
+mod incremental;
+mod schema_type;
+mod types;
+
+pub use disk_cache::default_cache_directory;
+pub use incremental::IncrementalGeneration;
+
+use dependencies::collect_router_dependencies;
+use disk_cache::{hash_identifiers, CachedRouterFile, GenerationCache};
+use hoisting::name_hoisted_declarations;
+
+/// Result of a generation operation
+#[derive(Debug, Clone)]
+pub struct GenerationResult {
+    pub router_count: usize,
+    pub procedure_count: usize,
+    pub duration_milliseconds: u64,
+}
+
+/// A generated file and the path it belongs at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneratedFile {
+    pub path: PathBuf,
+    pub content: String,
+}
+
+/// Files produced by a generation run that has not written anything yet.
+#[derive(Debug, Clone)]
+pub struct GeneratedOutput {
+    pub result: GenerationResult,
+    pub files: Vec<GeneratedFile>,
+    /// Files an earlier run generated that this one no longer does, e.g. `types.ts` once
+    /// nothing needs it.
+    pub removed_files: Vec<PathBuf>,
+}
+
+/// Inputs for a generation run.
+#[derive(Debug, Clone)]
+pub struct GenerationOptions {
+    pub base_directory: PathBuf,
+    pub output_path: PathBuf,
+    pub router_pattern: String,
+    pub transformer: Option<TransformerInfo>,
+    pub import_extension: bool,
+    /// Module containing `TRPCModule.forRoot()`, used to resolve context and global middlewares
+    pub root_module: Option<PathBuf>,
+    /// Directory for the on-disk cache reused across runs; `None` disables it
+    pub cache_directory: Option<PathBuf>,
+    /// Custom procedure decorators from the config file, by name
+    pub procedure_decorators: BTreeMap<String, ProcedureDecoratorAlias>,
+    /// How outputs are typed when they have no schema and can't be inferred from their router
+    pub untyped_output: UntypedOutput,
+    /// Type of procedure `meta`; `None` falls back to a default when a procedure declares one
+    pub meta_type: Option<String>,
+}
+
+impl GenerationOptions {
+    #[must_use]
+    pub fn new(
+        base_directory: impl Into<PathBuf>,
+        output_path: impl Into<PathBuf>,
+        router_pattern: impl Into<String>,
+    ) -> Self {
+        Self {
+            base_directory: base_directory.into(),
+            output_path: output_path.into(),
+            router_pattern: router_pattern.into(),
+            transformer: None,
+            import_extension: false,
+            root_module: None,
+            cache_directory: None,
+            procedure_decorators: BTreeMap::new(),
+            untyped_output: UntypedOutput::default(),
+            meta_type: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_transformer(mut self, transformer: Option<TransformerInfo>) -> Self {
+        self.transformer = transformer;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_import_extension(mut self, import_extension: bool) -> Self {
+        self.import_extension = import_extension;
+        self
+    }
+
+    #[must_use]
+    pub fn with_root_module(mut self, root_module: Option<PathBuf>) -> Self {
+        self.root_module = root_module;
+        self
+    }
+
+    #[must_use]
+    pub fn with_cache_directory(mut self, cache_directory: Option<PathBuf>) -> Self {
+        self.cache_directory = cache_directory;
+        self
+    }
+
+    #[must_use]
+    pub fn with_procedure_decorators(
+        mut self,
+        procedure_decorators: BTreeMap<String, ProcedureDecoratorAlias>,
+    ) -> Self {
+        self.procedure_decorators = procedure_decorators;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_untyped_output(mut self, untyped_output: UntypedOutput) -> Self {
+        self.untyped_output = untyped_output;
+        self
+    }
+
+    #[must_use]
+    pub fn with_meta_type(mut self, meta_type: Option<String>) -> Self {
+        self.meta_type = meta_type;
+        self
+    }
+
+    fn decorator_parser(&self) -> DecoratorParser {
+        DecoratorParser::new().with_procedure_decorators(self.procedure_decorators.clone())
+    }
+}
+
+/// Core generation logic that can be called from both CLI and watch mode.
+/// Returns router count, procedure count, and duration in milliseconds.
+pub fn run_generation(
+    base_directory: &Path,
+    output_path: &Path,
+    router_pattern: &str,
+    transformer: Option<&TransformerInfo>,
+    import_extension: bool,
+) -> Result<GenerationResult> {
+    let options = GenerationOptions::new(base_directory, output_path, router_pattern)
+        .with_transformer(transformer.cloned())
+        .with_import_extension(import_extension);
+    run_generation_with_options(&options)
+}
+
+/// Runs generation and writes `server.ts`, plus `types.ts` when the root module declares a
+/// context or the routers use middlewares.
+pub fn run_generation_with_options(options: &GenerationOptions) -> Result<GenerationResult> {
+    let (output, generation_cache) = generate_with_cache(options)?;
+    write_generated_files(&output.files)?;
+    remove_stale_files(&output.removed_files)?;
+    generation_cache.save();
+    Ok(output.result)
+}
+
+/// Runs generation without writing any output or updating the generation cache, returning the
+/// files with their target paths.
+pub fn generate_in_memory(options: &GenerationOptions) -> Result<GeneratedOutput> {
+    generate_with_cache(options).map(|(output, _)| output)
+}
+
+/// Generates the output along with the cache entries to save once it has been written.
+fn generate_with_cache(options: &GenerationOptions) -> Result<(GeneratedOutput, GenerationCache)> {
+    let start_time = Instant::now();
+    let base_directory = options.base_directory.as_path();
+
+    let router_files = scan_router_files(base_directory, &options.router_pattern)?;
+    let (typescript_parser, parsed_files) = parse_router_files(&router_files)?;
+    let parse_cache = ParseCache::new();
+    let mut generation_cache = GenerationCache::load(options);
+    let cached_files: Vec<Option<CachedRouterFile>> = parsed_files
+        .iter()
+        .map(|parsed_file| generation_cache.take_fresh(&parsed_file.file_path))
+        .collect();
+
+    let imports_maps: Vec<HashMap<String, ResolvedImport>> = parsed_files
+        .par_iter()
+        .zip(&cached_files)
+        .map(|(parsed_file, cached)| {
+            cached.as_ref().map_or_else(
+                || {
+                    build_file_imports_map(
+                        &typescript_parser,
+                        &parse_cache,
+                        parsed_file,
+                        base_directory,
+                    )
+                },
+                |cached| cached.imports_map.clone(),
+            )
+        })
+        .collect();
+    let schema_locations = merge_schema_locations(&parsed_files, &imports_maps, base_directory);
+    let flatten_context = FlattenContext::new(
+        &typescript_parser,
+        &parse_cache,
+        base_directory,
+        &schema_locations,
+    );
+    let routers = extract_and_flatten_routers(
+        &flatten_context,
+        &options.decorator_parser(),
+        &parsed_files,
+        &imports_maps,
+        cached_files,
+        &mut generation_cache,
+    )?;
+    ensure_routers_found(&routers, parsed_files.len())?;
+    RouterTree::from_routers(&routers).check_conflicts()?;
+    check_untyped_outputs(options.untyped_output, &routers, &parsed_files)?;
+    let validator_imports =
+        merge_validator_imports(&typescript_parser, &parse_cache, &routers, base_directory)?;
+
+    let result = generation_result(&routers, start_time);
+    let files = render_outputs(
+        options,
+        &typescript_parser,
+        &parsed_files,
+        routers,
+        &schema_locations,
+        &validator_imports,
+    );
+    let removed_files = stale_output_files(options, &files);
+
+    let output = GeneratedOutput {
+        result,
+        files,
+        removed_files,
+    };
+    Ok((output, generation_cache))
+}
+
+/// Renders `server.ts`, and `types.ts` when needed, from flattened routers.
+fn render_outputs(
+    options: &GenerationOptions,
+    typescript_parser: &TsParser,
+    parsed_files: &[ParsedFile],
+    mut routers: Vec<RouterMetadata>,
+    schema_locations: &HashMap<String, PathBuf>,
+    validator_imports: &[ValidatorImport],
+) -> Vec<GeneratedFile> {
+    let module_types = types::collect_module_types(
+        typescript_parser,
+        options.root_module.as_deref(),
+        parsed_files,
+        &routers,
+        &options.base_directory,
+    );
+    apply_global_middlewares(&mut routers, &module_types.global_middleware_names);
+    name_hoisted_declarations(
+        &mut routers,
+        schema_locations,
+        validator_imports,
+        ZodVersion::detect(&options.base_directory),
+        options
+            .transformer
+            .as_ref()
+            .map(|transformer| transformer.import_name.as_str()),
+    );
+
+    let static_generator = StaticGenerator::new()
+        .with_transformer(options.transformer.clone())
+        .with_import_extension(options.import_extension)
+        .with_meta_type(options.meta_type.clone())
+        .with_validator_imports(used_validator_imports(&routers, validator_imports));
+    let server_generator = ServerGenerator::new()
+        .with_static_generator(static_generator.clone())
+        .with_middlewares(module_types.middlewares.clone())
+        .with_untyped_output(options.untyped_output);
+    let server_file_path = server_file_path(&options.output_path);
+    let server_content = server_generator.generate_with_schema_imports(
+        &routers,
+        schema_locations,
+        &server_file_path,
+    );
+
+    debug!(
+        content_length = server_content.len(),
+        "Generated server.ts content"
+    );
+
+    let output_directory = server_file_path.parent().map(Path::to_path_buf);
+    let mut files = vec![GeneratedFile {
+        path: server_file_path,
+        content: server_content,
+    }];
+    files.extend(output_directory.and_then(|output_directory| {
+        types::render_types_file(&output_directory, &module_types, static_generator)
+    }));
+
+    files
+}
+
+/// Writes generated files, leaving files whose content is unchanged untouched.
+fn write_generated_files(files: &[GeneratedFile]) -> Result<()> {
+    for file in files {
+        let parent = file.path.parent().unwrap_or_else(|| Path::new(""));
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create output directory '{}'", parent.display()))?;
+
+        if write_if_changed(&file.path, &file.content)? {
+            info!(output = %file.path.display(), "Generated file");
+        }
+    }
+
+    Ok(())
+}
+
+/// Generated files in the output directory that `files` no longer includes. Files without the
+/// generated header are left alone, so a hand-written `types.ts` is never reported or removed.
+fn stale_output_files(options: &GenerationOptions, files: &[GeneratedFile]) -> Vec<PathBuf> {
+    let server_file_path = server_file_path(&options.output_path);
+    let Some(output_directory) = server_file_path.parent() else {
+        return Vec::new();
+    };
+
+    let types_file_path = output_directory.join(types::TYPES_FILE_NAME);
+    let is_generated = files.iter().any(|file| file.path == types_file_path);
+    let was_generated = fs::read_to_string(&types_file_path)
+        .is_ok_and(|content| content.contains(GENERATED_FILE_MARKER));
+    if is_generated || !was_generated {
+        return Vec::new();
+    }
+    vec![types_file_path]
+}
+
+fn remove_stale_files(paths: &[PathBuf]) -> Result<()> {
+    for path in paths {
+        fs::remove_file(path)
+            .with_context(|| format!("Failed to remove stale file '{}'", path.display()))?;
+        info!(output = %path.display(), "Removed stale generated file");
+    }
+    Ok(())
+}
+
+fn generation_result(routers: &[RouterMetadata], start_time: Instant) -> GenerationResult {
+    let router_count = routers.len();
+    let procedure_count = routers.iter().map(|r| r.procedures.len()).sum();
+    // u128 millis won't overflow u64 for any realistic program lifetime (584 million years)
+    #[allow(clippy::cast_possible_truncation)]
+    let duration_milliseconds = start_time.elapsed().as_millis() as u64;
+
+    GenerationResult {
+        router_count,
+        procedure_count,
+        duration_milliseconds,
+    }
+}
+
+fn scan_router_files(base_directory: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
+    let scanner = FileScanner::new(base_directory).with_context(|| {
+        format!(
+            "Failed to initialize scanner for '{}'",
+            base_directory.display()
+        )
+    })?;
+
+    let router_files = scanner
+        .scan(pattern)
+        .with_context(|| format!("Failed to scan for router files with pattern '{pattern}'"))?;
+
+    if router_files.is_empty() {
+        anyhow::bail!(
+            "No router files found matching pattern '{pattern}' in '{}'.\n\
+            Check that your router files exist and match the pattern.",
+            base_directory.display()
+        );
+    }
+
+    info!(
+        count = router_files.len(),
+        pattern = %pattern,
+        "Found router files"
+    );
+
+    for file in &router_files {
+        debug!(file = ?file, "Found router file");
+    }
+
+    Ok(router_files)
+}
+
+fn parse_router_files(router_files: &[PathBuf]) -> Result<(TsParser, Vec<ParsedFile>)> {
+    let typescript_parser = TsParser::new();
+    let (parsed_files, parse_errors) = typescript_parser.parse_files(router_files);
+
+    for error in &parse_errors {
+        print_parse_error(error);
+    }
+
+    if parsed_files.is_empty() {
+        anyhow::bail!(
+            "Failed to parse any router files. {} files had syntax errors.",
+            parse_errors.len()
+        );
+    }
+
+    info!(
+        parsed = parsed_files.len(),
+        errors = parse_errors.len(),
+        "Parsed TypeScript files"
+    );
+
+    Ok((typescript_parser, parsed_files))
+}
+
+fn print_parse_error(error: &ParserError) {
+    if let ParserError::SyntaxError {
+        path,
+        line,
+        column,
+        message,
+    } = error
+    {
+        if let Ok(content) = fs::read_to_string(path) {
+            let diagnostic = SyntaxDiagnostic::new(path, &content, *line, *column, message.clone());
+            let report = miette::Report::new(diagnostic);
+            eprintln!("{report:?}");
+            return;
+        }
+    }
+
+    warn!("Parse error: {}", error);
+}
+
+/// Extracts and flattens every router file, reusing cached results that were flattened against
+/// the same importable identifiers.
+fn extract_and_flatten_routers(
+    context: &FlattenContext<'_>,
+    decorator_parser: &DecoratorParser,
+    parsed_files: &[ParsedFile],
+    imports_maps: &[HashMap<String, ResolvedImport>],
+    cached_files: Vec<Option<CachedRouterFile>>,
+    generation_cache: &mut GenerationCache,
+) -> Result<Vec<RouterMetadata>> {
+    let identifiers_hash = hash_identifiers(&context.importable_identifiers);
+    let cache_identifiers_hash = generation_cache.is_enabled().then_some(identifiers_hash);
+
+    let extracted: Vec<(Vec<RouterMetadata>, Option<CachedRouterFile>)> = parsed_files
+        .par_iter()
+        .zip(imports_maps)
+        .zip(cached_files)
+        .map(|((parsed_file, imports_map), cached)| {
+            match cached.filter(|entry| entry.is_flattened_against(identifiers_hash)) {
+                Some(entry) => Ok((entry.routers.clone(), Some(entry))),
+                None => extract_router_file(
+                    context,
+                    decorator_parser,
+                    parsed_file,
+                    imports_map,
+                    cache_identifiers_hash,
+                ),
+            }
+        })
+        .collect::<Result<_>>()?;
+
+    let mut routers = Vec::new();
+    for ((file_routers, entry), parsed_file) in extracted.into_iter().zip(parsed_files) {
+        routers.extend(file_routers);
+        if let Some(entry) = entry {
+            generation_cache.insert(parsed_file.file_path.clone(), entry);
+        }
+    }
+
+    Ok(routers)
+}
+
+/// Extracts and flattens one router file, building its cache entry when `cache_identifiers_hash` is set.
+fn extract_router_file(
+    context: &FlattenContext<'_>,
+    decorator_parser: &DecoratorParser,
+    parsed_file: &ParsedFile,
+    imports_map: &HashMap<String, ResolvedImport>,
+    cache_identifiers_hash: Option<u64>,
+) -> Result<(Vec<RouterMetadata>, Option<CachedRouterFile>)> {
+    let decorator_parser =
+        file_decorator_parser(context, decorator_parser, parsed_file, imports_map);
+    let mut routers = extract_routers_from_file(
+        context,
+        parsed_file,
+        imports_map,
+        &RouterParser::new(),
+        &decorator_parser,
+    )?;
+    flatten_unimportable_schemas(
+        &mut routers,
+        context,
+        RouterFile {
+            parsed_file,
+            imports_map,
+        },
+    );
+
+    let entry = cache_identifiers_hash.and_then(|identifiers_hash| {
+        let dependencies = collect_router_dependencies(context, parsed_file, imports_map, &routers);
+        CachedRouterFile::new(
+            &parsed_file.file_path,
+            &dependencies,
+            identifiers_hash,
+            imports_map.clone(),
+            routers.clone(),
+        )
+    });
+
+    Ok((routers, entry))
+}
+
+/// `decorator_parser` extended with the `applyDecorators` procedure decorators `parsed_file` uses.
+fn file_decorator_parser(
+    context: &FlattenContext<'_>,
+    decorator_parser: &DecoratorParser,
+    parsed_file: &ParsedFile,
+    imports_map: &HashMap<String, ResolvedImport>,
+) -> DecoratorParser {
+    let composed_decorators = detect_composed_decorators(
+        parsed_file,
+        imports_map,
+        context.typescript_parser,
+        context.parse_cache,
+    );
+    decorator_parser
+        .clone()
+        .with_procedure_decorators(composed_decorators)
+}
+
+fn ensure_routers_found(routers: &[RouterMetadata], parsed_file_count: usize) -> Result<()> {
+    if routers.is_empty() {
+        anyhow::bail!(
+            "No @Router decorated classes found in {parsed_file_count} parsed files.\n\
+            Ensure your router classes are decorated with @Router from 'nestjs-trpc'."
+        );
+    }
+
+    info!(
+        routers = routers.len(),
+        total_procedures = routers.iter().map(|r| r.procedures.len()).sum::<usize>(),
+        "Extracted router metadata"
+    );
+
+    Ok(())
+}
+
+fn extract_routers_from_file(
+    context: &FlattenContext<'_>,
+    parsed_file: &ParsedFile,
+    imports_map: &HashMap<String, ResolvedImport>,
+    router_parser: &RouterParser,
+    decorator_parser: &DecoratorParser,
+) -> Result<Vec<RouterMetadata>> {
+    let resolver = context
+        .resolver_context()
+        .with_imports_map(parsed_file, imports_map.clone());
+    let constant_evaluator = ConstantEvaluator::new(&resolver);
+    let mut routers = Vec::new();
+
+    for router_info in router_parser.extract_routers(parsed_file) {
+        let alias = resolve_router_alias(&router_info, parsed_file, &constant_evaluator)?;
+        let mut procedures = extract_router_procedures(
+            context,
+            &resolver,
+            parsed_file,
+            &router_info,
+            decorator_parser,
+        );
+
+        apply_output_inference(&mut procedures, &router_info);
+
+        let router_metadata = RouterMetadata {
+            name: router_info.class_name,
+            alias,
+            file_path: router_info.file_path,
+            procedures,
+        };
+
+        debug!(
+            router = %router_metadata.name,
+            alias = ?router_metadata.alias,
+            procedures = router_metadata.procedures.len(),
+            "Extracted router metadata"
+        );
+
+        routers.push(router_metadata);
+    }
+
+    Ok(routers)
+}
+
+/// The router's own and inherited procedures. Inherited ones are flattened against the file that
+/// declares them, since their schemas refer to that file's bindings.
+fn extract_router_procedures(
+    context: &FlattenContext<'_>,
+    resolver: &ResolverContext<'_>,
+    parsed_file: &ParsedFile,
+    router_info: &RouterInfo,
+    decorator_parser: &DecoratorParser,
+) -> Vec<ProcedureMetadata> {
+    let base_classes =
+        BaseClassResolver::new(resolver).resolve_base_classes(parsed_file, &router_info.class_name);
+    let decorator_parser_for = |file: &ParsedFile| {
+        if file.file_path == parsed_file.file_path {
+            return decorator_parser.clone();
+        }
+        file_decorator_parser(context, decorator_parser, file, &resolver.imports_map(file))
+    };
+
+    extract_procedures_from_hierarchy(
+        parsed_file,
+        &router_info.class_name,
+        &base_classes,
+        decorator_parser_for,
+    )
+    .into_iter()
+    .flat_map(|class_procedures| {
+        let mut procedures = class_procedures.procedures;
+        if class_procedures.parsed_file.file_path == parsed_file.file_path {
+            return procedures;
+        }
+        let imports_map = resolver.imports_map(class_procedures.parsed_file);
+        let router_file = RouterFile {
+            parsed_file: class_procedures.parsed_file,
+            imports_map: &imports_map,
+        };
+        for procedure in &mut procedures {
+            flatten_procedure_schemas(procedure, context, router_file);
+        }
+        procedures
+    })
+    .collect()
+}
+
+/// The router's alias, statically evaluating it when it isn't a string literal.
+fn resolve_router_alias(
+    router_info: &RouterInfo,
+    parsed_file: &ParsedFile,
+    constant_evaluator: &ConstantEvaluator<'_>,
+) -> Result<Option<String>> {
+    let Some(expression) = router_info.alias_expression.as_deref() else {
+        return Ok(router_info.alias.clone());
+    };
+
+    if let Some(alias) = constant_evaluator.evaluate_string(expression, parsed_file) {
+        debug!(router = %router_info.class_name, alias = %alias, "Evaluated router alias");
+        return Ok(Some(alias));
+    }
+
+    let (offset, length) = parsed_file.get_byte_range(expression.span());
+    let diagnostic = DecoratorDiagnostic::new(
+        &parsed_file.file_path,
+        &parsed_file.source_file.src,
+        "Router".to_string(),
+        offset,
+        length,
+    )
+    .with_help(format!(
+        "The alias of {} must evaluate to a string at build time: use a string literal, a \
+        `const`, a string enum member, an `as const` object member, or a template literal of those",
+        router_info.class_name
+    ));
+    Err(diagnostic.into())
+}
+
+/// Lets `server.ts` type outputs without a schema from the router's return types, for routers
+/// it can import by class name.
+fn apply_output_inference(procedures: &mut [ProcedureMetadata], router_info: &RouterInfo) {
+    let default_export = match router_info.export_kind {
+        RouterExportKind::Named => false,
+        // Anonymous classes have no name to import them under.
+        RouterExportKind::Default if router_info.class_name != DEFAULT_EXPORT => true,
+        RouterExportKind::Default | RouterExportKind::None => return,
+    };
+
+    for procedure in procedures.iter_mut() {
+        if procedure.output_schema.is_some() {
+            continue;
+        }
+
+        procedure.output_inference = Some(OutputInference {
+            router_class_name: router_info.class_name.clone(),
+            router_file_path: router_info.file_path.clone(),
+            default_export,
+        });
+    }
+}
+
+/// Reports routers whose outputs fall back to `any` or `unknown`, pointing at the class when it
+/// only needs exporting. Fails on the first one when `untyped_output` is `error`.
+fn check_untyped_outputs(
+    untyped_output: UntypedOutput,
+    routers: &[RouterMetadata],
+    parsed_files: &[ParsedFile],
+) -> Result<()> {
+    for router in routers {
+        let untyped: Vec<&str> = router
+            .procedures
+            .iter()
+            .filter(|procedure| {
+                procedure.output_schema.is_none() && procedure.output_inference.is_none()
+            })
+            .map(|procedure| procedure.name.as_str())
+            .collect();
+        if untyped.is_empty() {
+            continue;
+        }
+
+        let diagnostic = parsed_files
+            .iter()
+            .find(|parsed_file| parsed_file.file_path == router.file_path)
+            .and_then(|parsed_file| unexported_router_diagnostic(parsed_file, &router.name));
+        match (untyped_output, diagnostic) {
+            (UntypedOutput::Error, Some(diagnostic)) => return Err(diagnostic.into()),
+            (UntypedOutput::Error, None) => anyhow::bail!(
+                "The outputs of {} in {} can't be typed: give them an `output` schema or export \
+                the router as a named class",
+                untyped.join(", "),
+                router.name
+            ),
+            (_, Some(diagnostic)) => warn!(
+                router = %router.name,
+                procedures = %untyped.join(", "),
+                path = ?router.file_path,
+                help = %diagnostic.help,
+                "{diagnostic}"
+            ),
+            (_, None) => warn!(
+                router = %router.name,
+                procedures = %untyped.join(", "),
+                "Procedure outputs can't be typed"
+            ),
+        }
+    }
+    Ok(())
+}
+
+/// Points at the router class when it isn't exported under its own name, the only case an
+/// export fixes. Such a class is always a plain `class` statement.
+fn unexported_router_diagnostic(
+    parsed_file: &ParsedFile,
+    class_name: &str,
+) -> Option<UnexportedRouterDiagnostic> {
+    let is_unexported =
+        RouterParser::new()
+            .extract_routers(parsed_file)
+            .iter()
+            .any(|router_info| {
+                router_info.class_name == class_name
+                    && router_info.export_kind == RouterExportKind::None
+            });
+    if !is_unexported {
+        return None;
+    }
+
+    let class_identifier = parsed_file.module.body.iter().find_map(|item| {
+        let ModuleItem::Stmt(Stmt::Decl(Decl::Class(class_declaration))) = item else {
+            return None;
+        };
+        (class_declaration.ident.sym.as_ref() == class_name).then_some(&class_declaration.ident)
+    })?;
+
+    let (offset, length) = parsed_file.get_byte_range(class_identifier.span);
+    Some(UnexportedRouterDiagnostic::new(
+        &parsed_file.file_path,
+        &parsed_file.source_file.src,
+        class_name.to_string(),
+        offset,
+        length,
+    ))
+}
+
+fn apply_global_middlewares(routers: &mut [RouterMetadata], global_middleware_names: &[String]) {
+    if global_middleware_names.is_empty() {
+        return;
+    }
+
+    for procedure in routers.iter_mut().flat_map(|router| &mut router.procedures) {
+        procedure
+            .middlewares
+            .splice(0..0, global_middleware_names.iter().cloned());
+    }
+}
+
+fn build_imports_maps(
+    typescript_parser: &TsParser,
+    parse_cache: &ParseCache,
+    parsed_files: &[ParsedFile],
+    base_directory: &Path,
+) -> Vec<HashMap<String, ResolvedImport>> {
+    parsed_files
+        .par_iter()
+        .map(|parsed_file| {
+            build_file_imports_map(typescript_parser, parse_cache, parsed_file, base_directory)
+        })
+        .collect()
+}
+
+fn merge_schema_locations(
+    parsed_files: &[ParsedFile],
+    imports_maps: &[HashMap<String, ResolvedImport>],
+    base_directory: &Path,
+) -> HashMap<String, PathBuf> {
+    let mut schema_locations = HashMap::new();
+    let path_aliases = PathAliases::load(base_directory);
+
+    // Merged sequentially in file order so later files win exactly as they would unthreaded.
+    for (parsed_file, imports_map) in parsed_files.iter().zip(imports_maps) {
+        // Namespace and default imports are flattened instead, since `server.ts` imports by name.
+        for (name, resolved) in imports_map
+            .iter()
+            .filter(|(_, resolved)| resolved.is_named_export())
+        {
+            schema_locations.insert(name.clone(), resolved.source_file.clone());
+        }
+        add_external_imports_from_file(&mut schema_locations, parsed_file, path_aliases.as_ref());
+        add_exported_declarations_from_file(&mut schema_locations, parsed_file);
+    }
+
+    debug!(
+        schema_count = schema_locations.len(),
+        "Built schema locations map"
+    );
+
+    schema_locations
+}
+
+fn build_file_imports_map(
+    typescript_parser: &TsParser,
+    parse_cache: &ParseCache,
+    parsed_file: &ParsedFile,
+    base_directory: &Path,
+) -> HashMap<String, ResolvedImport> {
+    ImportResolver::new(typescript_parser)
+        .with_parse_cache(parse_cache.clone())
+        .build_imports_map(parsed_file, base_directory)
+        .unwrap_or_else(|error| {
+            warn!(
+                "Failed to build imports map for {:?}: {}",
+                parsed_file.file_path, error
+            );
+            HashMap::new()
+        })
+}
+
+/// Validator bindings imported by the files `server.ts` copies schemas from: the router files and
+/// the sources of their flattened schemas. Fails when they mix Zod entry points or bind one name
+/// to different imports.
+fn merge_validator_imports(
+    typescript_parser: &TsParser,
+    parse_cache: &ParseCache,
+    routers: &[RouterMetadata],
+    base_directory: &Path,
+) -> Result<Vec<ValidatorImport>> {
+    let router_files: BTreeSet<&PathBuf> = routers.iter().map(|router| &router.file_path).collect();
+    let schema_sources: BTreeSet<&PathBuf> = routers
+        .iter()
+        .flat_map(|router| &router.procedures)
+        .flat_map(|procedure| &procedure.schema_sources)
+        .filter(|path| !router_files.contains(path))
+        .collect();
+
+    let detected: Vec<(PathBuf, ValidatorImport)> = router_files
+        .into_iter()
+        .chain(schema_sources)
+        .filter_map(|path| parse_cache.get_or_parse(typescript_parser, path).ok())
+        .flat_map(|parsed_file| {
+            detect_validator_imports(&parsed_file)
+                .into_iter()
+                .map(|validator_import| (parsed_file.file_path.clone(), validator_import))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    let installed_zod = ZodVersion::detect(base_directory);
+    debug!(version = ?installed_zod, "Detected installed Zod version");
+    check_zod_entry_points(&detected, installed_zod)?;
+    let validator_imports = merge_validator_bindings(detected, installed_zod)?;
+
+    debug!(validators = ?validator_imports, "Detected validator imports");
+    Ok(validator_imports)
+}
+
+/// The validator imports `server.ts` needs for the schemas it contains. `z` without a detected
+/// import is assumed to come from `zod`.
+fn used_validator_imports(
+    routers: &[RouterMetadata],
+    validator_imports: &[ValidatorImport],
+) -> Vec<ValidatorImport> {
+    let used: HashSet<&str> = routers
+        .iter()
+        .flat_map(|router| &router.procedures)
+        .flat_map(|procedure| &procedure.schema_identifiers)
+        .map(String::as_str)
+        .collect();
+
+    let mut imports: Vec<ValidatorImport> = validator_imports
+        .iter()
+        .filter(|validator_import| used.contains(validator_import.local_name.as_str()))
+        .cloned()
+        .collect();
+    let is_zod_undetected =
+        used.contains("z") && !imports.iter().any(|import| import.local_name == "z");
+    if is_zod_undetected {
+        imports.push(ValidatorImport::zod());
+    }
+    imports
+}
+
+/// Package imports keep their bare specifier in `server.ts`, even when the resolver followed them
+/// into a workspace package's source.
+fn add_external_imports_from_file(
+    schema_locations: &mut HashMap<String, PathBuf>,
+    parsed_file: &ParsedFile,
+    path_aliases: Option<&PathAliases>,
+) {
+    for item in &parsed_file.module.body {
+        let ModuleItem::ModuleDecl(ModuleDecl::Import(import_declaration)) = item else {
+            continue;
+        };
+
+        let module_specifier = import_declaration.src.value.to_string_lossy().into_owned();
+        if module_specifier.starts_with('.') || module_specifier.starts_with('/') {
+            continue;
+        }
+        if path_aliases.is_some_and(|aliases| aliases.resolve(&module_specifier).is_some()) {
+            continue;
+        }
+
+        add_named_import_specifiers(schema_locations, import_declaration, &module_specifier);
+    }
+}
+
+fn add_named_import_specifiers(
+    schema_locations: &mut HashMap<String, PathBuf>,
+    import_declaration: &swc_ecma_ast::ImportDecl,
+    module_specifier: &str,
+) {
+    for specifier in &import_declaration.specifiers {
+        let swc_ecma_ast::ImportSpecifier::Named(named) = specifier else {
+            continue;
+        };
+        let local_name = named.local.sym.to_string();
+        schema_locations.insert(local_name, PathBuf::from(module_specifier));
+    }
+}
+
+fn add_exported_declarations_from_file(
+    schema_locations: &mut HashMap<String, PathBuf>,
+    parsed_file: &ParsedFile,
+) {
+    for item in &parsed_file.module.body {
+        let ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) = item else {
+            continue;
+        };
+        let Decl::Var(variable_declaration) = &export.decl else {
+            continue;
+        };
+
+        add_variable_declarator_names(
+            schema_locations,
+            variable_declaration,
+            &parsed_file.file_path,
+        );
+    }
+}
+
+fn add_variable_declarator_names(
+    schema_locations: &mut HashMap<String, PathBuf>,
+    variable_declaration: &swc_ecma_ast::VarDecl,
+    file_path: &Path,
+) {
+    for declarator in &variable_declaration.decls {
+        let swc_ecma_ast::Pat::Ident(identifier) = &declarator.name else {
+            continue;
+        };
+        let name = identifier.id.sym.to_string();
+        schema_locations
+            .entry(name)
+            .or_insert_with(|| file_path.to_path_buf());
+    }
+}
+
+/// Inputs shared by every worker flattening schemas that `server.ts` cannot import.
+struct FlattenContext<'a> {
+    typescript_parser: &'a TsParser,
+    parse_cache: &'a ParseCache,
+    base_directory: &'a Path,
+    importable_identifiers: HashSet<String>,
+}
+
+impl<'a> FlattenContext<'a> {
+    fn new(
+        typescript_parser: &'a TsParser,
+        parse_cache: &'a ParseCache,
+        base_directory: &'a Path,
+        schema_locations: &HashMap<String, PathBuf>,
+    ) -> Self {
+        Self {
+            typescript_parser,
+            parse_cache,
+            base_directory,
+            importable_identifiers: schema_locations.keys().cloned().collect(),
+        }
+    }
+
+    /// Flattens `schema_text`, adding the enums it now refers to by placeholder to `collected`.
+    fn flatten(
+        &self,
+        schema_text: &str,
+        router_file: RouterFile<'_>,
+        collected: &mut Flattened,
+    ) -> Option<String> {
+        self.with_flattener(router_file, collected, |flattener| {
+            flattener
+                .flatten_schema(schema_text, router_file.parsed_file)
+                .ok()
+        })
+    }
+
+    /// Flattens the schema variable `identifier` names into a declaration in `collected`, and
+    /// returns the placeholder that refers to it.
+    fn hoist_schema(
+        &self,
+        identifier: &str,
+        router_file: RouterFile<'_>,
+        collected: &mut Flattened,
+    ) -> Option<String> {
+        self.with_flattener(router_file, collected, |flattener| {
+            flattener
+                .hoist_schema(identifier, router_file.parsed_file)
+                .ok()
+                .flatten()
+        })
+    }
+
+    fn with_flattener(
+        &self,
+        router_file: RouterFile<'_>,
+        collected: &mut Flattened,
+        flatten: impl FnOnce(&mut ZodFlattener<'_>) -> Option<String>,
+    ) -> Option<String> {
+        let mut flattener = ZodFlattener::new(self.typescript_parser, self.base_directory)
+            .with_importable_identifiers(self.importable_identifiers.clone())
+            .with_parse_cache(self.parse_cache.clone())
+            .with_imports_map(router_file.parsed_file, router_file.imports_map.clone());
+        let schema = flatten(&mut flattener)?;
+        merge_hoisted_declarations(&mut collected.hoisted, flattener.hoisted_declarations());
+        collected
+            .source_files
+            .extend(flattener.source_files().iter().cloned());
+        Some(schema)
+    }
+
+    fn resolver_context(&self) -> ResolverContext<'a> {
+        ResolverContext::new(
+            self.typescript_parser,
+            self.parse_cache,
+            self.base_directory,
+        )
+    }
+}
+
+/// What flattening a procedure's schemas adds besides their text: the enums and schemas they
+/// now refer to by placeholder, and the files they were copied from.
+#[derive(Debug, Default)]
+struct Flattened {
+    hoisted: Vec<HoistedDeclaration>,
+    source_files: BTreeSet<PathBuf>,
+}
+
+impl Flattened {
+    fn merge_into(self, procedure: &mut ProcedureMetadata) {
+        merge_hoisted_declarations(&mut procedure.hoisted_declarations, &self.hoisted);
+        procedure.schema_sources.extend(self.source_files);
+    }
+}
+
+/// The router file whose schemas are being flattened and the imports it resolves names against.
+#[derive(Clone, Copy)]
+struct RouterFile<'a> {
+    parsed_file: &'a ParsedFile,
+    imports_map: &'a HashMap<String, ResolvedImport>,
+}
+
+fn flatten_unimportable_schemas(
+    routers: &mut [RouterMetadata],
+    context: &FlattenContext<'_>,
+    router_file: RouterFile<'_>,
+) {
+    routers.par_iter_mut().for_each(|router| {
+        for procedure in &mut router.procedures {
+            flatten_procedure_schemas(procedure, context, router_file);
+        }
+    });
+}
+
+fn flatten_procedure_schemas(
+    procedure: &mut ProcedureMetadata,
+    context: &FlattenContext<'_>,
+    router_file: RouterFile<'_>,
+) {
+    let unimportable: Vec<_> = procedure
+        .schema_identifiers
+        .iter()
+        .filter(|identifier| !context.importable_identifiers.contains(identifier.as_str()))
+        .cloned()
+        .collect();
+
+    // Schema variables are flattened once and declared in `server.ts`, so every procedure
+    // referring to them shares one declaration instead of repeating it.
+    let shared_count = unimportable
+        .iter()
+        .filter(|identifier| share_schema_reference(procedure, identifier, context, router_file))
+        .count();
+
+    let mut flattened = Flattened::default();
+    let input_changed = try_flatten_schema(
+        &mut procedure.input_schema,
+        &mut procedure.input_schema_ref,
+        &mut flattened,
+        context,
+        router_file,
+    );
+
+    let output_changed = try_flatten_schema(
+        &mut procedure.output_schema,
+        &mut procedure.output_schema_ref,
+        &mut flattened,
+        context,
+        router_file,
+    );
+    flattened.merge_into(procedure);
+
+    // Resolve remaining unimportable identifiers nested within inline schemas and meta,
+    // which are read from the router file rather than from the schema's own source.
+    let mut inner_changed = false;
+    for identifier in &unimportable {
+        if resolve_and_replace_identifier(procedure, identifier, context, router_file) {
+            inner_changed = true;
+        }
+    }
+
+    if shared_count > 0 || input_changed || output_changed || inner_changed {
+        recollect_schema_identifiers(procedure, context.typescript_parser);
+    }
+}
+
+fn try_flatten_schema(
+    schema: &mut Option<String>,
+    schema_ref: &mut Option<String>,
+    flattened: &mut Flattened,
+    context: &FlattenContext<'_>,
+    router_file: RouterFile<'_>,
+) -> bool {
+    let Some(schema_text) = schema else {
+        return false;
+    };
+    let Some(flattened_text) = context.flatten(schema_text, router_file, flattened) else {
+        return false;
+    };
+    if flattened_text == *schema_text {
+        return false;
+    }
+    *schema = Some(flattened_text);
+    *schema_ref = None;
+    true
+}
+
+/// Replaces references to the schema variable `identifier` with a shared declaration's placeholder.
+fn share_schema_reference(
+    procedure: &mut ProcedureMetadata,
+    identifier: &str,
+    context: &FlattenContext<'_>,
+    router_file: RouterFile<'_>,
+) -> bool {
+    let mut flattened = Flattened::default();
+    let Some(placeholder) = context.hoist_schema(identifier, router_file, &mut flattened) else {
+        return false;
+    };
+    let changed = replace_in_schemas(procedure, |text| {
+        replace_identifier(text, identifier, &placeholder)
+    });
+    if !changed {
+        return false;
+    }
+
+    flattened.merge_into(procedure);
+    if procedure.input_schema != procedure.input_schema_ref {
+        procedure.input_schema_ref = None;
+    }
+    if procedure.output_schema != procedure.output_schema_ref {
+        procedure.output_schema_ref = None;
+    }
+    true
+}
+
+fn resolve_and_replace_identifier(
+    procedure: &mut ProcedureMetadata,
+    identifier: &str,
+    context: &FlattenContext<'_>,
+    router_file: RouterFile<'_>,
+) -> bool {
+    // Kept aside until something is replaced, so unused enums aren't hoisted.
+    let mut flattened = Flattened::default();
+    let Some(resolved) = context.flatten(identifier, router_file, &mut flattened) else {
+        return false;
+    };
+    if resolved == identifier {
+        return false;
+    }
+
+    let changed = replace_in_schemas(procedure, |text| {
+        replace_identifier(text, identifier, &resolved)
+    });
+    if changed {
+        flattened.merge_into(procedure);
+    }
+    changed
+}
+
+/// Applies `replace` to the procedure's input, output and meta, returning whether any changed.
+fn replace_in_schemas(
+    procedure: &mut ProcedureMetadata,
+    replace: impl Fn(&str) -> Option<String>,
+) -> bool {
+    let mut changed = false;
+    let texts = [
+        &mut procedure.input_schema,
+        &mut procedure.output_schema,
+        &mut procedure.meta,
+    ];
+    for text in texts.into_iter().flatten() {
+        if let Some(replaced) = replace(text) {
+            *text = replaced;
+            changed = true;
+        }
+    }
+    changed
+}
+
+fn merge_hoisted_declarations(
+    hoisted: &mut Vec<HoistedDeclaration>,
+    declarations: &[HoistedDeclaration],
+) {
+    let new_declarations: Vec<_> = declarations
+        .iter()
+        .filter(|declaration| {
+            !hoisted
+                .iter()
+                .any(|existing| existing.placeholder == declaration.placeholder)
+        })
+        .cloned()
+        .collect();
+    hoisted.extend(new_declarations);
+}
+
+fn recollect_schema_identifiers(procedure: &mut ProcedureMetadata, typescript_parser: &TsParser) {
+    let mut all_identifiers = HashSet::new();
+
+    if let Some(schema_text) = &procedure.input_schema {
+        all_identifiers.extend(collect_identifiers_from_schema_text(
+            typescript_parser,
+            schema_text,
+        ));
+    }
+
+    if let Some(schema_text) = &procedure.output_schema {
+        all_identifiers.extend(collect_identifiers_from_schema_text(
+            typescript_parser,
+            schema_text,
+        ));
+    }
+
+    if let Some(meta_text) = &procedure.meta {
+        all_identifiers.extend(collect_identifiers_from_schema_text(
+            typescript_parser,
+            meta_text,
+        ));
+    }
+
+    // Shared schemas are declared in `server.ts` too, so what they import is still needed.
+    for declaration in &procedure.hoisted_declarations {
+        if declaration.kind.is_schema() {
+            all_identifiers.extend(collect_identifiers_from_schema_text(
+                typescript_parser,
+                &declaration.declaration,
+            ));
+        }
+    }
+
+    let mut identifiers: Vec<String> = all_identifiers.into_iter().collect();
+    identifiers.sort();
+    procedure.schema_identifiers = identifiers;
+}
+
+fn collect_identifiers_from_schema_text(
+    typescript_parser: &TsParser,
+    schema_text: &str,
+) -> Vec<String> {
+    let wrapper = format!("const __temp = {schema_text};");
+    let Ok(parsed) = typescript_parser.parse_source("<schema>", &wrapper) else {
+        return Vec::new();
+    };
+
+    let Some(expression) = extract_initializer_expression(&parsed.module) else {
+        return Vec::new();
+    };
+
+    collect_schema_identifiers(expression)
+}
+
+fn extract_initializer_expression(module: &swc_ecma_ast::Module) -> Option<&swc_ecma_ast::Expr> {
+    for item in &module.body {
+        let ModuleItem::Stmt(Stmt::Decl(Decl::Var(variable_declaration))) = item else {
+            continue;
+        };
+        let initializer = variable_declaration
+            .decls
+            .first()
+            .and_then(|declarator| declarator.init.as_deref());
+        if initializer.is_some() {
+            return initializer;
+        }
+    }
+    None
+}
+
+/// `output_path` when it names a `.ts`/`.tsx` file, otherwise `server.ts` inside it.
+#[must_use]
+pub fn server_file_path(output_path: &Path) -> PathBuf {
+    if output_path
+        .extension()
+        .is_some_and(|ext| ext == "ts" || ext == "tsx")
+    {
+        output_path.to_path_buf()
+    } else {
+        output_path.join("server.ts")
+    }
+}
+
+/// Writes `content` unless the file already holds it, so editors and dev servers watching the
+/// output are not retriggered by no-op regenerations.
+fn write_if_changed(path: &Path, content: &str) -> Result<bool> {
+    if fs::read_to_string(path).is_ok_and(|existing| existing == content) {
+        debug!(output = %path.display(), "Generated file unchanged, skipping write");
+        return Ok(false);
+    }
+
+    fs::write(path, content)
+        .with_context(|| format!("Failed to write generated file to '{}'", path.display()))?;
+
+    Ok(true)
+}
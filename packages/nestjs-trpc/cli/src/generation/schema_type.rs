@@ -0,0 +1,438 @@
+//! The TypeScript output and input types of a flattened Zod schema, for recursive schemas whose
+//! type TypeScript can't infer. Constructs it doesn't know become `unknown`.
+
+use crate::parser::ParsedFile;
+use std::collections::HashMap;
+use swc_ecma_ast::{
+    BlockStmtOrExpr, CallExpr, Callee, Expr, ExprOrSpread, Lit, ObjectLit, Prop, PropName,
+    PropOrSpread,
+};
+
+const UNKNOWN: &str = "unknown";
+
+/// Methods that refine or describe a schema without changing its output type.
+const REFINEMENTS: &[&str] = &[
+    "min",
+    "max",
+    "length",
+    "email",
+    "url",
+    "uuid",
+    "cuid",
+    "cuid2",
+    "ulid",
+    "regex",
+    "includes",
+    "startsWith",
+    "endsWith",
+    "datetime",
+    "date",
+    "time",
+    "ip",
+    "trim",
+    "toLowerCase",
+    "toUpperCase",
+    "int",
+    "positive",
+    "negative",
+    "nonnegative",
+    "nonpositive",
+    "multipleOf",
+    "finite",
+    "safe",
+    "gt",
+    "gte",
+    "lt",
+    "lte",
+    "nonempty",
+    "refine",
+    "superRefine",
+    "check",
+    "describe",
+    "meta",
+    "strict",
+    "passthrough",
+    "strip",
+    "catchall",
+];
+
+/// Which type of a schema to describe: what parsing returns, or what it accepts. They differ for
+/// schemas with `.default()`, `.catch()` or `.transform()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Side {
+    Input,
+    Output,
+}
+
+pub(super) struct SchemaTypes<'a> {
+    file: &'a ParsedFile,
+    zod: &'a str,
+    /// Recursive schemas by name, mapped to the type alias declared for each on this side.
+    aliases: &'a HashMap<String, String>,
+    side: Side,
+}
+
+impl<'a> SchemaTypes<'a> {
+    pub(super) const fn new(
+        file: &'a ParsedFile,
+        zod: &'a str,
+        aliases: &'a HashMap<String, String>,
+        side: Side,
+    ) -> Self {
+        Self {
+            file,
+            zod,
+            aliases,
+            side,
+        }
+    }
+
+    pub(super) fn type_of(&self, expression: &Expr) -> String {
+        match expression.unwrap_parens() {
+            Expr::Ident(identifier) => self.identifier_type(identifier.sym.as_ref()),
+            Expr::Call(call_expression) => self.call_type(call_expression),
+            _ => UNKNOWN.to_string(),
+        }
+    }
+
+    /// A recursive schema's alias, or the inferred type of any other declared schema.
+    fn identifier_type(&self, name: &str) -> String {
+        let helper = match self.side {
+            Side::Input => "input",
+            Side::Output => "infer",
+        };
+        self.aliases
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| format!("{}.{helper}<typeof {name}>", self.zod))
+    }
+
+    fn call_type(&self, call_expression: &CallExpr) -> String {
+        let Callee::Expr(callee) = &call_expression.callee else {
+            return UNKNOWN.to_string();
+        };
+        let Expr::Member(member) = callee.unwrap_parens() else {
+            return UNKNOWN.to_string();
+        };
+        let Some(method) = member.prop.as_ident().map(|name| name.sym.as_ref()) else {
+            return UNKNOWN.to_string();
+        };
+
+        let is_constructor = member
+            .obj
+            .as_ident()
+            .is_some_and(|object| object.sym.as_ref() == self.zod);
+        if is_constructor {
+            self.constructor_type(method, &call_expression.args)
+        } else {
+            self.method_type(&member.obj, method, &call_expression.args)
+        }
+    }
+
+    /// The type of `z.{name}(arguments)`.
+    fn constructor_type(&self, name: &str, arguments: &[ExprOrSpread]) -> String {
+        let argument = |index: usize| arguments.get(index).map(|argument| &*argument.expr);
+        let argument_type = |index: usize| {
+            argument(index).map_or_else(|| UNKNOWN.to_string(), |value| self.type_of(value))
+        };
+
+        match name {
+            "string" | "email" | "url" | "uuid" | "cuid" | "cuid2" | "ulid" => "string".to_string(),
+            "number" | "int" => "number".to_string(),
+            "bigint" | "boolean" | "null" | "undefined" | "void" | "any" | "never" | "symbol" => {
+                name.to_string()
+            }
+            "date" => "Date".to_string(),
+            "object" | "strictObject" | "looseObject" => argument(0)
+                .and_then(Expr::as_object)
+                .map_or_else(|| UNKNOWN.to_string(), |shape| self.object_type(shape)),
+            "array" => format!("Array<{}>", argument_type(0)),
+            "set" => format!("Set<{}>", argument_type(0)),
+            "promise" => format!("Promise<{}>", argument_type(0)),
+            "map" => format!("Map<{}, {}>", argument_type(0), argument_type(1)),
+            "record" if arguments.len() == 1 => format!("Record<string, {}>", argument_type(0)),
+            "record" => format!("Record<{}, {}>", argument_type(0), argument_type(1)),
+            "optional" => format!("{} | undefined", argument_type(0)),
+            "nullable" => format!("{} | null", argument_type(0)),
+            "nullish" => format!("{} | null | undefined", argument_type(0)),
+            "lazy" => argument(0)
+                .and_then(Expr::as_arrow)
+                .and_then(|arrow| match &*arrow.body {
+                    BlockStmtOrExpr::Expr(body) => Some(self.type_of(body)),
+                    BlockStmtOrExpr::BlockStmt(_) => None,
+                })
+                .unwrap_or_else(|| UNKNOWN.to_string()),
+            "literal" => {
+                argument(0).map_or_else(|| UNKNOWN.to_string(), |value| self.literal(value))
+            }
+            "enum" => elements(argument(0), " | ", |value| self.literal(value)),
+            "nativeEnum" => argument(0).and_then(Expr::as_ident).map_or_else(
+                || UNKNOWN.to_string(),
+                |enum_name| enum_name.sym.to_string(),
+            ),
+            "union" => elements(argument(0), " | ", |member| self.type_of(member)),
+            "discriminatedUnion" => elements(argument(1), " | ", |member| self.type_of(member)),
+            "tuple" => format!(
+                "[{}]",
+                elements(argument(0), ", ", |item| self.type_of(item))
+            ),
+            "intersection" => format!(
+                "{} & {}",
+                grouped(&argument_type(0)),
+                grouped(&argument_type(1))
+            ),
+            _ => UNKNOWN.to_string(),
+        }
+    }
+
+    /// The type of `{schema}.{name}(arguments)`.
+    fn method_type(&self, schema: &Expr, name: &str, arguments: &[ExprOrSpread]) -> String {
+        let argument = arguments.first().map(|argument| &*argument.expr);
+        let schema_type = self.type_of(schema);
+        if REFINEMENTS.contains(&name) {
+            return schema_type;
+        }
+
+        match name {
+            "optional" => format!("{schema_type} | undefined"),
+            "nullable" => format!("{schema_type} | null"),
+            "nullish" => format!("{schema_type} | null | undefined"),
+            "array" => format!("Array<{schema_type}>"),
+            "default" | "catch" | "transform" | "pipe" => {
+                self.wrapper_type(name, &schema_type, argument)
+            }
+            "readonly" => format!("Readonly<{schema_type}>"),
+            "partial" => format!("Partial<{schema_type}>"),
+            "required" => format!("Required<{schema_type}>"),
+            "extend" => argument.and_then(Expr::as_object).map_or_else(
+                || UNKNOWN.to_string(),
+                |shape| {
+                    format!(
+                        "Omit<{schema_type}, keyof {0}> & {0}",
+                        self.object_type(shape)
+                    )
+                },
+            ),
+            "merge" | "and" => argument.map_or_else(
+                || UNKNOWN.to_string(),
+                |other| {
+                    let other_type = self.type_of(other);
+                    format!("{} & {}", grouped(&schema_type), grouped(&other_type))
+                },
+            ),
+            "or" => argument.map_or_else(
+                || UNKNOWN.to_string(),
+                |other| format!("{schema_type} | {}", self.type_of(other)),
+            ),
+            "pick" => argument.and_then(Expr::as_object).map_or_else(
+                || UNKNOWN.to_string(),
+                |keys| format!("Pick<{schema_type}, {}>", key_union(keys)),
+            ),
+            "omit" => argument.and_then(Expr::as_object).map_or_else(
+                || UNKNOWN.to_string(),
+                |keys| format!("Omit<{schema_type}, {}>", key_union(keys)),
+            ),
+            _ => UNKNOWN.to_string(),
+        }
+    }
+
+    /// `.default()` and `.catch()` accept `undefined` but never return it, while `.transform()`
+    /// and `.pipe()` accept their schema's input and return something else.
+    fn wrapper_type(&self, name: &str, schema_type: &str, argument: Option<&Expr>) -> String {
+        match (self.side, name) {
+            (Side::Input, "default") => format!("{schema_type} | undefined"),
+            (Side::Input, "transform" | "pipe") => schema_type.to_string(),
+            (Side::Output, "default" | "catch") => format!("Exclude<{schema_type}, undefined>"),
+            (Side::Output, "pipe") => {
+                argument.map_or_else(|| UNKNOWN.to_string(), |target| self.type_of(target))
+            }
+            _ => UNKNOWN.to_string(),
+        }
+    }
+
+    fn object_type(&self, shape: &ObjectLit) -> String {
+        let fields: Option<Vec<String>> = shape
+            .props
+            .iter()
+            .map(|property| self.field_type(property))
+            .collect();
+        fields.map_or_else(
+            || UNKNOWN.to_string(),
+            |fields| format!("{{ {} }}", fields.join("; ")),
+        )
+    }
+
+    /// `name: type`, or `name?: type` for optional fields. `None` for spreads and computed keys.
+    fn field_type(&self, property: &PropOrSpread) -> Option<String> {
+        let PropOrSpread::Prop(property) = property else {
+            return None;
+        };
+        let (key, value) = match &**property {
+            Prop::KeyValue(key_value) => (property_name(&key_value.key)?, &*key_value.value),
+            Prop::Shorthand(identifier) => {
+                let value = self.type_of(&Expr::Ident(identifier.clone()));
+                return Some(format!("{}: {value}", identifier.sym));
+            }
+            _ => return None,
+        };
+
+        // Zod leaves keys that accept `undefined` out of required keys, `unknown` and `any` included.
+        let value_type = self.type_of(value);
+        let marker =
+            if value_type.ends_with("| undefined") || [UNKNOWN, "any"].contains(&&*value_type) {
+                "?"
+            } else {
+                ""
+            };
+        Some(format!("{key}{marker}: {value_type}"))
+    }
+
+    /// A literal's own text is its type, e.g. `'active'`, `5` or `Role.Admin`.
+    fn literal(&self, value: &Expr) -> String {
+        match value.unwrap_parens() {
+            Expr::Lit(Lit::Str(_) | Lit::Num(_) | Lit::Bool(_) | Lit::Null(_))
+            | Expr::Member(_) => self.file.get_source_text(swc_common::Spanned::span(value)),
+            _ => UNKNOWN.to_string(),
+        }
+    }
+}
+
+/// The array literal's elements rendered and joined, `never` when there are none, or `unknown`
+/// when `array` isn't an array literal.
+fn elements(array: Option<&Expr>, separator: &str, render: impl Fn(&Expr) -> String) -> String {
+    let Some(array) = array.and_then(Expr::as_array) else {
+        return UNKNOWN.to_string();
+    };
+    let rendered: Vec<String> = array
+        .elems
+        .iter()
+        .flatten()
+        .map(|element| render(&element.expr))
+        .collect();
+    if rendered.is_empty() {
+        "never".to_string()
+    } else {
+        rendered.join(separator)
+    }
+}
+
+/// Parenthesizes unions, so they keep their meaning next to `&`.
+fn grouped(type_text: &str) -> String {
+    if type_text.contains(" | ") {
+        format!("({type_text})")
+    } else {
+        type_text.to_string()
+    }
+}
+
+fn property_name(key: &PropName) -> Option<String> {
+    match key {
+        PropName::Ident(identifier) => Some(identifier.sym.to_string()),
+        PropName::Str(string) => Some(format!("{:?}", string.value.to_string_lossy())),
+        PropName::Num(number) => Some(number.value.to_string()),
+        _ => None,
+    }
+}
+
+/// `"id" | "name"` for `{ id: true, name: true }`.
+fn key_union(keys: &ObjectLit) -> String {
+    let keys: Vec<String> = keys
+        .props
+        .iter()
+        .filter_map(property_key)
+        .map(|key| format!("{key:?}"))
+        .collect();
+    if keys.is_empty() {
+        "never".to_string()
+    } else {
+        keys.join(" | ")
+    }
+}
+
+/// The key of `{ key: true }` in `.pick({ key: true })`.
+fn property_key(property: &PropOrSpread) -> Option<String> {
+    let PropOrSpread::Prop(property) = property else {
+        return None;
+    };
+    match &**property {
+        Prop::KeyValue(key_value) => match &key_value.key {
+            PropName::Ident(identifier) => Some(identifier.sym.to_string()),
+            PropName::Str(string) => Some(string.value.to_string_lossy().into_owned()),
+            _ => None,
+        },
+        Prop::Shorthand(identifier) => Some(identifier.sym.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_typescript_source;
+    use swc_ecma_ast::Stmt;
+
+    fn schema_type(schema: &str, side: Side) -> String {
+        let parsed_file =
+            parse_typescript_source(&format!("({schema});")).expect("Failed to parse");
+        let expression = &parsed_file.module.body[0]
+            .as_stmt()
+            .and_then(Stmt::as_expr)
+            .expect("Expected an expression")
+            .expr;
+        let aliases = HashMap::from([("treeSchema".to_string(), "Tree".to_string())]);
+        SchemaTypes::new(&parsed_file, "z", &aliases, side).type_of(expression)
+    }
+
+    fn output_type(schema: &str) -> String {
+        schema_type(schema, Side::Output)
+    }
+
+    #[test]
+    fn test_object_fields_are_optional_when_their_schema_is() {
+        assert_eq!(
+            output_type("z.object({ id: z.string().uuid(), note: z.string().nullish(), age: z.number().int().optional() })"),
+            "{ id: string; note?: string | null | undefined; age?: number | undefined }"
+        );
+    }
+
+    #[test]
+    fn test_recursive_references_use_their_alias() {
+        assert_eq!(
+            output_type(
+                "z.object({ children: z.lazy(() => treeSchema.array()), owner: userSchema })"
+            ),
+            "{ children: Array<Tree>; owner: z.infer<typeof userSchema> }"
+        );
+    }
+
+    #[test]
+    fn test_literals_unions_and_intersections() {
+        assert_eq!(
+            output_type("z.enum(['a', 'b']).or(z.literal(5))"),
+            "'a' | 'b' | 5"
+        );
+        assert_eq!(
+            output_type("z.intersection(z.union([z.string(), z.null()]), z.object({ id: z.number().default(1) }))"),
+            "(string | null) & { id: Exclude<number, undefined> }"
+        );
+    }
+
+    #[test]
+    fn test_unknown_constructs_are_unknown() {
+        assert_eq!(output_type("z.custom<string>()"), UNKNOWN);
+        assert_eq!(output_type("z.object({ ...base })"), UNKNOWN);
+    }
+
+    #[test]
+    fn test_input_types_accept_what_defaults_and_transforms_replace() {
+        let schema = "z.object({ title: z.string().default('Untitled'), pinned: z.boolean().catch(false), slug: z.string().transform((value) => value.length) })";
+        assert_eq!(
+            schema_type(schema, Side::Input),
+            "{ title?: string | undefined; pinned?: unknown; slug: string }"
+        );
+        assert_eq!(
+            output_type(schema),
+            "{ title: Exclude<string, undefined>; pinned: Exclude<boolean, undefined>; slug?: unknown }"
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use tracing::{debug, warn};
+
+use super::GeneratedFile;
+use crate::parser::middleware::extract_middleware_names_from_class;
+use crate::parser::procedure::find_class_by_name;
+use crate::{
+    build_imports_map, extract_trpc_options, resolve_context_file, ContextInfo, ContextParser,
+    MiddlewareInfo, MiddlewareParser, ParsedFile, ResolvedImport, RouterMetadata, StaticGenerator,
+    TsParser, TypesGenerator,
+};
+
+pub(super) const TYPES_FILE_NAME: &str = "types.ts";
+
+/// Context and middleware types referenced by the `TRPCModule` and its routers.
+#[derive(Debug, Default)]
+pub(super) struct ModuleTypes {
+    pub context: Option<ContextInfo>,
+    pub middlewares: Vec<MiddlewareInfo>,
+    pub global_middleware_names: Vec<String>,
+}
+
+impl ModuleTypes {
+    fn is_empty(&self) -> bool {
+        self.context.is_none() && self.middlewares.is_empty()
+    }
+}
+
+pub(super) fn collect_module_types(
+    typescript_parser: &TsParser,
+    root_module: Option<&Path>,
+    parsed_files: &[ParsedFile],
+    routers: &[RouterMetadata],
+    base_directory: &Path,
+) -> ModuleTypes {
+    let parsed_module = root_module.and_then(|path| parse_or_warn(typescript_parser, path));
+    let options = parsed_module.as_ref().and_then(extract_trpc_options);
+
+    let context = parsed_module.as_ref().and_then(|parsed_module| {
+        let class_name = options.as_ref()?.context_class_name.as_deref()?;
+        collect_context(typescript_parser, parsed_module, class_name)
+    });
+
+    let mut seen = HashSet::new();
+    let mut middlewares = Vec::new();
+
+    if let (Some(parsed_module), Some(options)) = (&parsed_module, &options) {
+        collect_middlewares_from_file(
+            typescript_parser,
+            parsed_module,
+            &options.global_middleware_names,
+            base_directory,
+            &mut seen,
+            &mut middlewares,
+        );
+    }
+
+    for router in routers {
+        let Some(parsed_file) = parsed_files
+            .iter()
+            .find(|parsed_file| parsed_file.file_path == router.file_path)
+        else {
+            continue;
+        };
+        let Some(class) = find_class_by_name(&parsed_file.module.body, &router.name) else {
+            continue;
+        };
+
+        collect_middlewares_from_file(
+            typescript_parser,
+            parsed_file,
+            &extract_middleware_names_from_class(class),
+            base_directory,
+            &mut seen,
+            &mut middlewares,
+        );
+    }
+
+    debug!(
+        context = ?context.as_ref().map(|context| &context.class_name),
+        middlewares = middlewares.len(),
+        "Collected module types"
+    );
+
+    ModuleTypes {
+        context,
+        middlewares,
+        global_middleware_names: options
+            .map(|options| options.global_middleware_names)
+            .unwrap_or_default(),
+    }
+}
+
+fn collect_context(
+    typescript_parser: &TsParser,
+    parsed_module: &ParsedFile,
+    class_name: &str,
+) -> Option<ContextInfo> {
+    let context_parser = ContextParser::new();
+
+    let Some(context_path) = resolve_context_file(parsed_module, class_name) else {
+        return context_parser.extract_context(parsed_module, class_name);
+    };
+
+    let parsed_context = parse_or_warn(typescript_parser, &context_path)?;
+    context_parser.extract_context(&parsed_context, class_name)
+}
+
+fn collect_middlewares_from_file(
+    typescript_parser: &TsParser,
+    parsed_file: &ParsedFile,
+    names: &[String],
+    base_directory: &Path,
+    seen: &mut HashSet<String>,
+    middlewares: &mut Vec<MiddlewareInfo>,
+) {
+    let pending: Vec<&String> = names
+        .iter()
+        .filter(|name| seen.insert((*name).clone()))
+        .collect();
+    if pending.is_empty() {
+        return;
+    }
+
+    let imports_map =
+        build_imports_map(typescript_parser, parsed_file, base_directory).unwrap_or_default();
+    let middleware_parser = MiddlewareParser::new();
+
+    for name in pending {
+        let middleware = imports_map.get(name.as_str()).map_or_else(
+            || middleware_parser.extract_middleware(parsed_file, name),
+            |resolved| extract_imported_middleware(typescript_parser, resolved),
+        );
+
+        let Some(middleware) = middleware else {
+            warn!(middleware = %name, "Could not resolve middleware class");
+            continue;
+        };
+        middlewares.push(middleware);
+    }
+}
+
+fn extract_imported_middleware(
+    typescript_parser: &TsParser,
+    resolved: &ResolvedImport,
+) -> Option<MiddlewareInfo> {
+    let parsed = parse_or_warn(typescript_parser, &resolved.source_file)?;
+    MiddlewareParser::new().extract_middleware(&parsed, &resolved.name)
+}
+
+fn parse_or_warn(typescript_parser: &TsParser, path: &Path) -> Option<ParsedFile> {
+    typescript_parser
+        .parse_file(path)
+        .map_err(|error| warn!(path = ?path, error = %error, "Failed to parse file"))
+        .ok()
+}
+
+/// Renders `types.ts` for `output_directory` when there is a context or middleware to describe.
+pub(super) fn render_types_file(
+    output_directory: &Path,
+    module_types: &ModuleTypes,
+    static_generator: StaticGenerator,
+) -> Option<GeneratedFile> {
+    if module_types.is_empty() {
+        return None;
+    }
+
+    let types_generator = TypesGenerator::with_static_generator(static_generator);
+    Some(GeneratedFile {
+        path: output_directory.join(TYPES_FILE_NAME),
+        content: types_generator.generate(module_types.context.as_ref(), &module_types.middlewares),
+    })
+}
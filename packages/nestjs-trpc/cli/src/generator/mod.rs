@@ -1,3 +1,4 @@
+pub mod router_tree;
 pub mod server;
 pub mod types;
 
@@ -6,10 +7,19 @@ use std::fmt::Write;
 use std::path::Path;
 
 use crate::parser::module::TransformerInfo;
+use crate::parser::schema::{ValidatorImport, ValidatorImportKind};
 
+pub use router_tree::{router_path, RouterTree};
 pub use server::{generate_server_file, ServerGenerator};
 pub use types::{generate_types_file, TypesGenerator};
 
+/// Heads every file the CLI writes, so files from earlier runs can be told apart from
+/// hand-written ones.
+pub(crate) const GENERATED_FILE_MARKER: &str = "AUTO-GENERATED FILE - DO NOT EDIT!";
+
+/// Matches the `meta` option type accepted by the runtime procedure decorators.
+pub(crate) const DEFAULT_META_TYPE: &str = "Record<string, unknown>";
+
 pub type GeneratorResult<T> = std::result::Result<T, crate::error::GeneratorError>;
 
 #[derive(Debug, Clone)]
@@ -21,6 +31,11 @@ pub struct StaticGenerator {
     pub(crate) import_extension: bool,
 
     pub(crate) transformer: Option<TransformerInfo>,
+
+    pub(crate) meta_type: Option<String>,
+
+    /// Validator bindings the schemas use; `None` keeps the default `import { z } from 'zod'`.
+    pub(crate) validator_imports: Option<Vec<ValidatorImport>>,
 }
 
 impl Default for StaticGenerator {
@@ -37,6 +52,8 @@ impl StaticGenerator {
             use_semicolons: true,
             import_extension: false,
             transformer: None,
+            meta_type: None,
+            validator_imports: None,
         }
     }
 
@@ -64,6 +81,28 @@ impl StaticGenerator {
         self
     }
 
+    #[must_use]
+    pub fn with_meta_type(mut self, meta_type: Option<String>) -> Self {
+        self.meta_type = meta_type;
+        self
+    }
+
+    #[must_use]
+    pub fn with_validator_imports(mut self, validator_imports: Vec<ValidatorImport>) -> Self {
+        self.validator_imports = Some(validator_imports);
+        self
+    }
+
+    /// Whether `name` is emitted as a validator import rather than a schema import.
+    #[must_use]
+    pub fn is_validator_import(&self, name: &str) -> bool {
+        self.validator_imports
+            .as_ref()
+            .map_or(name == "z", |imports| {
+                imports.iter().any(|import| import.local_name == name)
+            })
+    }
+
     const fn quote(&self) -> char {
         if self.use_single_quotes {
             '\''
@@ -91,7 +130,7 @@ impl StaticGenerator {
             output,
             "import {{ initTRPC }} from {q}@trpc/server{q}{term}"
         );
-        let _ = writeln!(output, "import {{ z }} from {q}zod{q}{term}");
+        output.push_str(&self.generate_validator_imports());
 
         if let Some(transformer) = &self.transformer {
             let _ = writeln!(output, "{}", self.generate_transformer_import(transformer));
@@ -119,14 +158,84 @@ impl StaticGenerator {
         format!("import {{ z }} from {q}zod{q}{term}")
     }
 
+    /// Imports the validator bindings the schemas use, grouping named bindings from the same
+    /// package into one statement.
+    #[must_use]
+    pub fn generate_validator_imports(&self) -> String {
+        let Some(validator_imports) = &self.validator_imports else {
+            return format!("{}\n", self.generate_zod_import());
+        };
+
+        let mut seen_modules: HashSet<&str> = HashSet::new();
+        let mut module_order: Vec<&str> = validator_imports
+            .iter()
+            .map(|import| import.module_specifier.as_str())
+            .collect();
+        module_order.retain(|module_specifier| seen_modules.insert(module_specifier));
+
+        module_order
+            .into_iter()
+            .map(|module_specifier| {
+                let imports: Vec<&ValidatorImport> = validator_imports
+                    .iter()
+                    .filter(|import| import.module_specifier == module_specifier)
+                    .collect();
+                self.render_validator_module_imports(module_specifier, &imports)
+            })
+            .collect()
+    }
+
+    fn render_validator_module_imports(
+        &self,
+        module_specifier: &str,
+        imports: &[&ValidatorImport],
+    ) -> String {
+        let q = self.quote();
+        let term = self.terminator();
+
+        let named: Vec<String> = imports
+            .iter()
+            .filter_map(|import| match &import.kind {
+                ValidatorImportKind::Named(imported) if *imported == import.local_name => {
+                    Some(imported.clone())
+                }
+                ValidatorImportKind::Named(imported) => {
+                    Some(format!("{imported} as {}", import.local_name))
+                }
+                _ => None,
+            })
+            .collect();
+        let clauses = imports.iter().filter_map(|import| match import.kind {
+            ValidatorImportKind::Named(_) => None,
+            ValidatorImportKind::Namespace => Some(format!("* as {}", import.local_name)),
+            ValidatorImportKind::Default => Some(import.local_name.clone()),
+        });
+
+        (!named.is_empty())
+            .then(|| format!("{{ {} }}", named.join(", ")))
+            .into_iter()
+            .chain(clauses)
+            .fold(String::new(), |mut output, clause| {
+                let _ = writeln!(
+                    output,
+                    "import {clause} from {q}{module_specifier}{q}{term}"
+                );
+                output
+            })
+    }
+
     #[must_use]
     pub fn generate_t_declaration(&self) -> String {
         let term = self.terminator();
+        let builder = self.meta_type.as_ref().map_or_else(
+            || "initTRPC".to_string(),
+            |meta_type| format!("initTRPC.meta<{meta_type}>()"),
+        );
         self.transformer.as_ref().map_or_else(
-            || format!("const t = initTRPC.create(){term}"),
+            || format!("const t = {builder}.create(){term}"),
             |transformer| {
                 let name = &transformer.import_name;
-                format!("const t = initTRPC.create({{ transformer: {name} }}){term}")
+                format!("const t = {builder}.create({{ transformer: {name} }}){term}")
             },
         )
     }
@@ -159,7 +268,9 @@ impl StaticGenerator {
     where
         I: IntoIterator<Item = &'a str>,
     {
-        self.render_named_imports("import", schema_names, schema_locations, output_file_path)
+        self.render_imports(schema_names, schema_locations, output_file_path, |names| {
+            vec![format!("import {{ {} }}", names.join(", "))]
+        })
     }
 
     pub fn generate_type_imports<'a, I>(
@@ -171,15 +282,37 @@ impl StaticGenerator {
     where
         I: IntoIterator<Item = &'a str>,
     {
-        self.render_named_imports("import type", type_names, type_locations, output_file_path)
+        self.render_imports(type_names, type_locations, output_file_path, |names| {
+            vec![format!("import type {{ {} }}", names.join(", "))]
+        })
+    }
+
+    /// `import type Name from '...'` for each name, the form default exports are imported with.
+    pub fn generate_default_type_imports<'a, I>(
+        &self,
+        type_names: I,
+        type_locations: &std::collections::HashMap<String, std::path::PathBuf>,
+        output_file_path: &Path,
+    ) -> String
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        self.render_imports(type_names, type_locations, output_file_path, |names| {
+            names
+                .iter()
+                .map(|name| format!("import type {name}"))
+                .collect()
+        })
     }
 
-    fn render_named_imports<'a, I>(
+    /// One import per source file of `names`, or several when `clauses`, which gets the names
+    /// each file provides, returns more than one `import ...` clause for it.
+    fn render_imports<'a, I>(
         &self,
-        keyword: &str,
         names: I,
         locations: &std::collections::HashMap<String, std::path::PathBuf>,
         output_file_path: &Path,
+        clauses: impl Fn(&[&str]) -> Vec<String>,
     ) -> String
     where
         I: IntoIterator<Item = &'a str>,
@@ -206,12 +339,11 @@ impl StaticGenerator {
 
         path_order
             .iter()
-            .filter_map(|path| {
-                let names = names_by_path.get(path)?;
-                let names_joined = names.join(", ");
-                Some(format!(
-                    "{keyword} {{ {names_joined} }} from {q}{path}{q}{term}\n"
-                ))
+            .filter_map(|path| Some((path, names_by_path.get(path)?)))
+            .flat_map(|(path, names)| {
+                clauses(names)
+                    .into_iter()
+                    .map(move |clause| format!("{clause} from {q}{path}{q}{term}\n"))
             })
             .collect()
     }
@@ -354,6 +486,42 @@ mod tests {
         assert_eq!(output, "import { z } from \"zod\";");
     }
 
+    #[test]
+    fn test_generate_validator_imports_groups_by_package() {
+        let validator_import = |local_name: &str, kind, module_specifier: &str| ValidatorImport {
+            local_name: local_name.to_string(),
+            kind,
+            module_specifier: module_specifier.to_string(),
+        };
+        let generator = StaticGenerator::new()
+            .with_single_quotes(true)
+            .with_validator_imports(vec![
+                validator_import("v", ValidatorImportKind::Namespace, "valibot"),
+                validator_import("object", ValidatorImportKind::Named("object".into()), "yup"),
+                validator_import("str", ValidatorImportKind::Named("string".into()), "yup"),
+                validator_import("yup", ValidatorImportKind::Default, "yup"),
+            ]);
+
+        assert_eq!(
+            generator.generate_validator_imports(),
+            "import * as v from 'valibot';\n\
+             import { object, string as str } from 'yup';\n\
+             import yup from 'yup';\n"
+        );
+        assert!(generator.is_validator_import("str"));
+        assert!(!generator.is_validator_import("z"));
+    }
+
+    #[test]
+    fn test_generate_static_declarations_without_validators() {
+        let output = StaticGenerator::new()
+            .with_validator_imports(Vec::new())
+            .generate_static_declarations();
+
+        assert!(!output.contains("zod"));
+        assert!(output.starts_with("import { initTRPC } from \"@trpc/server\";\n\n"));
+    }
+
     #[test]
     fn test_generate_t_declaration() {
         let generator = StaticGenerator::new();
@@ -361,6 +529,32 @@ mod tests {
         assert_eq!(output, "const t = initTRPC.create();");
     }
 
+    #[test]
+    fn test_generate_t_declaration_with_meta() {
+        let generator = StaticGenerator::new().with_meta_type(Some(DEFAULT_META_TYPE.to_string()));
+        let output = generator.generate_t_declaration();
+        assert_eq!(
+            output,
+            "const t = initTRPC.meta<Record<string, unknown>>().create();"
+        );
+    }
+
+    #[test]
+    fn test_generate_t_declaration_with_meta_and_transformer() {
+        let generator = StaticGenerator::new()
+            .with_meta_type(Some(DEFAULT_META_TYPE.to_string()))
+            .with_transformer(Some(TransformerInfo {
+                package_name: "superjson".to_string(),
+                import_name: "superjson".to_string(),
+                is_default_import: true,
+            }));
+        let output = generator.generate_t_declaration();
+        assert_eq!(
+            output,
+            "const t = initTRPC.meta<Record<string, unknown>>().create({ transformer: superjson });"
+        );
+    }
+
     #[test]
     fn test_generate_public_procedure_declaration() {
         let generator = StaticGenerator::new();
@@ -0,0 +1,275 @@
+use crate::error::GeneratorError;
+use crate::generator::GeneratorResult;
+use crate::{ProcedureMetadata, RouterMetadata};
+
+/// One level of `appRouter`: the procedures of every router aliased to this path, followed by
+/// the sub-routers nested below it, both in the order they were first seen.
+#[derive(Debug, Default)]
+pub struct RouterTree<'a> {
+    pub procedures: Vec<&'a ProcedureMetadata>,
+    pub children: Vec<(String, Self)>,
+    router_names: Vec<&'a str>,
+    /// The router declaring each of `procedures`.
+    procedure_routers: Vec<&'a str>,
+}
+
+impl<'a> RouterTree<'a> {
+    /// Nests routers by their alias path, merging routers that share a path.
+    #[must_use]
+    pub fn from_routers(routers: &'a [RouterMetadata]) -> Self {
+        let mut root = Self::default();
+
+        for router in routers {
+            let node = router_path(router)
+                .into_iter()
+                .fold(&mut root, |node, segment| node.child(segment));
+            node.procedures.extend(&router.procedures);
+            node.procedure_routers
+                .extend(router.procedures.iter().map(|_| router.name.as_str()));
+            node.router_names.push(&router.name);
+        }
+
+        root
+    }
+
+    fn child(&mut self, segment: String) -> &mut Self {
+        let index = self
+            .children
+            .iter()
+            .position(|(key, _)| *key == segment)
+            .unwrap_or_else(|| {
+                self.children.push((segment, Self::default()));
+                self.children.len() - 1
+            });
+        &mut self.children[index].1
+    }
+
+    /// Fails when a path segment names both a procedure and a sub-router, or when routers merged
+    /// under one alias declare the same procedure, neither of which tRPC can merge.
+    pub fn check_conflicts(&self) -> GeneratorResult<()> {
+        self.find_conflict(&[]).map_or(Ok(()), Err)
+    }
+
+    fn find_conflict(&self, path: &[&str]) -> Option<GeneratorError> {
+        self.find_duplicate_procedure(path)
+            .or_else(|| self.find_child_conflict(path))
+    }
+
+    fn find_duplicate_procedure(&self, path: &[&str]) -> Option<GeneratorError> {
+        self.procedures
+            .iter()
+            .enumerate()
+            .find_map(|(index, procedure)| {
+                let first = self.procedures[..index]
+                    .iter()
+                    .position(|earlier| earlier.name == procedure.name)?;
+                Some(GeneratorError::DuplicateProcedure {
+                    path: [path, &[procedure.name.as_str()]].concat().join("."),
+                    first_router: self.procedure_routers[first].to_string(),
+                    second_router: self.procedure_routers[index].to_string(),
+                })
+            })
+    }
+
+    fn find_child_conflict(&self, path: &[&str]) -> Option<GeneratorError> {
+        self.children.iter().find_map(|(key, child)| {
+            let child_path = [path, &[key.as_str()]].concat();
+            let is_procedure = self
+                .procedures
+                .iter()
+                .any(|procedure| procedure.name == *key);
+
+            is_procedure
+                .then(|| GeneratorError::RouterPathConflict {
+                    path: child_path.join("."),
+                    routers: self.router_names.join(", "),
+                })
+                .or_else(|| child.find_conflict(&child_path))
+        })
+    }
+}
+
+/// The keys a router is nested under: its alias split on `.` or `/`, e.g. `admin.users` or
+/// `admin/users`, falling back to the class name, with every key camel-cased the way the
+/// runtime keys routers.
+#[must_use]
+pub fn router_path(router: &RouterMetadata) -> Vec<String> {
+    let segments: Vec<String> = router
+        .alias
+        .iter()
+        .flat_map(|alias| alias.split(['.', '/']))
+        .map(camel_case)
+        .filter(|segment| !segment.is_empty())
+        .collect();
+
+    if segments.is_empty() {
+        return vec![camel_case(&router.name)];
+    }
+    segments
+}
+
+/// Mirrors lodash's `camelCase`, e.g. `user-profile` and `User Profile` become `userProfile`.
+fn camel_case(text: &str) -> String {
+    text.split(|character: char| !character.is_alphanumeric())
+        .flat_map(split_words)
+        .filter(|word| !word.is_empty())
+        .enumerate()
+        .map(|(index, word)| {
+            let word = word.to_lowercase();
+            if index == 0 {
+                return word;
+            }
+            let mut characters = word.chars();
+            characters.next().map_or_else(String::new, |first| {
+                first.to_uppercase().chain(characters).collect()
+            })
+        })
+        .collect()
+}
+
+/// Splits `chunk` where its case or digits change, e.g. `XMLHttpRequest2` into `XML`, `Http`,
+/// `Request` and `2`.
+fn split_words(chunk: &str) -> Vec<&str> {
+    let characters: Vec<(usize, char)> = chunk.char_indices().collect();
+    let boundaries = (1..characters.len()).filter(|&index| {
+        let previous = characters[index - 1].1;
+        let current = characters[index].1;
+        let next = characters.get(index + 1).map(|&(_, character)| character);
+        (previous.is_lowercase() && current.is_uppercase())
+            || (previous.is_uppercase()
+                && current.is_uppercase()
+                && next.is_some_and(char::is_lowercase))
+            || previous.is_numeric() != current.is_numeric()
+    });
+
+    let mut start = 0;
+    let mut words: Vec<&str> = boundaries
+        .map(|index| {
+            let offset = characters[index].0;
+            let word = &chunk[start..offset];
+            start = offset;
+            word
+        })
+        .collect();
+    words.push(&chunk[start..]);
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProcedureType;
+    use std::collections::BTreeSet;
+    use std::path::PathBuf;
+
+    fn create_router(name: &str, alias: Option<&str>, procedures: &[&str]) -> RouterMetadata {
+        RouterMetadata {
+            name: name.to_string(),
+            alias: alias.map(str::to_string),
+            file_path: PathBuf::from("test.router.ts"),
+            procedures: procedures
+                .iter()
+                .map(|procedure| ProcedureMetadata {
+                    name: (*procedure).to_string(),
+                    procedure_type: ProcedureType::Query,
+                    input_schema: None,
+                    output_schema: None,
+                    input_schema_ref: None,
+                    output_schema_ref: None,
+                    schema_identifiers: Vec::new(),
+                    output_inference: None,
+                    meta: None,
+                    middlewares: Vec::new(),
+                    hoisted_declarations: Vec::new(),
+                    schema_sources: BTreeSet::new(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_router_path_splits_dots_and_slashes() {
+        let dotted = create_router("UsersRouter", Some("admin.users"), &[]);
+        let slashed = create_router("UsersRouter", Some("/admin/users/"), &[]);
+        let unaliased = create_router("UsersRouter", None, &[]);
+
+        assert_eq!(router_path(&dotted), vec!["admin", "users"]);
+        assert_eq!(router_path(&slashed), vec!["admin", "users"]);
+        assert_eq!(router_path(&unaliased), vec!["usersRouter"]);
+    }
+
+    #[test]
+    fn test_router_path_camel_cases_like_the_runtime() {
+        let paths = [
+            (Some("user-profile"), vec!["userProfile"]),
+            (Some("User Profile"), vec!["userProfile"]),
+            (Some("admin.user_settings"), vec!["admin", "userSettings"]),
+            (Some("v2/APIKeys"), vec!["v2", "apiKeys"]),
+            (None, vec!["xmlHttpRouter"]),
+        ];
+
+        for (alias, expected) in paths {
+            let router = create_router("XMLHttpRouter", alias, &[]);
+            assert_eq!(router_path(&router), expected, "{alias:?}");
+        }
+    }
+
+    #[test]
+    fn test_nests_and_merges_siblings() {
+        let routers = vec![
+            create_router("AdminUsersRouter", Some("admin.users"), &["list"]),
+            create_router("AdminRouter", Some("admin"), &["stats"]),
+            create_router("AdminPostsRouter", Some("admin/posts"), &["list"]),
+            create_router("MoreAdminUsersRouter", Some("admin.users"), &["ban"]),
+        ];
+
+        let tree = RouterTree::from_routers(&routers);
+
+        assert_eq!(tree.children.len(), 1);
+        let (key, admin) = &tree.children[0];
+        assert_eq!(key, "admin");
+        assert_eq!(admin.procedures.len(), 1);
+        let keys: Vec<&str> = admin.children.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, vec!["users", "posts"]);
+        assert_eq!(admin.children[0].1.procedures.len(), 2);
+        assert!(tree.check_conflicts().is_ok());
+    }
+
+    #[test]
+    fn test_detects_procedure_and_sub_router_conflict() {
+        let routers = vec![
+            create_router("AdminRouter", Some("admin"), &["users"]),
+            create_router("AdminUsersRouter", Some("admin.users"), &["list"]),
+        ];
+
+        let error = RouterTree::from_routers(&routers)
+            .check_conflicts()
+            .unwrap_err();
+
+        assert!(matches!(
+            error,
+            GeneratorError::RouterPathConflict { ref path, ref routers }
+                if path == "admin.users" && routers == "AdminRouter"
+        ));
+    }
+
+    #[test]
+    fn test_detects_duplicate_procedure_across_merged_routers() {
+        let routers = vec![
+            create_router("UsersRouter", Some("users"), &["list", "get"]),
+            create_router("MoreUsersRouter", Some("users"), &["list"]),
+        ];
+
+        let error = RouterTree::from_routers(&routers)
+            .check_conflicts()
+            .unwrap_err();
+
+        assert!(matches!(
+            error,
+            GeneratorError::DuplicateProcedure { ref path, ref first_router, ref second_router }
+                if path == "users.list"
+                    && first_router == "UsersRouter"
+                    && second_router == "MoreUsersRouter"
+        ));
+    }
+}
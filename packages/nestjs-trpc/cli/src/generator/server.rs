@@ -1,6 +1,12 @@
-use crate::generator::StaticGenerator;
-use crate::{OutputInference, ProcedureMetadata, RouterMetadata};
-use std::collections::HashMap;
+use crate::config::UntypedOutput;
+use crate::generator::{RouterTree, StaticGenerator, DEFAULT_META_TYPE};
+use crate::parser::middleware::MiddlewareInfo;
+use crate::{
+    HoistedDeclaration, HoistedKind, OutputInference, ProcedureMetadata, ProcedureType,
+    RouterMetadata,
+};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
 use std::path::{Path, PathBuf};
 
 const GENERATED_FILE_HEADER: &str = "\
@@ -15,11 +21,18 @@ const GENERATED_FILE_HEADER: &str = "\
  */
 ";
 
+/// Declared in `server.ts` when a subscription's output is inferred from its method.
+pub(crate) const SUBSCRIPTION_EVENT_TYPE: &str = "SubscriptionEvent";
+
 #[derive(Debug, Clone)]
 pub struct ServerGenerator {
     static_generator: StaticGenerator,
 
     indent: String,
+
+    middlewares: Vec<MiddlewareInfo>,
+
+    untyped_output: UntypedOutput,
 }
 
 impl Default for ServerGenerator {
@@ -34,6 +47,8 @@ impl ServerGenerator {
         Self {
             static_generator: StaticGenerator::new(),
             indent: "  ".to_string(),
+            middlewares: Vec::new(),
+            untyped_output: UntypedOutput::default(),
         }
     }
 
@@ -49,6 +64,20 @@ impl ServerGenerator {
         self
     }
 
+    /// Sets the middlewares whose ctx properties are typed on the generated procedure builders.
+    #[must_use]
+    pub fn with_middlewares(mut self, middlewares: Vec<MiddlewareInfo>) -> Self {
+        self.middlewares = middlewares;
+        self
+    }
+
+    /// Sets the type of outputs that have no schema and can't be inferred from their router.
+    #[must_use]
+    pub const fn with_untyped_output(mut self, untyped_output: UntypedOutput) -> Self {
+        self.untyped_output = untyped_output;
+        self
+    }
+
     #[must_use]
     pub const fn static_generator(&self) -> &StaticGenerator {
         &self.static_generator
@@ -61,7 +90,9 @@ impl ServerGenerator {
         output.push_str(GENERATED_FILE_HEADER);
         output.push('\n');
 
-        output.push_str(&self.static_generator.generate_static_declarations());
+        output.push_str(&self.generate_static_declarations(routers));
+
+        self.append_procedure_builders(&mut output, routers);
 
         output.push('\n');
 
@@ -86,12 +117,13 @@ impl ServerGenerator {
         output.push_str(GENERATED_FILE_HEADER);
         output.push('\n');
 
-        output.push_str(&self.static_generator.generate_static_declarations());
+        output.push_str(&self.generate_static_declarations(routers));
 
         let schema_names: Vec<&str> = routers
             .iter()
             .flat_map(|r| &r.procedures)
             .flat_map(Self::extract_schema_refs)
+            .filter(|name| !self.static_generator.is_validator_import(name))
             .collect();
 
         self.append_schema_imports(
@@ -103,6 +135,12 @@ impl ServerGenerator {
 
         self.append_output_inference_imports(&mut output, routers, output_file_path);
 
+        self.append_subscription_event_type(&mut output, routers);
+
+        self.append_hoisted_declarations(&mut output, routers);
+
+        self.append_procedure_builders(&mut output, routers);
+
         output.push('\n');
 
         output.push_str(&self.generate_app_router(routers));
@@ -122,10 +160,11 @@ impl ServerGenerator {
             return format!("const appRouter = t.router({{}}){term}\n");
         }
 
-        let merged = merge_routers_by_key(routers);
-        let router_strings: Vec<String> = merged
+        let tree = RouterTree::from_routers(routers);
+        let router_strings: Vec<String> = tree
+            .children
             .iter()
-            .map(|(key, procedures)| self.generate_merged_router_string(key, procedures, 1))
+            .map(|(key, node)| self.generate_tree_router_string(key, node, 1))
             .collect();
 
         let routers_content = router_strings.join(",\n");
@@ -133,27 +172,34 @@ impl ServerGenerator {
         format!("const appRouter = t.router({{\n{routers_content}\n}}){term}\n")
     }
 
-    fn generate_merged_router_string(
+    /// Renders a router's procedures followed by its nested sub-routers.
+    fn generate_tree_router_string(
         &self,
         router_key: &str,
-        procedures: &[&ProcedureMetadata],
+        node: &RouterTree<'_>,
         depth: usize,
     ) -> String {
         let indent = self.indent.repeat(depth);
         let inner_indent = self.indent.repeat(depth + 1);
 
-        if procedures.is_empty() {
+        if node.procedures.is_empty() && node.children.is_empty() {
             return format!("{indent}{router_key}: t.router({{}})");
         }
 
-        let procedure_strings: Vec<String> = procedures
+        let entry_strings: Vec<String> = node
+            .procedures
             .iter()
             .map(|procedure| self.generate_procedure_string(procedure, depth + 1))
+            .chain(
+                node.children
+                    .iter()
+                    .map(|(key, child)| self.generate_tree_router_string(key, child, depth + 1)),
+            )
             .collect();
 
-        let procedures_content = procedure_strings.join(",\n");
+        let entries_content = entry_strings.join(",\n");
 
-        format!("{indent}{router_key}: t.router({{\n{procedures_content}\n{inner_indent}}})")
+        format!("{indent}{router_key}: t.router({{\n{entries_content}\n{inner_indent}}})")
     }
 
     #[must_use]
@@ -188,7 +234,13 @@ impl ServerGenerator {
 
         let mut chain_parts: Vec<String> = Vec::new();
 
-        chain_parts.push(format!("{indent}{}: publicProcedure", procedure.name));
+        let builder = procedure_builder_name(&procedure.middlewares);
+        let key = object_key(&procedure.name, self.quote());
+        chain_parts.push(format!("{indent}{key}: {builder}"));
+
+        if let Some(meta) = &procedure.meta {
+            chain_parts.push(format!("{chain_indent}.meta({meta})"));
+        }
 
         if let Some(input) = &procedure.input_schema {
             chain_parts.push(format!("{chain_indent}.input({input})"));
@@ -207,24 +259,170 @@ impl ServerGenerator {
         chain_parts.join("\n")
     }
 
+    /// The type a subscription method yields or emits, whether it is an async generator (or
+    /// returns any async iterable) or returns an `Observable`. `tracked()` envelopes are kept,
+    /// since tRPC turns them into `{ id, data }` on the client.
+    fn append_subscription_event_type(&self, output: &mut String, routers: &[RouterMetadata]) {
+        let has_inferred_subscription = routers
+            .iter()
+            .flat_map(|router| &router.procedures)
+            .any(is_inferred_subscription);
+        if !has_inferred_subscription {
+            return;
+        }
+
+        let indent = &self.indent;
+        let term = self.terminator();
+        let _ = write!(
+            output,
+            "\ntype {SUBSCRIPTION_EVENT_TYPE}<T> = T extends AsyncIterable<infer Event>\n\
+            {indent}? Event\n\
+            {indent}: T extends {{ subscribe(next: (value: infer Event) => void, ...rest: any[]): unknown }}\n\
+            {indent}{indent}? Event\n\
+            {indent}{indent}: T extends {{ subscribe(observer: {{ next?: (value: infer Event) => void }}): unknown }}\n\
+            {indent}{indent}{indent}? Event\n\
+            {indent}{indent}{indent}: unknown{term}\n"
+        );
+    }
+
+    /// Enums and shared schemas from router and schema files that `server.ts` can't import.
+    fn append_hoisted_declarations(&self, output: &mut String, routers: &[RouterMetadata]) {
+        let mut seen = HashSet::new();
+        for declaration in routers
+            .iter()
+            .flat_map(|router| &router.procedures)
+            .flat_map(|procedure| &procedure.hoisted_declarations)
+            .filter(|declaration| seen.insert(declaration.placeholder.as_str()))
+        {
+            output.push('\n');
+            output.push_str(&self.generate_hoisted_declaration(declaration));
+        }
+    }
+
+    fn generate_hoisted_declaration(&self, declaration: &HoistedDeclaration) -> String {
+        let term = self.terminator();
+        let name = &declaration.name;
+        let schema = &declaration.declaration;
+        if declaration.kind == HoistedKind::Enum {
+            return format!("{schema}\n");
+        }
+
+        let Some(schema_type) = &declaration.schema_type else {
+            return format!("const {name} = {schema}{term}\n");
+        };
+        let mut output = format!(
+            "type {} = {}{term}\n",
+            schema_type.name, schema_type.definition
+        );
+        if let Some(input) = &schema_type.input {
+            let _ = writeln!(output, "type {} = {}{term}", input.name, input.definition);
+        }
+        let _ = writeln!(
+            output,
+            "const {name}: {} = {schema}{term}",
+            schema_type.annotation
+        );
+        output
+    }
+
+    fn append_procedure_builders(&self, output: &mut String, routers: &[RouterMetadata]) {
+        let mut seen = HashSet::new();
+        let stacks: Vec<&[String]> = routers
+            .iter()
+            .flat_map(|router| &router.procedures)
+            .map(|procedure| procedure.middlewares.as_slice())
+            .filter(|stack| !stack.is_empty() && seen.insert(*stack))
+            .collect();
+
+        if stacks.is_empty() {
+            return;
+        }
+
+        output.push('\n');
+        for stack in stacks {
+            output.push_str(&self.generate_procedure_builder(stack));
+        }
+    }
+
+    fn generate_procedure_builder(&self, middlewares: &[String]) -> String {
+        let term = self.terminator();
+        let name = procedure_builder_name(middlewares);
+        let mut uses = String::new();
+        for middleware in middlewares {
+            let stub = self.generate_middleware_stub(middleware);
+            let _ = write!(uses, "\n{}.use({stub})", self.indent);
+        }
+
+        format!("const {name} = publicProcedure{uses}{term}\n")
+    }
+
+    fn generate_middleware_stub(&self, middleware_name: &str) -> String {
+        let properties = self
+            .middlewares
+            .iter()
+            .find(|middleware| middleware.class_name == middleware_name)
+            .map(|middleware| middleware.context_properties.as_slice())
+            .unwrap_or_default();
+
+        if properties.is_empty() {
+            return "async (opts) => opts.next()".to_string();
+        }
+
+        let fields: Vec<String> = properties
+            .iter()
+            .map(|property| format!("{}: {}", property.name, property.type_string))
+            .collect();
+
+        format!(
+            "async (opts) => opts.next({{ ctx: {{}} as {{ {} }} }})",
+            fields.join("; ")
+        )
+    }
+
+    fn generate_static_declarations(&self, routers: &[RouterMetadata]) -> String {
+        let has_meta = routers
+            .iter()
+            .flat_map(|router| &router.procedures)
+            .any(|procedure| procedure.meta.is_some());
+
+        if !has_meta || self.static_generator.meta_type.is_some() {
+            return self.static_generator.generate_static_declarations();
+        }
+
+        self.static_generator
+            .clone()
+            .with_meta_type(Some(DEFAULT_META_TYPE.to_string()))
+            .generate_static_declarations()
+    }
+
     fn output_cast(&self, procedure: &ProcedureMetadata) -> String {
         if procedure.output_schema.is_some() {
             return "any".to_string();
         }
 
         let Some(inference) = procedure.output_inference.as_ref() else {
-            return "any".to_string();
+            return match self.untyped_output {
+                UntypedOutput::Unknown => "unknown".to_string(),
+                UntypedOutput::Any | UntypedOutput::Error => "any".to_string(),
+            };
         };
 
-        let quote = if self.static_generator.use_single_quotes {
+        let class_name = &inference.router_class_name;
+        let method_name = quoted(&procedure.name, self.quote());
+        let return_type = format!("Awaited<ReturnType<{class_name}[{method_name}]>>");
+
+        if procedure.procedure_type == ProcedureType::Subscription {
+            return format!("unknown as AsyncIterable<{SUBSCRIPTION_EVENT_TYPE}<{return_type}>>");
+        }
+        format!("unknown as {return_type}")
+    }
+
+    const fn quote(&self) -> char {
+        if self.static_generator.use_single_quotes {
             '\''
         } else {
             '"'
-        };
-        let class_name = &inference.router_class_name;
-        let method_name = &procedure.name;
-
-        format!("unknown as Awaited<ReturnType<{class_name}[{quote}{method_name}{quote}]>>")
+        }
     }
 
     #[must_use]
@@ -294,13 +492,25 @@ impl ServerGenerator {
                 .or_insert_with(|| inference.router_file_path.clone());
         }
 
-        let type_imports = self.static_generator.generate_type_imports(
-            inferences
+        let (default_exports, named_exports): (Vec<&OutputInference>, Vec<_>) = inferences
+            .iter()
+            .partition(|inference| inference.default_export);
+        let mut type_imports = self.static_generator.generate_default_type_imports(
+            default_exports
                 .iter()
                 .map(|inference| inference.router_class_name.as_str()),
             &type_locations,
             output_file_path,
         );
+        type_imports.push_str(
+            &self.static_generator.generate_type_imports(
+                named_exports
+                    .iter()
+                    .map(|inference| inference.router_class_name.as_str()),
+                &type_locations,
+                output_file_path,
+            ),
+        );
 
         if !type_imports.is_empty() {
             output.push_str(&type_imports);
@@ -308,34 +518,59 @@ impl ServerGenerator {
     }
 }
 
-fn to_camel_case(s: &str) -> String {
+fn is_inferred_subscription(procedure: &ProcedureMetadata) -> bool {
+    procedure.procedure_type == ProcedureType::Subscription
+        && procedure.output_schema.is_none()
+        && procedure.output_inference.is_some()
+}
+
+pub(super) fn to_camel_case(s: &str) -> String {
     let mut chars = s.chars();
     chars.next().map_or_else(String::new, |first| {
         first.to_lowercase().chain(chars).collect()
     })
 }
 
-fn merge_routers_by_key(routers: &[RouterMetadata]) -> Vec<(String, Vec<&ProcedureMetadata>)> {
-    let mut key_order: Vec<String> = Vec::new();
-    let mut merged: HashMap<String, Vec<&ProcedureMetadata>> = HashMap::new();
-
-    for router in routers {
-        let key = router
-            .alias
-            .clone()
-            .unwrap_or_else(|| to_camel_case(&router.name));
-
-        if !merged.contains_key(&key) {
-            key_order.push(key.clone());
-        }
+/// `name` as an object key: bare when it is a valid identifier, quoted otherwise (e.g. `'get-user'`).
+fn object_key(name: &str, quote: char) -> String {
+    let mut characters = name.chars();
+    let is_identifier = characters
+        .next()
+        .is_some_and(|first| first.is_alphabetic() || first == '_' || first == '$')
+        && characters
+            .all(|character| character.is_alphanumeric() || character == '_' || character == '$');
 
-        merged.entry(key).or_default().extend(&router.procedures);
+    if is_identifier {
+        name.to_string()
+    } else {
+        quoted(name, quote)
     }
+}
+
+fn quoted(text: &str, quote: char) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace(quote, &format!("\\{quote}"));
+    format!("{quote}{escaped}{quote}")
+}
 
-    key_order
-        .into_iter()
-        .filter_map(|key| merged.remove(&key).map(|procedures| (key, procedures)))
-        .collect()
+/// Name of the generated builder for a middleware stack, e.g.
+/// `LoggingMiddleware_AuthMiddlewareProcedure`.
+///
+/// Middlewares are joined with `_`, and a `_` or `$` inside a middleware name is escaped
+/// with `$`, so different stacks such as `[AB, C]` and `[A, BC]` never share a builder.
+/// Names keep their case, so `authMiddleware` and `AuthMiddleware` don't either.
+pub(crate) fn procedure_builder_name(middlewares: &[String]) -> String {
+    if middlewares.is_empty() {
+        return "publicProcedure".to_string();
+    }
+
+    let stack = middlewares
+        .iter()
+        .map(|middleware| middleware.replace('$', "$$").replace('_', "$_"))
+        .collect::<Vec<_>>()
+        .join("_");
+    format!("{stack}Procedure")
 }
 
 #[must_use]
@@ -347,6 +582,7 @@ pub fn generate_server_file(routers: &[RouterMetadata]) -> String {
 mod tests {
     use super::*;
     use crate::{ProcedureMetadata, ProcedureType};
+    use std::collections::BTreeSet;
 
     fn create_test_procedure(
         name: &str,
@@ -363,6 +599,10 @@ mod tests {
             output_schema_ref: None,
             schema_identifiers: Vec::new(),
             output_inference: None,
+            meta: None,
+            middlewares: Vec::new(),
+            hoisted_declarations: Vec::new(),
+            schema_sources: BTreeSet::new(),
         }
     }
 
@@ -384,7 +624,12 @@ mod tests {
             output_inference: Some(OutputInference {
                 router_class_name: router_class_name.to_string(),
                 router_file_path: std::path::PathBuf::from(router_file_path),
+                default_export: false,
             }),
+            meta: None,
+            middlewares: Vec::new(),
+            hoisted_declarations: Vec::new(),
+            schema_sources: BTreeSet::new(),
         }
     }
 
@@ -489,6 +734,24 @@ mod tests {
         assert!(output.contains(".query(async () => \"PLACEHOLDER_DO_NOT_REMOVE\" as any)"));
     }
 
+    #[test]
+    fn test_generate_procedure_string_with_meta() {
+        let generator = ServerGenerator::new();
+        let mut procedure = create_test_procedure(
+            "getUser",
+            ProcedureType::Query,
+            Some("z.object({ id: z.string() })"),
+            None,
+        );
+        procedure.meta = Some("{ roles: ['admin'] }".to_string());
+
+        let output = generator.generate_procedure_string(&procedure, 1);
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines[1].trim(), ".meta({ roles: ['admin'] })");
+        assert_eq!(lines[2].trim(), ".input(z.object({ id: z.string() }))");
+    }
+
     #[test]
     fn test_generate_router_string_with_alias() {
         let generator = ServerGenerator::new();
@@ -644,6 +907,88 @@ mod tests {
         assert!(output.contains("export type AppRouter = typeof appRouter;"));
     }
 
+    #[test]
+    fn test_generate_uses_meta_builder_when_meta_present() {
+        let generator = ServerGenerator::new();
+        let mut procedure = create_test_procedure("getUser", ProcedureType::Query, None, None);
+        procedure.meta = Some("{ public: true }".to_string());
+        let routers = vec![create_test_router(
+            "UserRouter",
+            Some("users"),
+            vec![procedure],
+        )];
+
+        let output = generator.generate(&routers);
+
+        assert!(output.contains("const t = initTRPC.meta<Record<string, unknown>>().create();"));
+        assert!(output.contains(".meta({ public: true })"));
+    }
+
+    #[test]
+    fn test_generate_procedure_builders_per_middleware_stack() {
+        use crate::parser::middleware::ContextProperty;
+
+        let generator = ServerGenerator::new().with_middlewares(vec![MiddlewareInfo {
+            class_name: "AuthMiddleware".to_string(),
+            file_path: std::path::PathBuf::from("auth.middleware.ts"),
+            context_properties: vec![ContextProperty {
+                name: "userId".to_string(),
+                type_string: "string".to_string(),
+            }],
+        }]);
+
+        let mut get_user = create_test_procedure("getUser", ProcedureType::Query, None, None);
+        get_user.middlewares = vec!["AuthMiddleware".to_string()];
+        let mut get_profile = create_test_procedure("getProfile", ProcedureType::Query, None, None);
+        get_profile.middlewares = vec!["AuthMiddleware".to_string()];
+        let mut get_audit = create_test_procedure("getAudit", ProcedureType::Query, None, None);
+        get_audit.middlewares = vec!["AuthMiddleware".to_string(), "AuditMiddleware".to_string()];
+        let list = create_test_procedure("list", ProcedureType::Query, None, None);
+
+        let routers = vec![create_test_router(
+            "UserRouter",
+            Some("users"),
+            vec![get_user, get_profile, get_audit, list],
+        )];
+
+        let output = generator.generate(&routers);
+
+        assert!(output.contains(
+            "const AuthMiddlewareProcedure = publicProcedure\n  .use(async (opts) => opts.next({ ctx: {} as { userId: string } }));"
+        ));
+        assert!(output.contains(
+            "const AuthMiddleware_AuditMiddlewareProcedure = publicProcedure\n  .use(async (opts) => opts.next({ ctx: {} as { userId: string } }))\n  .use(async (opts) => opts.next());"
+        ));
+        assert_eq!(output.matches("const AuthMiddlewareProcedure").count(), 1);
+        assert!(output.contains("getUser: AuthMiddlewareProcedure"));
+        assert!(output.contains("getProfile: AuthMiddlewareProcedure"));
+        assert!(output.contains("getAudit: AuthMiddleware_AuditMiddlewareProcedure"));
+        assert!(output.contains("list: publicProcedure"));
+    }
+
+    #[test]
+    fn test_procedure_builder_names_are_distinct_per_stack() {
+        let stack =
+            |names: &[&str]| -> Vec<String> { names.iter().map(ToString::to_string).collect() };
+
+        assert_ne!(
+            procedure_builder_name(&stack(&["AB", "C"])),
+            procedure_builder_name(&stack(&["A", "BC"]))
+        );
+        assert_ne!(
+            procedure_builder_name(&stack(&["A_", "B"])),
+            procedure_builder_name(&stack(&["A", "_B"]))
+        );
+        assert_eq!(
+            procedure_builder_name(&stack(&["Auth_Guard"])),
+            "Auth$_GuardProcedure"
+        );
+        assert_ne!(
+            procedure_builder_name(&stack(&["authMiddleware"])),
+            procedure_builder_name(&stack(&["AuthMiddleware"]))
+        );
+    }
+
     #[test]
     fn test_generate_complete_file_empty_routers() {
         let generator = ServerGenerator::new();
@@ -893,12 +1238,33 @@ mod tests {
             output_schema_ref: None,
             schema_identifiers: Vec::new(),
             output_inference: None,
+            meta: None,
+            middlewares: Vec::new(),
+            hoisted_declarations: Vec::new(),
+            schema_sources: BTreeSet::new(),
         };
 
         let output = generator.generate_procedure_string(&procedure, 1);
         assert!(output.contains(": publicProcedure"));
     }
 
+    #[test]
+    fn test_generate_quotes_non_identifier_procedure_keys() {
+        let generator = ServerGenerator::new();
+        let procedure =
+            create_inferred_procedure("get-user", ProcedureType::Query, "UserRouter", "user.ts");
+
+        let output = generator.generate_procedure_string(&procedure, 1);
+
+        assert!(output.contains("\"get-user\": publicProcedure"), "{output}");
+        assert!(
+            output.contains("Awaited<ReturnType<UserRouter[\"get-user\"]>>"),
+            "{output}"
+        );
+        assert_eq!(object_key("$getUser_2", '"'), "$getUser_2");
+        assert_eq!(object_key("it's", '\''), "'it\\'s'");
+    }
+
     #[test]
     fn test_generate_handles_empty_router_name() {
         let generator = ServerGenerator::new();
@@ -928,6 +1294,10 @@ mod tests {
                 output_schema_ref: None,
                 schema_identifiers: Vec::new(),
                 output_inference: None,
+                meta: None,
+                middlewares: Vec::new(),
+                hoisted_declarations: Vec::new(),
+                schema_sources: BTreeSet::new(),
             }],
         )];
 
@@ -1030,6 +1400,10 @@ mod tests {
             output_schema_ref: Some("OutputRef".to_string()),
             schema_identifiers: Vec::new(),
             output_inference: None,
+            meta: None,
+            middlewares: Vec::new(),
+            hoisted_declarations: Vec::new(),
+            schema_sources: BTreeSet::new(),
         };
 
         let refs = ServerGenerator::extract_schema_refs(&procedure);
@@ -1049,6 +1423,10 @@ mod tests {
             output_schema_ref: None,
             schema_identifiers: Vec::new(),
             output_inference: None,
+            meta: None,
+            middlewares: Vec::new(),
+            hoisted_declarations: Vec::new(),
+            schema_sources: BTreeSet::new(),
         };
 
         let refs = ServerGenerator::extract_schema_refs(&procedure);
@@ -1302,6 +1680,51 @@ mod tests {
         assert!(output.contains("action: publicProcedure"));
     }
 
+    #[test]
+    fn test_dotted_aliases_generate_nested_routers() {
+        let generator = ServerGenerator::new().with_indent("  ");
+        let routers = vec![
+            create_test_router(
+                "AdminUsersRouter",
+                Some("admin.users"),
+                vec![create_test_procedure(
+                    "list",
+                    ProcedureType::Query,
+                    None,
+                    None,
+                )],
+            ),
+            create_test_router(
+                "AdminRouter",
+                Some("admin"),
+                vec![create_test_procedure(
+                    "stats",
+                    ProcedureType::Query,
+                    None,
+                    None,
+                )],
+            ),
+            create_test_router("AdminPostsRouter", Some("admin/posts"), vec![]),
+        ];
+
+        let output = generator.generate_app_router(&routers);
+
+        let expected = "\
+const appRouter = t.router({
+  admin: t.router({
+    stats: publicProcedure
+      .query(async () => \"PLACEHOLDER_DO_NOT_REMOVE\" as any),
+    users: t.router({
+      list: publicProcedure
+        .query(async () => \"PLACEHOLDER_DO_NOT_REMOVE\" as any)
+      }),
+    posts: t.router({})
+    })
+});
+";
+        assert_eq!(output, expected);
+    }
+
     // ========================================================================
     // Transformer integration tests
     // ========================================================================
@@ -1364,6 +1787,29 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_inferred_subscription_yields_its_event_type() {
+        let generator = ServerGenerator::new();
+        let procedure = create_inferred_procedure(
+            "onMessage",
+            ProcedureType::Subscription,
+            "EventRouter",
+            "event.router.ts",
+        );
+        let routers = vec![create_test_router("EventRouter", None, vec![procedure])];
+
+        let output = generator.generate_with_schema_imports(
+            &routers,
+            &HashMap::new(),
+            Path::new("server.ts"),
+        );
+
+        assert!(output.contains("type SubscriptionEvent<T> = T extends AsyncIterable<infer Event>"));
+        assert!(output.contains(
+            ".subscription(async () => \"PLACEHOLDER_DO_NOT_REMOVE\" as unknown as AsyncIterable<SubscriptionEvent<Awaited<ReturnType<EventRouter[\"onMessage\"]>>>>)"
+        ));
+    }
+
     #[test]
     fn test_explicit_output_overrides_inference() {
         let generator = ServerGenerator::new();
@@ -49,10 +49,18 @@ impl TypesGenerator {
         output.push_str(FILE_HEADER);
         output.push('\n');
 
+        let has_middleware_interfaces = middlewares
+            .iter()
+            .any(|middleware| !middleware.context_properties.is_empty());
+
         if let Some(context_info) = context {
             let context_type = self.generate_context_type(context_info);
             output.push_str(&context_type);
             output.push('\n');
+        } else if has_middleware_interfaces {
+            // Middleware interfaces extend `Context`, so it must exist even without a context class.
+            let terminator = self.terminator();
+            let _ = writeln!(output, "export type Context = object{terminator}\n");
         }
 
         for middleware in middlewares {
@@ -175,6 +183,7 @@ mod tests {
         assert!(output.contains("AUTO-GENERATED FILE"));
         assert!(output.contains("export interface ProtectedMiddlewareContext extends Context {"));
         assert!(output.contains("ben: number;"));
+        assert!(output.contains("export type Context = object;"));
     }
 
     #[test]
@@ -14,36 +14,50 @@ pub mod tsconfig;
 pub mod validation;
 pub mod watcher;
 
+use serde::{Deserialize, Serialize};
+
 pub use anyhow::Result;
-pub use config::{Config, GenerationConfig, ParsingConfig};
+pub use config::{Config, GenerationConfig, ParsingConfig, UntypedOutput};
 pub use diagnostic::{
     DecoratorDiagnostic, ImportDiagnostic, NoRoutersDiagnostic, SchemaDiagnostic, SourceContext,
-    SyntaxDiagnostic,
+    SyntaxDiagnostic, UnexportedRouterDiagnostic,
 };
 pub use discovery::discover_root_module;
 pub use error::{CliError, ConfigError, GeneratorError, ImportError, ParserError, ScannerError};
-pub use generation::{run_generation, GenerationResult};
+pub use generation::{
+    default_cache_directory, generate_in_memory, run_generation, run_generation_with_options,
+    server_file_path, GeneratedFile, GeneratedOutput, GenerationOptions, GenerationResult,
+    IncrementalGeneration,
+};
 pub use generator::{
-    generate_server_file, generate_static_section, generate_types_file, ServerGenerator,
-    StaticGenerator, TypesGenerator,
+    generate_server_file, generate_static_section, generate_types_file, router_path, RouterTree,
+    ServerGenerator, StaticGenerator, TypesGenerator,
+};
+pub use parser::imports::{
+    build_imports_map, DeclarationType, ImportResolver, PathAliases, ResolvedImport,
+};
+pub use parser::procedure::{
+    extract_procedures_from_class, extract_procedures_from_hierarchy, ClassProcedures,
 };
-pub use parser::imports::{build_imports_map, DeclarationType, ImportResolver, ResolvedImport};
-pub use parser::procedure::extract_procedures_from_class;
 pub use parser::{
-    extract_context, extract_middleware, extract_middleware_names_from_class, extract_routers,
-    extract_trpc_options, flatten_zod_schema, is_procedure_decorator, parse_typescript_file,
-    parse_typescript_source, resolve_context_file, resolve_transformer_import, ContextInfo,
-    ContextParser, ContextProperty, DecoratorParser, MiddlewareInfo, MiddlewareParser,
-    ModuleParser, ParsedFile, ProcedureDecoratorInfo, RouterExportKind, RouterInfo, RouterParser,
-    TransformerInfo, TrpcModuleOptions, TsParser, ZodFlattener, ZodResult,
+    check_zod_entry_points, detect_composed_decorators, detect_validator_imports, extract_context,
+    extract_middleware, extract_middleware_names_from_class, extract_routers, extract_trpc_options,
+    flatten_zod_schema, is_procedure_decorator, merge_validator_bindings, parse_typescript_file,
+    parse_typescript_source, resolve_context_file, resolve_transformer_import, BaseClass,
+    BaseClassResolver, ConstantEvaluator, ContextInfo, ContextParser, ContextProperty,
+    DecoratorParser, MiddlewareInfo, MiddlewareParser, ModuleParser, ParseCache, ParsedFile,
+    ProcedureDecoratorAlias, ProcedureDecoratorInfo, ResolverContext, RouterExportKind, RouterInfo,
+    RouterParser, TransformerInfo, TrpcModuleOptions, TsParser, ValidatorImport,
+    ValidatorImportKind, ZodFlattener, ZodResult, ZodVersion,
 };
 pub use scanner::{scan_for_routers, FileScanner};
 pub use validation::{
-    compute_diff, find_tsc, run_tsc_validation, DiffResult, TscError, TscResult, TscSeverity,
+    compute_diff, compute_removal_diff, find_tsc, run_tsc_validation, DiffResult, TscError,
+    TscResult, TscSeverity,
 };
 pub use watcher::{WatchConfig, WatchSession};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RouterMetadata {
     pub name: String,
     pub alias: Option<String>,
@@ -51,7 +65,7 @@ pub struct RouterMetadata {
     pub procedures: Vec<ProcedureMetadata>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcedureMetadata {
     pub name: String,
     pub procedure_type: ProcedureType,
@@ -66,15 +80,86 @@ pub struct ProcedureMetadata {
     /// generated file. This is only populated for routers that can be imported by
     /// name; it stays `None` for non-exported or default-exported routers.
     pub output_inference: Option<OutputInference>,
+
+    /// Source text of the decorator `meta` option, emitted as `.meta(...)`.
+    pub meta: Option<String>,
+
+    /// `@UseMiddlewares` names applied to this procedure: global, then class, then method level.
+    pub middlewares: Vec<String>,
+
+    /// Non-exported enums and schemas the flattened schemas refer to, dependencies first. They
+    /// are declared in the generated file, since it can't import them.
+    pub hoisted_declarations: Vec<HoistedDeclaration>,
+
+    /// Files the flattened schemas were copied from, whose validator imports `server.ts` follows.
+    pub schema_sources: std::collections::BTreeSet<std::path::PathBuf>,
+}
+
+/// A declaration copied into the generated file, such as `enum Role { Admin = 'ADMIN' }`.
+///
+/// Until it is named, the declaration and the schemas using it refer to it by `placeholder`, so
+/// same-named declarations from different files stay apart. Naming then replaces `name` with the
+/// one used in the generated file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HoistedDeclaration {
+    pub name: String,
+    pub source_file: std::path::PathBuf,
+    pub placeholder: String,
+    pub declaration: String,
+    pub kind: HoistedKind,
+    /// Set for recursive schemas once named, when their validator library can be annotated.
+    pub schema_type: Option<SchemaType>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HoistedKind {
+    /// `declaration` is the enum's source, renamed to the placeholder.
+    Enum,
+    /// `declaration` is a flattened schema, declared as `const {name} = ...` when several
+    /// procedures use it and inlined otherwise.
+    Schema,
+    /// A flattened schema that refers to itself by placeholder, e.g. through `z.lazy`. It is
+    /// always declared, since it can't be inlined into itself.
+    RecursiveSchema,
+}
+
+impl HoistedKind {
+    #[must_use]
+    pub const fn is_schema(self) -> bool {
+        matches!(self, Self::Schema | Self::RecursiveSchema)
+    }
+}
+
+/// The output type a recursive schema is declared with.
+///
+/// E.g. `type Category = { ... }` and `const categorySchema_flat: z.ZodType<Category> = ...`,
+/// since TypeScript can't infer the type of a declaration that refers to itself.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaType {
+    pub name: String,
+    pub definition: String,
+    /// Declared too when the schema accepts something other than it returns, e.g. with
+    /// `.default()`, and passed to the annotation after the output type.
+    pub input: Option<InputType>,
+    pub annotation: String,
+}
+
+/// The input type alias of a recursive schema, e.g. `type CategoryInput = { ... }`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputType {
+    pub name: String,
+    pub definition: String,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct OutputInference {
     pub router_class_name: String,
     pub router_file_path: std::path::PathBuf,
+    /// The router is its file's default export, imported as `import type Router from '...'`.
+    pub default_export: bool,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ProcedureType {
     Query,
     Mutation,
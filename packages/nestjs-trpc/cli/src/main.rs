@@ -9,8 +9,8 @@ use tracing::debug;
 use tracing_subscriber::filter::LevelFilter;
 use tracing_subscriber::EnvFilter;
 
-use cli::{Cli, Commands};
-use nestjs_trpc::{ParserError, SyntaxDiagnostic};
+use cli::{Cli, CliOverrides, Commands, GenerateMode};
+use nestjs_trpc::{DecoratorDiagnostic, GeneratorError, ParserError, SyntaxDiagnostic};
 
 const EXIT_SUCCESS: u8 = 0;
 const EXIT_RUNTIME_ERROR: u8 = 1;
@@ -47,13 +47,13 @@ fn main() -> ExitCode {
     match run(&cli) {
         Ok(exit_code) => exit_code,
         Err(error) => {
-            print_error(&error);
+            print_error(error);
             ExitCode::from(EXIT_RUNTIME_ERROR)
         }
     }
 }
 
-fn print_error(error: &anyhow::Error) {
+fn print_error(error: anyhow::Error) {
     if let Some(ParserError::SyntaxError {
         path,
         line,
@@ -69,6 +69,22 @@ fn print_error(error: &anyhow::Error) {
         }
     }
 
+    let error = match error.downcast::<DecoratorDiagnostic>() {
+        Ok(diagnostic) => {
+            eprintln!("{:?}", miette::Report::new(diagnostic));
+            return;
+        }
+        Err(error) => error,
+    };
+
+    let error = match error.downcast::<GeneratorError>() {
+        Ok(generator_error) => {
+            eprintln!("{:?}", miette::Report::new(generator_error));
+            return;
+        }
+        Err(error) => error,
+    };
+
     let report = miette::Report::msg(format!("{error:#}"));
     eprintln!("{report:?}");
 }
@@ -104,35 +120,44 @@ fn setup_logging(cli: &Cli) {
 fn run(cli: &Cli) -> Result<ExitCode> {
     match &cli.command {
         Some(Commands::Generate {
+            config,
             entrypoint,
             output,
             router_pattern,
             json,
             dry_run,
+            check,
+            no_cache,
             import_extension,
         }) => {
+            let overrides = CliOverrides {
+                config: config.as_deref(),
+                entrypoint: entrypoint.as_deref(),
+                output: output.as_deref(),
+                router_pattern: router_pattern.as_deref(),
+            };
             return cli::run_generate(
-                entrypoint.as_deref(),
-                output.as_deref(),
-                router_pattern.as_deref(),
-                *dry_run,
+                &overrides,
+                GenerateMode::from_flags(*dry_run, *check),
                 *json,
+                *no_cache,
                 import_extension.as_ref(),
             );
         }
         Some(Commands::Watch {
+            config,
             entrypoint,
             output,
             router_pattern,
             import_extension,
         }) => {
-            cli::run_watch(
-                entrypoint.as_deref(),
-                output.as_deref(),
-                router_pattern.as_deref(),
-                cli.verbose > 0,
-                import_extension.as_ref(),
-            )?;
+            let overrides = CliOverrides {
+                config: config.as_deref(),
+                entrypoint: entrypoint.as_deref(),
+                output: output.as_deref(),
+                router_pattern: router_pattern.as_deref(),
+            };
+            cli::run_watch(&overrides, cli.verbose > 0, import_extension.as_ref())?;
         }
         None => {
             tracing::info!("No command specified. Use --help for usage information.");
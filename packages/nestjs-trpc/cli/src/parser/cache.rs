@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, PoisonError, RwLock};
+
+use tracing::trace;
+
+use super::imports::module_path::normalize_path;
+use super::{ParsedFile, ParserResult, TsParser};
+
+/// Thread-safe cache of parsed files, shared by the import resolver and schema flattener.
+///
+/// Clones share the same underlying map, so a single cache can be handed to every worker.
+/// Entries are keyed by the lexically normalized path.
+#[derive(Debug, Clone, Default)]
+pub struct ParseCache {
+    files: Arc<RwLock<HashMap<PathBuf, Arc<ParsedFile>>>>,
+}
+
+impl ParseCache {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the cached parse of `path`, parsing it outside the lock on a miss.
+    pub fn get_or_parse(&self, parser: &TsParser, path: &Path) -> ParserResult<Arc<ParsedFile>> {
+        if let Some(cached) = self.get(path) {
+            trace!(path = ?path, "Parse cache hit");
+            return Ok(cached);
+        }
+
+        let parsed = parser.parse_file(path)?;
+        Ok(self.insert(parsed))
+    }
+
+    #[must_use]
+    pub fn get(&self, path: &Path) -> Option<Arc<ParsedFile>> {
+        self.files
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&normalize_path(path))
+            .cloned()
+    }
+
+    /// Stores `parsed`, keeping the existing entry if another thread got there first.
+    pub fn insert(&self, parsed: ParsedFile) -> Arc<ParsedFile> {
+        let mut files = self.files.write().unwrap_or_else(PoisonError::into_inner);
+        Arc::clone(
+            files
+                .entry(normalize_path(&parsed.file_path))
+                .or_insert_with(|| Arc::new(parsed)),
+        )
+    }
+
+    /// Drops the entry for `path` so the next lookup re-reads it from disk.
+    pub fn invalidate(&self, path: &Path) -> bool {
+        self.files
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&normalize_path(path))
+            .is_some()
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.files
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rayon::prelude::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parses_each_file_once() {
+        let temporary_directory = TempDir::new().unwrap();
+        let path = temporary_directory.path().join("schema.ts");
+        fs::write(&path, "export const a = 1;").unwrap();
+
+        let parser = TsParser::new();
+        let cache = ParseCache::new();
+
+        let first = cache.get_or_parse(&parser, &path).unwrap();
+        let second = cache.get_or_parse(&parser, &path).unwrap();
+
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_shared_across_threads() {
+        let temporary_directory = TempDir::new().unwrap();
+        let paths: Vec<PathBuf> = (0..8)
+            .map(|index| {
+                let path = temporary_directory.path().join(format!("file_{index}.ts"));
+                fs::write(&path, format!("export const value{index} = {index};")).unwrap();
+                path
+            })
+            .collect();
+
+        let parser = TsParser::new();
+        let cache = ParseCache::new();
+
+        (0..4)
+            .into_par_iter()
+            .flat_map_iter(|_| &paths)
+            .for_each(|path| {
+                cache.get_or_parse(&parser, path).unwrap();
+            });
+
+        assert_eq!(cache.len(), paths.len());
+    }
+
+    #[test]
+    fn test_invalidate_matches_unnormalized_paths() {
+        let temporary_directory = TempDir::new().unwrap();
+        fs::create_dir(temporary_directory.path().join("routers")).unwrap();
+        let path = temporary_directory.path().join("schema.ts");
+        fs::write(&path, "export const a = 1;").unwrap();
+
+        let parser = TsParser::new();
+        let cache = ParseCache::new();
+        let indirect = temporary_directory.path().join("routers/../schema.ts");
+        cache.get_or_parse(&parser, &indirect).unwrap();
+
+        assert!(cache.get(&path).is_some());
+        assert!(cache.invalidate(&path));
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_parse_errors_are_not_cached() {
+        let temporary_directory = TempDir::new().unwrap();
+        let path = temporary_directory.path().join("missing.ts");
+
+        let cache = ParseCache::new();
+        assert!(cache.get_or_parse(&TsParser::new(), &path).is_err());
+        assert!(cache.is_empty());
+    }
+}
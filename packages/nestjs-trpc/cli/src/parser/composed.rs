@@ -0,0 +1,292 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use swc_ecma_ast::{
+    BlockStmt, BlockStmtOrExpr, CallExpr, Class, Decl, DefaultDecl, Expr, Function, ModuleDecl,
+    ModuleItem, Stmt,
+};
+use tracing::{debug, trace};
+
+use super::decorator::{procedure_type_from_name, ProcedureDecoratorAlias};
+use super::middleware::extract_use_middlewares_call_names;
+use super::procedure::class_members;
+use super::provenance::{decorator_name, DecoratorImports};
+use super::{ParseCache, ParsedFile, TsParser};
+use crate::ResolvedImport;
+
+const APPLY_DECORATORS: &str = "applyDecorators";
+
+/// Procedure decorators used in `parsed_file` that are composed with `applyDecorators`, keyed by
+/// local name.
+///
+/// Each decorator is looked up where it is declared, following `imports_map` for imported ones,
+/// and must return `applyDecorators(...)` with a `Query`, `Mutation` or `Subscription` among its
+/// arguments. Middlewares from `UseMiddlewares(...)` arguments become implied middlewares.
+#[must_use]
+#[allow(clippy::implicit_hasher)]
+pub fn detect_composed_decorators(
+    parsed_file: &ParsedFile,
+    imports_map: &HashMap<String, ResolvedImport>,
+    typescript_parser: &TsParser,
+    parse_cache: &ParseCache,
+) -> BTreeMap<String, ProcedureDecoratorAlias> {
+    method_decorator_names(parsed_file)
+        .into_iter()
+        .filter_map(|name| {
+            let alias = imports_map.get(name).map_or_else(
+                || find_composed_decorator(parsed_file, name),
+                |resolved| {
+                    parse_cache
+                        .get_or_parse(typescript_parser, &resolved.source_file)
+                        .ok()
+                        .and_then(|source| find_composed_decorator(&source, &resolved.name))
+                },
+            )?;
+            debug!(decorator = %name, alias = ?alias, "Detected composed procedure decorator");
+            Some((name.to_string(), alias))
+        })
+        .collect()
+}
+
+/// Names of the decorators on class methods and properties that aren't `nestjs-trpc` procedure decorators.
+fn method_decorator_names(parsed_file: &ParsedFile) -> BTreeSet<&str> {
+    let imports = DecoratorImports::from_module(&parsed_file.module);
+
+    parsed_file
+        .module
+        .body
+        .iter()
+        .filter_map(class_in_item)
+        .flat_map(class_members)
+        .flat_map(|(_, decorators)| decorators)
+        .filter(|decorator| {
+            imports
+                .resolve(decorator)
+                .and_then(procedure_type_from_name)
+                .is_none()
+        })
+        .filter_map(decorator_name)
+        .collect()
+}
+
+fn class_in_item(item: &ModuleItem) -> Option<&Class> {
+    match item {
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_declaration)) => export_declaration
+            .decl
+            .as_class()
+            .map(|class| &*class.class),
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(export_default)) => {
+            match &export_default.decl {
+                DefaultDecl::Class(class_expression) => Some(&*class_expression.class),
+                _ => None,
+            }
+        }
+        ModuleItem::Stmt(Stmt::Decl(Decl::Class(class_declaration))) => {
+            Some(&*class_declaration.class)
+        }
+        _ => None,
+    }
+}
+
+/// Reads the `applyDecorators(...)` call returned by the decorator `name` declared in `parsed_file`.
+fn find_composed_decorator(
+    parsed_file: &ParsedFile,
+    name: &str,
+) -> Option<ProcedureDecoratorAlias> {
+    let returned = parsed_file
+        .module
+        .body
+        .iter()
+        .find_map(|item| declared_decorator_result(item, name))?;
+    let call_expression = apply_decorators_call(returned)?;
+    let imports = DecoratorImports::from_module(&parsed_file.module);
+
+    let procedure_type = call_expression.args.iter().find_map(|argument| {
+        imports
+            .resolve_expression(&argument.expr)
+            .and_then(procedure_type_from_name)
+    })?;
+    let middlewares = call_expression
+        .args
+        .iter()
+        .filter_map(|argument| extract_use_middlewares_call_names(&argument.expr))
+        .flatten()
+        .collect();
+
+    trace!(decorator = %name, path = ?parsed_file.file_path, "Found applyDecorators factory");
+    Some(ProcedureDecoratorAlias {
+        procedure_type,
+        middlewares,
+    })
+}
+
+/// The expression a decorator declaration produces: a function's return value, or a
+/// variable's initializer (itself unwrapped if it is a function).
+fn declared_decorator_result<'a>(item: &'a ModuleItem, name: &str) -> Option<&'a Expr> {
+    let declaration = match item {
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_declaration)) => {
+            &export_declaration.decl
+        }
+        ModuleItem::Stmt(Stmt::Decl(declaration)) => declaration,
+        _ => return None,
+    };
+
+    match declaration {
+        Decl::Fn(function_declaration) if function_declaration.ident.sym.as_ref() == name => {
+            function_result(&function_declaration.function)
+        }
+        Decl::Var(variable_declaration) => variable_declaration
+            .decls
+            .iter()
+            .find(|declarator| {
+                declarator
+                    .name
+                    .as_ident()
+                    .is_some_and(|identifier| identifier.sym.as_ref() == name)
+            })
+            .and_then(|declarator| declarator.init.as_deref())
+            .and_then(initializer_result),
+        _ => None,
+    }
+}
+
+fn initializer_result(initializer: &Expr) -> Option<&Expr> {
+    match initializer.unwrap_parens() {
+        Expr::Arrow(arrow) => match &*arrow.body {
+            BlockStmtOrExpr::Expr(expression) => Some(expression),
+            BlockStmtOrExpr::BlockStmt(block) => returned_expression(block),
+        },
+        Expr::Fn(function_expression) => function_result(&function_expression.function),
+        expression => Some(expression),
+    }
+}
+
+fn function_result(function: &Function) -> Option<&Expr> {
+    function.body.as_ref().and_then(returned_expression)
+}
+
+fn returned_expression(block: &BlockStmt) -> Option<&Expr> {
+    block.stmts.iter().find_map(|statement| match statement {
+        Stmt::Return(return_statement) => return_statement.arg.as_deref(),
+        _ => None,
+    })
+}
+
+fn apply_decorators_call(expression: &Expr) -> Option<&CallExpr> {
+    let Expr::Call(call_expression) = expression.unwrap_parens() else {
+        return None;
+    };
+    let callee = call_expression.callee.as_expr()?.as_ident()?;
+
+    (callee.sym.as_ref() == APPLY_DECORATORS).then_some(call_expression)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::imports::build_imports_map;
+    use crate::ProcedureType;
+    use std::fs;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn detect(directory: &Path, router_source: &str) -> BTreeMap<String, ProcedureDecoratorAlias> {
+        let router_path = directory.join("users.router.ts");
+        fs::write(&router_path, router_source).unwrap();
+
+        let typescript_parser = TsParser::new();
+        let parsed = typescript_parser.parse_file(&router_path).unwrap();
+        let imports_map = build_imports_map(&typescript_parser, &parsed, directory).unwrap();
+
+        detect_composed_decorators(
+            &parsed,
+            &imports_map,
+            &typescript_parser,
+            &ParseCache::new(),
+        )
+    }
+
+    #[test]
+    fn test_detects_imported_apply_decorators_factory() {
+        let temporary_directory = TempDir::new().unwrap();
+        let directory = temporary_directory.path();
+        fs::write(
+            directory.join("admin.decorator.ts"),
+            r"
+            import { applyDecorators } from '@nestjs/common';
+            import { Mutation, UseMiddlewares } from 'nestjs-trpc';
+
+            export function AdminMutation(options?: any) {
+                return applyDecorators(Mutation(options), UseMiddlewares(AuthGuard, AdminGuard));
+            }
+            ",
+        )
+        .unwrap();
+
+        let aliases = detect(
+            directory,
+            r"
+            import { AdminMutation } from './admin.decorator';
+
+            export class UsersRouter {
+                @AdminMutation({ input: z.string() })
+                deleteUser() {}
+            }
+            ",
+        );
+
+        assert_eq!(
+            aliases.get("AdminMutation"),
+            Some(&ProcedureDecoratorAlias {
+                procedure_type: ProcedureType::Mutation,
+                middlewares: vec!["AuthGuard".to_string(), "AdminGuard".to_string()],
+            })
+        );
+    }
+
+    #[test]
+    fn test_detects_local_arrow_factory() {
+        let temporary_directory = TempDir::new().unwrap();
+        let aliases = detect(
+            temporary_directory.path(),
+            r"
+            import { applyDecorators } from '@nestjs/common';
+            import { Query as TrpcQuery } from 'nestjs-trpc';
+
+            const CachedQuery = (options?: any) => applyDecorators(TrpcQuery(options), CacheTTL(60));
+
+            export class UsersRouter {
+                @CachedQuery()
+                listUsers() {}
+            }
+            ",
+        );
+
+        assert_eq!(
+            aliases.get("CachedQuery").map(|alias| alias.procedure_type),
+            Some(ProcedureType::Query)
+        );
+    }
+
+    #[test]
+    fn test_ignores_factories_without_procedure_decorator() {
+        let temporary_directory = TempDir::new().unwrap();
+        let aliases = detect(
+            temporary_directory.path(),
+            r"
+            import { applyDecorators, SetMetadata } from '@nestjs/common';
+            import { Query } from '@nestjs/graphql';
+
+            const Public = () => applyDecorators(SetMetadata('public', true));
+            const GraphqlQuery = () => applyDecorators(Query());
+
+            export class UsersRouter {
+                @Public()
+                @GraphqlQuery()
+                listUsers() {}
+            }
+            ",
+        );
+
+        assert!(aliases.is_empty());
+    }
+}
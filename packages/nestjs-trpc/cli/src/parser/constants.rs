@@ -0,0 +1,314 @@
+use swc_ecma_ast::{
+    Decl, Expr, Lit, MemberExpr, MemberProp, ModuleDecl, ModuleItem, ObjectLit, Prop, PropName,
+    PropOrSpread, Stmt, Tpl, TsEnumDecl, TsEnumMemberId, VarDeclKind,
+};
+use tracing::trace;
+
+use super::{ParsedFile, ResolverContext};
+
+/// Guards against constants that refer to each other in a cycle.
+const MAX_EVALUATION_DEPTH: usize = 32;
+
+/// Statically evaluates string constants: literals, `const` declarations, string `enum` members,
+/// `as const` object members and template literals, following imports into other files.
+#[derive(Debug, Clone)]
+pub struct ConstantEvaluator<'a> {
+    resolver: &'a ResolverContext<'a>,
+}
+
+enum Declaration<'a> {
+    Const(&'a Expr),
+    Enum(&'a TsEnumDecl),
+}
+
+impl<'a> ConstantEvaluator<'a> {
+    #[must_use]
+    pub const fn new(resolver: &'a ResolverContext<'a>) -> Self {
+        Self { resolver }
+    }
+
+    /// The string `expression` evaluates to in `parsed_file`, or `None` if it isn't static.
+    #[must_use]
+    pub fn evaluate_string(&self, expression: &Expr, parsed_file: &ParsedFile) -> Option<String> {
+        self.evaluate(expression, &[], parsed_file, 0)
+    }
+
+    /// Evaluates `expression`, then reads the nested object or enum members named by `path`.
+    fn evaluate(
+        &self,
+        expression: &Expr,
+        path: &[String],
+        parsed_file: &ParsedFile,
+        depth: usize,
+    ) -> Option<String> {
+        if depth > MAX_EVALUATION_DEPTH {
+            return None;
+        }
+
+        match strip_type_assertions(expression) {
+            Expr::Lit(Lit::Str(string)) if path.is_empty() => {
+                Some(string.value.to_string_lossy().into_owned())
+            }
+            Expr::Tpl(template) if path.is_empty() => {
+                self.evaluate_template(template, parsed_file, depth)
+            }
+            Expr::Ident(identifier) => {
+                self.evaluate_binding(identifier.sym.as_ref(), path, parsed_file, depth + 1)
+            }
+            Expr::Member(member_expression) => {
+                let (root, mut segments) = member_path(member_expression)?;
+                segments.extend_from_slice(path);
+                self.evaluate_binding(root, &segments, parsed_file, depth + 1)
+            }
+            Expr::Object(object) => self.evaluate_property(object, path, parsed_file, depth),
+            _ => None,
+        }
+    }
+
+    fn evaluate_template(
+        &self,
+        template: &Tpl,
+        parsed_file: &ParsedFile,
+        depth: usize,
+    ) -> Option<String> {
+        let mut value = String::new();
+        for (index, quasi) in template.quasis.iter().enumerate() {
+            let text = quasi.cooked.as_ref().map_or_else(
+                || quasi.raw.to_string(),
+                |cooked| cooked.to_string_lossy().into_owned(),
+            );
+            value.push_str(&text);
+
+            let part = template
+                .exprs
+                .get(index)
+                .map(|expression| self.evaluate(expression, &[], parsed_file, depth + 1));
+            value.push_str(&part.unwrap_or_else(|| Some(String::new()))?);
+        }
+        Some(value)
+    }
+
+    fn evaluate_property(
+        &self,
+        object: &ObjectLit,
+        path: &[String],
+        parsed_file: &ParsedFile,
+        depth: usize,
+    ) -> Option<String> {
+        let (key, rest) = path.split_first()?;
+
+        object
+            .props
+            .iter()
+            .rev()
+            .filter_map(|property| match property {
+                PropOrSpread::Prop(property) => Some(&**property),
+                PropOrSpread::Spread(_) => None,
+            })
+            .find_map(|property| match property {
+                Prop::KeyValue(key_value) if property_name_matches(&key_value.key, key) => {
+                    self.evaluate(&key_value.value, rest, parsed_file, depth + 1)
+                }
+                Prop::Shorthand(identifier) if identifier.sym.as_ref() == key => {
+                    self.evaluate_binding(key, rest, parsed_file, depth + 1)
+                }
+                _ => None,
+            })
+    }
+
+    /// Evaluates the top-level binding `name` of `parsed_file`, following it through imports.
+    fn evaluate_binding(
+        &self,
+        name: &str,
+        path: &[String],
+        parsed_file: &ParsedFile,
+        depth: usize,
+    ) -> Option<String> {
+        let declaration = parsed_file
+            .module
+            .body
+            .iter()
+            .find_map(|item| find_declaration(item, name));
+
+        match declaration {
+            Some(Declaration::Const(initializer)) => {
+                self.evaluate(initializer, path, parsed_file, depth)
+            }
+            Some(Declaration::Enum(enum_declaration)) => match path {
+                [member_name] => enum_member_initializer(enum_declaration, member_name)
+                    .and_then(|initializer| self.evaluate(initializer, &[], parsed_file, depth)),
+                _ => None,
+            },
+            None => self.evaluate_import(name, path, parsed_file, depth),
+        }
+    }
+
+    fn evaluate_import(
+        &self,
+        name: &str,
+        path: &[String],
+        parsed_file: &ParsedFile,
+        depth: usize,
+    ) -> Option<String> {
+        let resolved = self.resolver.resolve_import(parsed_file, name)?;
+        trace!(name = %name, source = ?resolved.source_file, "Following imported constant");
+
+        let source = self.resolver.parse(&resolved.source_file)?;
+        self.evaluate_binding(&resolved.name, path, &source, depth + 1)
+    }
+}
+
+fn strip_type_assertions(expression: &Expr) -> &Expr {
+    match expression {
+        Expr::Paren(parenthesized) => strip_type_assertions(&parenthesized.expr),
+        Expr::TsAs(assertion) => strip_type_assertions(&assertion.expr),
+        Expr::TsConstAssertion(assertion) => strip_type_assertions(&assertion.expr),
+        Expr::TsSatisfies(satisfies) => strip_type_assertions(&satisfies.expr),
+        Expr::TsNonNull(non_null) => strip_type_assertions(&non_null.expr),
+        Expr::TsTypeAssertion(assertion) => strip_type_assertions(&assertion.expr),
+        _ => expression,
+    }
+}
+
+/// Splits `ROUTES.admin['users']` into `ROUTES` and `["admin", "users"]`.
+fn member_path(member_expression: &MemberExpr) -> Option<(&str, Vec<String>)> {
+    let segment = match &member_expression.prop {
+        MemberProp::Ident(property) => property.sym.to_string(),
+        MemberProp::Computed(computed) => match &*computed.expr {
+            Expr::Lit(Lit::Str(string)) => string.value.to_string_lossy().into_owned(),
+            _ => return None,
+        },
+        MemberProp::PrivateName(_) => return None,
+    };
+
+    let (root, mut segments) = match strip_type_assertions(&member_expression.obj) {
+        Expr::Ident(identifier) => (identifier.sym.as_ref(), Vec::new()),
+        Expr::Member(object) => member_path(object)?,
+        _ => return None,
+    };
+    segments.push(segment);
+    Some((root, segments))
+}
+
+fn find_declaration<'a>(item: &'a ModuleItem, name: &str) -> Option<Declaration<'a>> {
+    let declaration = match item {
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_declaration)) => {
+            &export_declaration.decl
+        }
+        ModuleItem::Stmt(Stmt::Decl(declaration)) => declaration,
+        _ => return None,
+    };
+
+    match declaration {
+        Decl::Var(variable_declaration) if variable_declaration.kind == VarDeclKind::Const => {
+            variable_declaration
+                .decls
+                .iter()
+                .find(|declarator| {
+                    declarator
+                        .name
+                        .as_ident()
+                        .is_some_and(|identifier| identifier.sym.as_ref() == name)
+                })
+                .and_then(|declarator| declarator.init.as_deref())
+                .map(Declaration::Const)
+        }
+        Decl::TsEnum(enum_declaration) if enum_declaration.id.sym.as_ref() == name => {
+            Some(Declaration::Enum(enum_declaration))
+        }
+        _ => None,
+    }
+}
+
+fn enum_member_initializer<'a>(enum_declaration: &'a TsEnumDecl, name: &str) -> Option<&'a Expr> {
+    enum_declaration
+        .members
+        .iter()
+        .find(|member| match &member.id {
+            TsEnumMemberId::Ident(identifier) => identifier.sym.as_ref() == name,
+            TsEnumMemberId::Str(string) => string.value.to_string_lossy() == name,
+        })
+        .and_then(|member| member.init.as_deref())
+}
+
+fn property_name_matches(property_name: &PropName, name: &str) -> bool {
+    match property_name {
+        PropName::Ident(identifier) => identifier.sym.as_ref() == name,
+        PropName::Str(string) => string.value.to_string_lossy() == name,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{ParseCache, TsParser};
+    use std::fs;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn evaluate_alias(directory: &Path, source: &str) -> Option<String> {
+        let path = directory.join("users.router.ts");
+        fs::write(&path, source).unwrap();
+
+        let typescript_parser = TsParser::new();
+        let parse_cache = ParseCache::new();
+        let parsed = typescript_parser.parse_file(&path).unwrap();
+        let Some(ModuleItem::Stmt(Stmt::Decl(Decl::Var(alias)))) = parsed.module.body.last() else {
+            panic!("Expected the source to end with `const alias = ...`");
+        };
+        let expression = alias.decls[0].init.as_deref().unwrap();
+
+        let resolver = ResolverContext::new(&typescript_parser, &parse_cache, directory);
+        ConstantEvaluator::new(&resolver).evaluate_string(expression, &parsed)
+    }
+
+    #[test]
+    fn test_evaluates_local_constants_and_enums() {
+        let temporary_directory = TempDir::new().unwrap();
+        let source = r"
+            const PREFIX = 'admin';
+            enum Section { Users = 'users' }
+            const ROUTES = { nested: { section: Section.Users } } as const;
+            const alias = `${PREFIX}.${ROUTES.nested['section']}`;
+        ";
+
+        assert_eq!(
+            evaluate_alias(temporary_directory.path(), source),
+            Some("admin.users".to_string())
+        );
+    }
+
+    #[test]
+    fn test_follows_imported_constants() {
+        let temporary_directory = TempDir::new().unwrap();
+        let directory = temporary_directory.path();
+        fs::write(
+            directory.join("routes.ts"),
+            "const users = 'users';\nexport const ROUTES = { users } as const;",
+        )
+        .unwrap();
+        fs::write(directory.join("index.ts"), "export * from './routes';").unwrap();
+
+        let source = r"
+            import { ROUTES } from './index';
+            const alias = ROUTES.users;
+        ";
+
+        assert_eq!(evaluate_alias(directory, source), Some("users".to_string()));
+    }
+
+    #[test]
+    fn test_rejects_non_static_values() {
+        let temporary_directory = TempDir::new().unwrap();
+        let directory = temporary_directory.path();
+
+        let mutable = "let name = 'users';\nconst alias = name;";
+        let computed = "const alias = `${process.env.PREFIX}.users`;";
+        let cyclic = "const a = b;\nconst b = a;\nconst alias = a;";
+
+        assert_eq!(evaluate_alias(directory, mutable), None);
+        assert_eq!(evaluate_alias(directory, computed), None);
+        assert_eq!(evaluate_alias(directory, cyclic), None);
+    }
+}
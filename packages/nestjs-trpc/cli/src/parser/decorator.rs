@@ -1,6 +1,7 @@
+use crate::parser::provenance::{decorator_name, DecoratorImports};
 use crate::parser::ParsedFile;
 use crate::ProcedureType;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use swc_ecma_ast::{
     CallExpr, Callee, Decorator, Expr, ExprOrSpread, MemberExpr, ObjectLit, Prop, PropName,
     PropOrSpread,
@@ -14,7 +15,17 @@ pub struct ProcedureDecoratorInfo {
     pub output: Option<String>,
     pub input_ref: Option<String>,
     pub output_ref: Option<String>,
+    pub meta: Option<String>,
     pub schema_identifiers: Vec<String>,
+    /// Middlewares implied by a custom procedure decorator, applied after the class-level ones.
+    pub middlewares: Vec<String>,
+}
+
+/// A custom decorator, such as one built with `applyDecorators`, that declares a procedure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcedureDecoratorAlias {
+    pub procedure_type: ProcedureType,
+    pub middlewares: Vec<String>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -23,16 +34,34 @@ pub struct DecoratorArguments {
     pub output: Option<String>,
     pub input_ref: Option<String>,
     pub output_ref: Option<String>,
+    pub meta: Option<String>,
     pub schema_identifiers: Vec<String>,
 }
 
 #[derive(Debug, Clone, Default)]
-pub struct DecoratorParser;
+pub struct DecoratorParser {
+    /// Custom decorators recognised alongside `@Query`, `@Mutation` and `@Subscription`, by local name.
+    procedure_decorators: BTreeMap<String, ProcedureDecoratorAlias>,
+}
 
 impl DecoratorParser {
     #[must_use]
     pub const fn new() -> Self {
-        Self
+        Self {
+            procedure_decorators: BTreeMap::new(),
+        }
+    }
+
+    /// Registers custom procedure decorators, keeping aliases that are already registered.
+    #[must_use]
+    pub fn with_procedure_decorators(
+        mut self,
+        procedure_decorators: impl IntoIterator<Item = (String, ProcedureDecoratorAlias)>,
+    ) -> Self {
+        for (name, alias) in procedure_decorators {
+            self.procedure_decorators.entry(name).or_insert(alias);
+        }
+        self
     }
 
     #[must_use]
@@ -41,10 +70,12 @@ impl DecoratorParser {
         decorators: &[Decorator],
         parsed_file: &ParsedFile,
     ) -> Vec<ProcedureDecoratorInfo> {
+        let imports = DecoratorImports::from_module(&parsed_file.module);
+
         decorators
             .iter()
             .filter_map(|decorator| {
-                let info = Self::extract_procedure_decorator(decorator, parsed_file)?;
+                let info = self.extract_procedure_decorator(decorator, &imports, parsed_file)?;
                 debug!(
                     procedure_type = ?info.procedure_type,
                     has_input = info.input.is_some(),
@@ -57,55 +88,40 @@ impl DecoratorParser {
     }
 
     fn extract_procedure_decorator(
+        &self,
         decorator: &Decorator,
+        imports: &DecoratorImports,
         parsed_file: &ParsedFile,
     ) -> Option<ProcedureDecoratorInfo> {
-        match &*decorator.expr {
-            Expr::Call(call_expr) => Self::extract_from_call_expr(call_expr, parsed_file),
-            Expr::Ident(ident) => Self::extract_ident_procedure(ident.sym.as_ref()),
+        let (procedure_type, middlewares) = imports
+            .resolve(decorator)
+            .and_then(procedure_type_from_name)
+            .map(|procedure_type| (procedure_type, Vec::new()))
+            .or_else(|| {
+                let alias = self.procedure_decorators.get(decorator_name(decorator)?)?;
+                Some((alias.procedure_type, alias.middlewares.clone()))
+            })?;
+
+        let first_argument = match &*decorator.expr {
+            Expr::Call(call_expression) => call_expression.args.first(),
             _ => None,
-        }
-    }
-
-    fn extract_ident_procedure(name: &str) -> Option<ProcedureDecoratorInfo> {
-        let procedure_type = match name {
-            "Query" => ProcedureType::Query,
-            "Mutation" => ProcedureType::Mutation,
-            "Subscription" => ProcedureType::Subscription,
-            _ => return None,
         };
 
-        trace!("Found @{} decorator without arguments", name);
-        Some(ProcedureDecoratorInfo {
-            procedure_type,
-            input: None,
-            output: None,
-            input_ref: None,
-            output_ref: None,
-            schema_identifiers: Vec::new(),
-        })
-    }
-
-    fn extract_from_call_expr(
-        call_expression: &CallExpr,
-        parsed_file: &ParsedFile,
-    ) -> Option<ProcedureDecoratorInfo> {
-        let procedure_type = Self::get_procedure_type_from_callee(&call_expression.callee)?;
-
-        trace!(procedure_type = ?procedure_type, "Found procedure decorator call expression");
-
-        if call_expression.args.is_empty() {
+        let Some(first_argument) = first_argument else {
+            trace!(procedure_type = ?procedure_type, "Found procedure decorator without arguments");
             return Some(ProcedureDecoratorInfo {
                 procedure_type,
                 input: None,
                 output: None,
                 input_ref: None,
                 output_ref: None,
+                meta: None,
                 schema_identifiers: Vec::new(),
+                middlewares,
             });
-        }
+        };
 
-        let first_argument = &call_expression.args[0];
+        trace!(procedure_type = ?procedure_type, "Found procedure decorator call expression");
         let arguments = Self::extract_arguments_from_argument(first_argument, parsed_file);
 
         Some(ProcedureDecoratorInfo {
@@ -114,27 +130,12 @@ impl DecoratorParser {
             output: arguments.output,
             input_ref: arguments.input_ref,
             output_ref: arguments.output_ref,
+            meta: arguments.meta,
             schema_identifiers: arguments.schema_identifiers,
+            middlewares,
         })
     }
 
-    fn get_procedure_type_from_callee(callee: &Callee) -> Option<ProcedureType> {
-        let Callee::Expr(expression) = callee else {
-            return None;
-        };
-
-        let Expr::Ident(identifier) = &**expression else {
-            return None;
-        };
-
-        match identifier.sym.as_ref() {
-            "Query" => Some(ProcedureType::Query),
-            "Mutation" => Some(ProcedureType::Mutation),
-            "Subscription" => Some(ProcedureType::Subscription),
-            _ => None,
-        }
-    }
-
     fn extract_arguments_from_argument(
         argument: &ExprOrSpread,
         parsed_file: &ParsedFile,
@@ -192,6 +193,12 @@ impl DecoratorParser {
                 arguments.schema_identifiers.extend(identifiers);
                 trace!(output = ?arguments.output, output_ref = ?arguments.output_ref, "Extracted output property");
             }
+            "meta" => {
+                arguments.meta = Some(Self::extract_value_text(&key_value.value, parsed_file));
+                let identifiers = collect_schema_identifiers(&key_value.value);
+                arguments.schema_identifiers.extend(identifiers);
+                trace!(meta = ?arguments.meta, "Extracted meta property");
+            }
             _ => {}
         }
     }
@@ -277,6 +284,7 @@ impl DecoratorParser {
     }
 }
 
+/// Identifiers a schema expression is built from, including validator roots such as `z` or `v`.
 pub(crate) fn collect_schema_identifiers(expression: &Expr) -> Vec<String> {
     let mut found = HashSet::new();
     walk_expression_for_identifiers(expression, &mut found);
@@ -286,10 +294,7 @@ pub(crate) fn collect_schema_identifiers(expression: &Expr) -> Vec<String> {
 fn walk_expression_for_identifiers(expression: &Expr, found: &mut HashSet<String>) {
     match expression {
         Expr::Ident(identifier) => {
-            let name = identifier.sym.as_ref();
-            if name != "z" {
-                found.insert(name.to_string());
-            }
+            found.insert(identifier.sym.to_string());
         }
         Expr::Member(member_expression) => {
             walk_member_expression_for_root(member_expression, found);
@@ -327,10 +332,7 @@ fn walk_object_properties_for_identifiers(
 fn walk_member_expression_for_root(member_expression: &MemberExpr, found: &mut HashSet<String>) {
     match &*member_expression.obj {
         Expr::Ident(identifier) => {
-            let name = identifier.sym.as_ref();
-            if name != "z" {
-                found.insert(name.to_string());
-            }
+            found.insert(identifier.sym.to_string());
         }
         Expr::Member(nested) => walk_member_expression_for_root(nested, found),
         _ => walk_expression_for_identifiers(&member_expression.obj, found),
@@ -346,27 +348,23 @@ fn walk_call_expression_for_identifiers(call_expression: &CallExpr, found: &mut
     }
 }
 
+/// Whether `decorator` is a procedure decorator, judged by name alone; use
+/// [`DecoratorParser::extract_procedure_decorators`] to also check where it was imported from.
 #[must_use]
 pub fn is_procedure_decorator(decorator: &Decorator) -> bool {
-    match &*decorator.expr {
-        Expr::Call(call_expr) => is_procedure_call(call_expr),
-        Expr::Ident(ident) => is_procedure_name(ident.sym.as_ref()),
-        _ => false,
-    }
+    DecoratorImports::default()
+        .resolve(decorator)
+        .and_then(procedure_type_from_name)
+        .is_some()
 }
 
-fn is_procedure_call(call_expression: &CallExpr) -> bool {
-    let Callee::Expr(expression) = &call_expression.callee else {
-        return false;
-    };
-    let Expr::Ident(ident) = &**expression else {
-        return false;
-    };
-    is_procedure_name(ident.sym.as_ref())
-}
-
-fn is_procedure_name(name: &str) -> bool {
-    name == "Query" || name == "Mutation" || name == "Subscription"
+pub(super) fn procedure_type_from_name(name: &str) -> Option<ProcedureType> {
+    match name {
+        "Query" => Some(ProcedureType::Query),
+        "Mutation" => Some(ProcedureType::Mutation),
+        "Subscription" => Some(ProcedureType::Subscription),
+        _ => None,
+    }
 }
 
 #[cfg(test)]
@@ -437,13 +435,13 @@ mod tests {
     #[test]
     fn test_decorator_parser_creation() {
         let parser = DecoratorParser::new();
-        assert_eq!(std::mem::size_of_val(&parser), 0);
+        assert!(parser.procedure_decorators.is_empty());
     }
 
     #[test]
     fn test_decorator_parser_default() {
-        let parser = DecoratorParser;
-        assert_eq!(std::mem::size_of_val(&parser), 0);
+        let parser = DecoratorParser::default();
+        assert!(parser.procedure_decorators.is_empty());
     }
 
     #[test]
@@ -523,6 +521,35 @@ mod tests {
         assert!(results[0].input.is_some());
     }
 
+    #[test]
+    fn test_extract_query_decorator_with_meta() {
+        let source = r"
+            function Query(opts?: { input?: any; meta?: any }): MethodDecorator {
+                return (target, key, desc) => desc;
+            }
+
+            export class UserRouter {
+                @Query({
+                    input: z.object({ id: z.string() }),
+                    meta: { roles: [Role.Admin], openapi: { method: 'GET' } },
+                })
+                async getUser() {}
+            }
+        ";
+
+        let (_temp, parsed) = parse_file(source);
+        let decorators = get_method_decorators(&parsed, "getUser").expect("Method not found");
+        let decorator_parser = DecoratorParser::new();
+        let results = decorator_parser.extract_procedure_decorators(decorators, &parsed);
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            results[0].meta.as_deref(),
+            Some("{ roles: [Role.Admin], openapi: { method: 'GET' } }")
+        );
+        assert!(results[0].schema_identifiers.contains(&"Role".to_string()));
+    }
+
     #[test]
     fn test_extract_query_without_arguments() {
         let source = r"
@@ -706,6 +733,28 @@ mod tests {
         assert!(is_procedure_decorator(&decorators[0]));
     }
 
+    #[test]
+    fn test_ignores_procedure_names_from_other_packages() {
+        let source = r"
+            import { Query } from '@nestjs/graphql';
+            import { Query as TrpcQuery } from 'nestjs-trpc';
+
+            export class UserRouter {
+                @Query(() => String)
+                @TrpcQuery({ input: z.string() })
+                async getUser() {}
+            }
+        ";
+
+        let (_temp, parsed) = parse_file(source);
+        let decorators = get_method_decorators(&parsed, "getUser").expect("Method not found");
+        let infos = DecoratorParser::new().extract_procedure_decorators(decorators, &parsed);
+
+        assert_eq!(infos.len(), 1);
+        assert_eq!(infos[0].procedure_type, ProcedureType::Query);
+        assert_eq!(infos[0].input, Some("z.string()".to_string()));
+    }
+
     #[test]
     fn test_complex_zod_schema() {
         let source = r"
@@ -823,7 +872,9 @@ mod tests {
             output: None,
             input_ref: None,
             output_ref: None,
+            meta: None,
             schema_identifiers: Vec::new(),
+            middlewares: Vec::new(),
         };
         let info2 = ProcedureDecoratorInfo {
             procedure_type: ProcedureType::Query,
@@ -831,7 +882,9 @@ mod tests {
             output: None,
             input_ref: None,
             output_ref: None,
+            meta: None,
             schema_identifiers: Vec::new(),
+            middlewares: Vec::new(),
         };
         assert_eq!(info1, info2);
     }
@@ -844,7 +897,9 @@ mod tests {
             output: Some("z.void()".to_string()),
             input_ref: None,
             output_ref: None,
+            meta: None,
             schema_identifiers: Vec::new(),
+            middlewares: Vec::new(),
         };
         let cloned = info.clone();
         assert_eq!(info, cloned);
@@ -858,7 +913,9 @@ mod tests {
             output: None,
             input_ref: None,
             output_ref: None,
+            meta: None,
             schema_identifiers: Vec::new(),
+            middlewares: Vec::new(),
         };
         let debug_str = format!("{info:?}");
         assert!(debug_str.contains("Query"));
@@ -22,6 +22,8 @@ pub struct StarExport {
 pub enum BarrelExportMatch {
     Named(BarrelExport),
     Star(StarExport),
+    /// `export * as name from './module'`, re-exporting the whole module under one name.
+    Namespace(StarExport),
     None,
 }
 
@@ -61,6 +63,18 @@ fn find_in_named_export(
         return BarrelExportMatch::None;
     };
 
+    let is_namespace = export_named.specifiers.iter().any(|specifier| {
+        let ExportSpecifier::Namespace(namespace) = specifier else {
+            return false;
+        };
+        get_module_export_name(&namespace.name) == target_name
+    });
+    if is_namespace {
+        return BarrelExportMatch::Namespace(StarExport {
+            source_path: resolved_path,
+        });
+    }
+
     export_named
         .specifiers
         .iter()
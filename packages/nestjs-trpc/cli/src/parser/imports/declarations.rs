@@ -1,11 +1,25 @@
-use super::types::{DeclarationType, ResolvedImport};
+use super::types::{DeclarationType, ResolvedImport, DEFAULT_EXPORT};
 use crate::parser::ParsedFile;
 use swc_common::Span;
-use swc_ecma_ast::{Decl, ExportDecl, ModuleDecl, ModuleItem, Stmt, VarDecl};
+use swc_ecma_ast::{
+    Decl, DefaultDecl, ExportDecl, ExportDefaultExpr, ExportSpecifier, Expr, ModuleDecl,
+    ModuleExportName, ModuleItem, Stmt, VarDecl,
+};
 
 /// Finds a declaration by name in a parsed file.
-/// Searches through all module items for matching declarations.
+/// Searches through all module items for matching declarations, then for the file's default
+/// export and local `export { name as alias }` lists.
 pub fn find_declaration_in_file(parsed: &ParsedFile, name: &str) -> Option<ResolvedImport> {
+    find_local_declaration(parsed, name).or_else(|| {
+        parsed
+            .module
+            .body
+            .iter()
+            .find_map(|item| match_export_item(item, parsed, name))
+    })
+}
+
+fn find_local_declaration(parsed: &ParsedFile, name: &str) -> Option<ResolvedImport> {
     parsed
         .module
         .body
@@ -13,6 +27,89 @@ pub fn find_declaration_in_file(parsed: &ParsedFile, name: &str) -> Option<Resol
         .find_map(|item| match_declaration_item(item, parsed, name))
 }
 
+fn match_export_item(item: &ModuleItem, parsed: &ParsedFile, name: &str) -> Option<ResolvedImport> {
+    match item {
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(export)) if name == DEFAULT_EXPORT => {
+            match_default_expression(export, parsed)
+        }
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(export)) if name == DEFAULT_EXPORT => {
+            let declaration_type = match &export.decl {
+                DefaultDecl::Class(_) => DeclarationType::Class,
+                DefaultDecl::Fn(_) => DeclarationType::Function,
+                DefaultDecl::TsInterfaceDecl(_) => DeclarationType::Interface,
+            };
+            Some(ResolvedImport {
+                name: DEFAULT_EXPORT.to_string(),
+                source_file: parsed.file_path.clone(),
+                declaration_span: export.span,
+                declaration_type,
+            })
+        }
+        ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(export)) if export.src.is_none() => export
+            .specifiers
+            .iter()
+            .find_map(|specifier| match_local_export_specifier(specifier, parsed, name)),
+        _ => None,
+    }
+}
+
+/// `export default userSchema` resolves to the declaration of `userSchema`; any other expression
+/// is a variable in its own right.
+fn match_default_expression(
+    export: &ExportDefaultExpr,
+    parsed: &ParsedFile,
+) -> Option<ResolvedImport> {
+    let resolved = match export.expr.unwrap_parens() {
+        Expr::Ident(identifier) => find_local_declaration(parsed, identifier.sym.as_ref())?,
+        _ => ResolvedImport {
+            name: DEFAULT_EXPORT.to_string(),
+            source_file: parsed.file_path.clone(),
+            declaration_span: export.span,
+            declaration_type: DeclarationType::Variable,
+        },
+    };
+    Some(ResolvedImport {
+        name: DEFAULT_EXPORT.to_string(),
+        ..resolved
+    })
+}
+
+/// `export { userSchema as createUserSchema }`, found under the exported name. The result keeps
+/// the local name so it can be looked up, unless it is exported as `default`.
+fn match_local_export_specifier(
+    specifier: &ExportSpecifier,
+    parsed: &ParsedFile,
+    name: &str,
+) -> Option<ResolvedImport> {
+    let ExportSpecifier::Named(named) = specifier else {
+        return None;
+    };
+    let local_name = module_export_name(&named.orig);
+    let exported_name = named
+        .exported
+        .as_ref()
+        .map_or_else(|| local_name.clone(), module_export_name);
+    if exported_name != name {
+        return None;
+    }
+
+    let resolved = find_local_declaration(parsed, &local_name)?;
+    if name == DEFAULT_EXPORT {
+        return Some(ResolvedImport {
+            name: DEFAULT_EXPORT.to_string(),
+            ..resolved
+        });
+    }
+    Some(resolved)
+}
+
+fn module_export_name(name: &ModuleExportName) -> String {
+    match name {
+        ModuleExportName::Ident(identifier) => identifier.sym.to_string(),
+        ModuleExportName::Str(string) => string.value.to_string_lossy().into_owned(),
+    }
+}
+
 fn match_declaration_item(
     item: &ModuleItem,
     parsed: &ParsedFile,
@@ -2,8 +2,13 @@ mod barrel;
 mod circular;
 mod declarations;
 pub mod module_path;
+mod packages;
+mod path_aliases;
 mod resolver;
 mod types;
 
+pub(crate) use circular::extract_import_paths;
+pub(crate) use packages::package_manifest;
+pub use path_aliases::PathAliases;
 pub use resolver::{build_imports_map, ImportResolver};
-pub use types::{DeclarationType, ImportResult, ResolvedImport};
+pub use types::{DeclarationType, ImportResult, ResolvedImport, DEFAULT_EXPORT};
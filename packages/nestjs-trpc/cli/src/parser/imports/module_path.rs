@@ -1,4 +1,4 @@
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use tracing::trace;
 
 /// Resolves a module specifier to an actual file path.
@@ -40,6 +40,23 @@ pub fn resolve_module_path(source_directory: &Path, specifier: &str) -> Option<P
     None
 }
 
+/// Lexically removes `.` and `..` components, so `src/routers/../schemas/a.ts` and
+/// `src/schemas/a.ts` compare equal without touching the filesystem.
+#[must_use]
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if normalized.file_name().is_some() => {
+                normalized.pop();
+            }
+            _ => normalized.push(component),
+        }
+    }
+    normalized
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,281 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+use tracing::trace;
+
+use super::module_path::resolve_module_path;
+use crate::tsconfig::split_package_reference;
+
+/// `exports` conditions we honour, in priority order; `source` and `types` usually sit closest to the TypeScript.
+const EXPORT_CONDITIONS: [&str; 6] = ["source", "types", "import", "require", "node", "default"];
+
+const BUILD_DIRECTORIES: [&str; 4] = ["dist", "lib", "build", "out"];
+
+const COMPILED_EXTENSIONS: [&str; 9] = [
+    ".d.ts", ".d.mts", ".d.cts", ".js", ".mjs", ".cjs", ".ts", ".tsx", ".mts",
+];
+
+/// Resolves a bare specifier to the TypeScript source of a package installed in `node_modules`.
+///
+/// Symlinked workspace packages are followed to their real location, and `package.json`
+/// `exports`, `types` and `main` are mapped back onto `.ts` sources (e.g. `dist/index.d.ts` to `src/index.ts`).
+pub(super) fn resolve_package_import(source_directory: &Path, specifier: &str) -> Option<PathBuf> {
+    if specifier.is_empty() || specifier.starts_with('.') || specifier.starts_with('/') {
+        return None;
+    }
+
+    let (package_name, subpath) = split_package_reference(specifier);
+    let package_directory = find_package_directory(source_directory, &package_name)?;
+    let resolved = resolve_in_package(&package_directory, &subpath);
+
+    if let Some(ref path) = resolved {
+        trace!(specifier = %specifier, path = ?path, "Resolved workspace package import");
+    }
+
+    resolved
+}
+
+/// The `package.json` that [`resolve_package_import`] reads for a bare specifier, if the
+/// package is installed.
+pub fn package_manifest(source_directory: &Path, specifier: &str) -> Option<PathBuf> {
+    if specifier.is_empty() || specifier.starts_with('.') || specifier.starts_with('/') {
+        return None;
+    }
+
+    let (package_name, _) = split_package_reference(specifier);
+    find_package_directory(source_directory, &package_name)
+        .map(|package_directory| package_directory.join("package.json"))
+}
+
+fn find_package_directory(source_directory: &Path, package_name: &str) -> Option<PathBuf> {
+    source_directory
+        .ancestors()
+        .map(|directory| directory.join("node_modules").join(package_name))
+        .find(|candidate| candidate.join("package.json").is_file())
+        .and_then(|candidate| fs::canonicalize(candidate).ok())
+}
+
+fn resolve_in_package(package_directory: &Path, subpath: &str) -> Option<PathBuf> {
+    let content = fs::read_to_string(package_directory.join("package.json")).ok()?;
+    let manifest: Value = serde_json::from_str(&content).ok()?;
+
+    let targets = match manifest.get("exports") {
+        Some(exports) => export_targets(exports, subpath),
+        None if subpath.is_empty() => ["types", "typings", "main"]
+            .iter()
+            .filter_map(|field| manifest.get(*field).and_then(Value::as_str))
+            .chain(std::iter::once("index"))
+            .map(String::from)
+            .collect(),
+        None => vec![subpath.to_string()],
+    };
+
+    targets
+        .iter()
+        .find_map(|target| resolve_source_file(package_directory, target))
+}
+
+/// Targets of the `exports` entry matching `subpath`, most preferred first.
+fn export_targets(exports: &Value, subpath: &str) -> Vec<String> {
+    let key = if subpath.is_empty() {
+        ".".to_string()
+    } else {
+        format!("./{subpath}")
+    };
+
+    let subpath_map = exports
+        .as_object()
+        .filter(|map| map.keys().any(|entry| entry.starts_with('.')));
+
+    let mut targets = Vec::new();
+    let Some(subpath_map) = subpath_map else {
+        if key == "." {
+            collect_condition_targets(exports, "", &mut targets);
+        }
+        return targets;
+    };
+
+    if let Some(entry) = subpath_map.get(&key) {
+        collect_condition_targets(entry, "", &mut targets);
+        return targets;
+    }
+
+    let wildcard_match = subpath_map
+        .iter()
+        .filter_map(|(pattern, entry)| {
+            let (prefix, suffix) = pattern.split_once('*')?;
+            let wildcard = key.strip_prefix(prefix)?.strip_suffix(suffix)?;
+            Some((prefix.len(), entry, wildcard))
+        })
+        .max_by_key(|(prefix_length, _, _)| *prefix_length);
+
+    if let Some((_, entry, wildcard)) = wildcard_match {
+        collect_condition_targets(entry, wildcard, &mut targets);
+    }
+
+    targets
+}
+
+fn collect_condition_targets(entry: &Value, wildcard: &str, targets: &mut Vec<String>) {
+    match entry {
+        Value::String(target) => targets.push(target.replace('*', wildcard)),
+        Value::Array(alternatives) => {
+            for alternative in alternatives {
+                collect_condition_targets(alternative, wildcard, targets);
+            }
+        }
+        Value::Object(conditions) => {
+            for nested in EXPORT_CONDITIONS
+                .iter()
+                .filter_map(|condition| conditions.get(*condition))
+            {
+                collect_condition_targets(nested, wildcard, targets);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Maps a `package.json` target onto a `.ts`/`.tsx` source file, looking in `src` for build outputs.
+fn resolve_source_file(package_directory: &Path, target: &str) -> Option<PathBuf> {
+    let target = target.strip_prefix("./").unwrap_or(target);
+    let stem = COMPILED_EXTENSIONS
+        .iter()
+        .find_map(|extension| target.strip_suffix(extension))
+        .unwrap_or(target);
+
+    let source_stem = stem.split_once('/').and_then(|(first, rest)| {
+        BUILD_DIRECTORIES
+            .contains(&first)
+            .then(|| format!("src/{rest}"))
+    });
+
+    std::iter::once(stem.to_string())
+        .chain(source_stem)
+        .filter_map(|candidate| resolve_module_path(package_directory, &candidate))
+        .find(|path| is_typescript_source(path))
+}
+
+fn is_typescript_source(path: &Path) -> bool {
+    let is_declaration = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.contains(".d."));
+
+    path.is_file()
+        && !is_declaration
+        && path
+            .extension()
+            .is_some_and(|extension| extension == "ts" || extension == "tsx")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_file(directory: &Path, name: &str, content: &str) {
+        let path = directory.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn resolve(root: &Path, specifier: &str) -> Option<PathBuf> {
+        resolve_package_import(&root.join("apps/api/src"), specifier)
+    }
+
+    #[test]
+    fn test_resolves_exports_types_condition_to_source() {
+        let temporary_directory = TempDir::new().unwrap();
+        let root = temporary_directory.path();
+        let package = root.join("node_modules/@acme/contracts");
+        write_file(
+            &package,
+            "package.json",
+            r#"{ "exports": { ".": { "types": "./dist/index.d.ts", "require": "./dist/index.js" } } }"#,
+        );
+        write_file(&package, "src/index.ts", "export const a = 1;");
+
+        let resolved = resolve(root, "@acme/contracts").unwrap();
+        assert!(resolved.ends_with("contracts/src/index.ts"));
+    }
+
+    #[test]
+    fn test_resolves_exports_wildcard_subpath() {
+        let temporary_directory = TempDir::new().unwrap();
+        let root = temporary_directory.path();
+        let package = root.join("node_modules/contracts");
+        write_file(
+            &package,
+            "package.json",
+            r#"{ "exports": { ".": "./src/index.ts", "./schemas/*": "./src/schemas/*.ts" } }"#,
+        );
+        write_file(&package, "src/schemas/user.ts", "export const a = 1;");
+
+        let resolved = resolve(root, "contracts/schemas/user").unwrap();
+        assert!(resolved.ends_with("src/schemas/user.ts"));
+    }
+
+    #[test]
+    fn test_unexported_subpath_is_unresolved() {
+        let temporary_directory = TempDir::new().unwrap();
+        let root = temporary_directory.path();
+        let package = root.join("node_modules/contracts");
+        write_file(
+            &package,
+            "package.json",
+            r#"{ "exports": { ".": "./src/index.ts" } }"#,
+        );
+        write_file(&package, "src/index.ts", "export const a = 1;");
+        write_file(&package, "src/internal.ts", "export const a = 1;");
+
+        assert_eq!(resolve(root, "contracts/src/internal"), None);
+    }
+
+    #[test]
+    fn test_falls_back_to_types_and_main() {
+        let temporary_directory = TempDir::new().unwrap();
+        let root = temporary_directory.path();
+        let package = root.join("node_modules/contracts");
+        write_file(
+            &package,
+            "package.json",
+            r#"{ "types": "./lib/index.d.ts", "main": "./lib/index.js" }"#,
+        );
+        write_file(&package, "src/index.ts", "export const a = 1;");
+
+        let resolved = resolve(root, "contracts").unwrap();
+        assert!(resolved.ends_with("src/index.ts"));
+    }
+
+    #[test]
+    fn test_declaration_only_package_is_unresolved() {
+        let temporary_directory = TempDir::new().unwrap();
+        let root = temporary_directory.path();
+        let package = root.join("node_modules/zod");
+        write_file(&package, "package.json", r#"{ "types": "./index.d.ts" }"#);
+        write_file(&package, "index.d.ts", "export declare const z: any;");
+
+        assert_eq!(resolve(root, "zod"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_follows_workspace_symlink() {
+        let temporary_directory = TempDir::new().unwrap();
+        let root = temporary_directory.path();
+        let package = root.join("packages/contracts");
+        write_file(&package, "package.json", r#"{ "main": "./src/index.ts" }"#);
+        write_file(&package, "src/index.ts", "export const a = 1;");
+        fs::create_dir_all(root.join("apps/api/node_modules/@acme")).unwrap();
+        std::os::unix::fs::symlink(&package, root.join("apps/api/node_modules/@acme/contracts"))
+            .unwrap();
+
+        let resolved = resolve(root, "@acme/contracts").unwrap();
+        assert_eq!(
+            resolved,
+            fs::canonicalize(package.join("src/index.ts")).unwrap()
+        );
+    }
+}
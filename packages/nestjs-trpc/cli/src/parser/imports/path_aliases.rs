@@ -0,0 +1,215 @@
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value};
+use tracing::trace;
+
+use super::module_path::resolve_module_path;
+use crate::tsconfig::{resolve_compiler_options, PATHS_BASE_PATH_KEY};
+
+/// `compilerOptions.paths` and `baseUrl` mappings for non-relative module specifiers.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathAliases {
+    base_url: Option<PathBuf>,
+    paths_base: PathBuf,
+    patterns: Vec<(String, Vec<String>)>,
+}
+
+impl PathAliases {
+    /// Loads aliases from the nearest `tsconfig.json` above `start_directory`.
+    #[must_use]
+    pub fn load(start_directory: &Path) -> Option<Self> {
+        let options = resolve_compiler_options(start_directory)?;
+        Self::from_compiler_options(&options)
+    }
+
+    /// Builds aliases from merged compiler options; `None` when neither `paths` nor `baseUrl` is set.
+    #[must_use]
+    pub fn from_compiler_options(options: &Map<String, Value>) -> Option<Self> {
+        let base_url = options
+            .get("baseUrl")
+            .and_then(Value::as_str)
+            .map(PathBuf::from);
+
+        let patterns: Vec<(String, Vec<String>)> = options
+            .get("paths")
+            .and_then(Value::as_object)
+            .map(|paths| {
+                paths
+                    .iter()
+                    .map(|(pattern, targets)| (pattern.clone(), string_array(targets)))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let paths_base = base_url.clone().or_else(|| {
+            options
+                .get(PATHS_BASE_PATH_KEY)
+                .and_then(Value::as_str)
+                .map(PathBuf::from)
+        })?;
+
+        Some(Self {
+            base_url,
+            paths_base,
+            patterns,
+        })
+    }
+
+    /// Maps a non-relative specifier onto a source file, trying `paths` first and `baseUrl` last.
+    #[must_use]
+    pub fn resolve(&self, specifier: &str) -> Option<PathBuf> {
+        let resolved = self.resolve_from_paths(specifier).or_else(|| {
+            let base_url = self.base_url.as_ref()?;
+            resolve_file(base_url, specifier)
+        });
+
+        if let Some(ref path) = resolved {
+            trace!(specifier = %specifier, path = ?path, "Resolved path alias");
+        }
+
+        resolved
+    }
+
+    fn resolve_from_paths(&self, specifier: &str) -> Option<PathBuf> {
+        let (targets, wildcard) = self.match_pattern(specifier)?;
+        targets
+            .iter()
+            .find_map(|target| resolve_file(&self.paths_base, &target.replace('*', wildcard)))
+    }
+
+    /// Exact patterns win; otherwise the wildcard pattern with the longest prefix, as in `tsc`.
+    fn match_pattern<'a>(&'a self, specifier: &'a str) -> Option<(&'a [String], &'a str)> {
+        if let Some((_, targets)) = self
+            .patterns
+            .iter()
+            .find(|(pattern, _)| pattern == specifier)
+        {
+            return Some((targets.as_slice(), ""));
+        }
+
+        self.patterns
+            .iter()
+            .rev()
+            .filter_map(|(pattern, targets)| {
+                let (prefix, suffix) = pattern.split_once('*')?;
+                let wildcard = specifier.strip_prefix(prefix)?.strip_suffix(suffix)?;
+                Some((prefix.len(), targets.as_slice(), wildcard))
+            })
+            .max_by_key(|(prefix_length, _, _)| *prefix_length)
+            .map(|(_, targets, wildcard)| (targets, wildcard))
+    }
+}
+
+fn string_array(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(Value::as_str)
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn resolve_file(base_directory: &Path, specifier: &str) -> Option<PathBuf> {
+    resolve_module_path(base_directory, specifier).filter(|path| path.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn write_file(directory: &Path, name: &str, content: &str) {
+        let path = directory.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn aliases(base: &Path, options: &Value) -> PathAliases {
+        let mut options = options.as_object().unwrap().clone();
+        options.insert(
+            PATHS_BASE_PATH_KEY.to_string(),
+            Value::String(base.to_string_lossy().into_owned()),
+        );
+        PathAliases::from_compiler_options(&options).unwrap()
+    }
+
+    #[test]
+    fn test_resolve_wildcard_pattern() {
+        let temporary_directory = TempDir::new().unwrap();
+        let base = temporary_directory.path();
+        write_file(base, "src/schemas/index.ts", "export const a = 1;");
+
+        let aliases = aliases(base, &serde_json::json!({ "paths": { "@/*": ["src/*"] } }));
+
+        let resolved = aliases.resolve("@/schemas").unwrap();
+        assert!(resolved.ends_with("src/schemas/index.ts"));
+    }
+
+    #[test]
+    fn test_resolve_exact_pattern() {
+        let temporary_directory = TempDir::new().unwrap();
+        let base = temporary_directory.path();
+        write_file(base, "src/shared/schemas.ts", "export const a = 1;");
+
+        let aliases = aliases(
+            base,
+            &serde_json::json!({ "paths": { "@schemas": ["src/shared/schemas.ts"] } }),
+        );
+
+        let resolved = aliases.resolve("@schemas").unwrap();
+        assert!(resolved.ends_with("src/shared/schemas.ts"));
+    }
+
+    #[test]
+    fn test_longest_prefix_wins() {
+        let temporary_directory = TempDir::new().unwrap();
+        let base = temporary_directory.path();
+        write_file(base, "generic/user.ts", "export const a = 1;");
+        write_file(base, "specific/user.ts", "export const a = 1;");
+
+        let aliases = aliases(
+            base,
+            &serde_json::json!({ "paths": { "@app/*": ["generic/*"], "@app/models/*": ["specific/*"] } }),
+        );
+
+        let resolved = aliases.resolve("@app/models/user").unwrap();
+        assert!(resolved.ends_with("specific/user.ts"));
+    }
+
+    #[test]
+    fn test_falls_back_to_base_url() {
+        let temporary_directory = TempDir::new().unwrap();
+        let base = temporary_directory.path();
+        write_file(base, "src/common/schemas.ts", "export const a = 1;");
+
+        let options = serde_json::json!({ "baseUrl": base.join("src").to_string_lossy() });
+        let aliases = PathAliases::from_compiler_options(options.as_object().unwrap()).unwrap();
+
+        let resolved = aliases.resolve("common/schemas").unwrap();
+        assert!(resolved.ends_with("src/common/schemas.ts"));
+    }
+
+    #[test]
+    fn test_unmatched_specifier_is_unresolved() {
+        let temporary_directory = TempDir::new().unwrap();
+        let base = temporary_directory.path();
+
+        let aliases = aliases(base, &serde_json::json!({ "paths": { "@/*": ["src/*"] } }));
+
+        assert_eq!(aliases.resolve("zod"), None);
+    }
+
+    #[test]
+    fn test_no_aliases_configured() {
+        let options = serde_json::json!({ "strict": true });
+        assert_eq!(
+            PathAliases::from_compiler_options(options.as_object().unwrap()),
+            None
+        );
+    }
+}
@@ -2,11 +2,16 @@ use super::barrel::{find_export_in_barrel_item, BarrelExportMatch};
 use super::circular::{extract_import_paths, CircularImportTracker};
 use super::declarations::find_declaration_in_file;
 use super::module_path::resolve_module_path;
-use super::types::{ImportResult, ResolvedImport};
+use super::packages::resolve_package_import;
+use super::path_aliases::PathAliases;
+use super::types::{ImportResult, ResolvedImport, DEFAULT_EXPORT};
 use crate::error::ImportError;
+use crate::parser::cache::ParseCache;
 use crate::parser::{ParsedFile, TsParser};
+use std::cell::OnceCell;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use swc_ecma_ast::{ModuleDecl, ModuleExportName, ModuleItem};
 use tracing::{debug, trace, warn};
 
@@ -15,8 +20,9 @@ const DEFAULT_MAX_IMPORT_DEPTH: usize = 10;
 #[derive(Debug)]
 pub struct ImportResolver<'a> {
     parser: &'a TsParser,
-    parsed_cache: HashMap<PathBuf, ParsedFile>,
+    parse_cache: ParseCache,
     max_depth: usize,
+    path_aliases: OnceCell<Option<PathAliases>>,
 }
 
 impl<'a> ImportResolver<'a> {
@@ -24,8 +30,9 @@ impl<'a> ImportResolver<'a> {
     pub fn new(parser: &'a TsParser) -> Self {
         Self {
             parser,
-            parsed_cache: HashMap::new(),
+            parse_cache: ParseCache::new(),
             max_depth: DEFAULT_MAX_IMPORT_DEPTH,
+            path_aliases: OnceCell::new(),
         }
     }
 
@@ -35,6 +42,20 @@ impl<'a> ImportResolver<'a> {
         self
     }
 
+    /// Shares parsed files with other resolvers and flatteners, including across threads.
+    #[must_use]
+    pub fn with_parse_cache(mut self, parse_cache: ParseCache) -> Self {
+        self.parse_cache = parse_cache;
+        self
+    }
+
+    /// Uses the given aliases instead of loading them from the `tsconfig.json` above the base directory.
+    #[must_use]
+    pub fn with_path_aliases(self, path_aliases: Option<PathAliases>) -> Self {
+        let _ = self.path_aliases.set(path_aliases);
+        self
+    }
+
     pub fn build_imports_map(
         &mut self,
         source_file: &ParsedFile,
@@ -70,12 +91,13 @@ impl<'a> ImportResolver<'a> {
         };
 
         let module_specifier = import_declaration.src.value.to_string_lossy().into_owned();
-        if !is_relative_path(&module_specifier) {
-            trace!(module = %module_specifier, "Skipping external import");
-            return;
-        }
+        let resolved_path = if is_relative_path(&module_specifier) {
+            resolve_module_path(source_directory, &module_specifier)
+        } else {
+            self.resolve_bare_specifier(&module_specifier, source_directory, base_directory)
+        };
 
-        let Some(resolved_path) = resolve_module_path(source_directory, &module_specifier) else {
+        let Some(resolved_path) = resolved_path else {
             trace!(module = %module_specifier, "Could not resolve module path");
             return;
         };
@@ -88,6 +110,20 @@ impl<'a> ImportResolver<'a> {
         );
     }
 
+    /// Tries `tsconfig.json` path aliases first, then packages installed in `node_modules`.
+    fn resolve_bare_specifier(
+        &self,
+        module_specifier: &str,
+        source_directory: &Path,
+        base_directory: &Path,
+    ) -> Option<PathBuf> {
+        self.path_aliases
+            .get_or_init(|| PathAliases::load(base_directory))
+            .as_ref()
+            .and_then(|aliases| aliases.resolve(module_specifier))
+            .or_else(|| resolve_package_import(source_directory, module_specifier))
+    }
+
     fn process_import_specifiers(
         &mut self,
         specifiers: &[swc_ecma_ast::ImportSpecifier],
@@ -107,21 +143,34 @@ impl<'a> ImportResolver<'a> {
         base_directory: &Path,
         imports_map: &mut HashMap<String, ResolvedImport>,
     ) {
-        let swc_ecma_ast::ImportSpecifier::Named(named) = specifier else {
-            return;
+        let (local_name, imported_name) = match specifier {
+            swc_ecma_ast::ImportSpecifier::Named(named) => {
+                let local_name = named.local.sym.to_string();
+                let imported_name = named
+                    .imported
+                    .as_ref()
+                    .map_or_else(|| local_name.clone(), get_module_export_name);
+                (local_name, imported_name)
+            }
+            swc_ecma_ast::ImportSpecifier::Default(default) => {
+                (default.local.sym.to_string(), DEFAULT_EXPORT.to_string())
+            }
+            swc_ecma_ast::ImportSpecifier::Namespace(namespace) => {
+                let local_name = namespace.local.sym.to_string();
+                trace!(local = %local_name, path = ?resolved_path, "Resolved namespace import");
+                imports_map.insert(
+                    local_name,
+                    ResolvedImport::namespace(resolved_path.to_path_buf()),
+                );
+                return;
+            }
         };
 
-        let local_name = named.local.sym.to_string();
-        let imported_name = named
-            .imported
-            .as_ref()
-            .map_or_else(|| local_name.clone(), get_module_export_name);
-
         trace!(
             name = %imported_name,
             local = %local_name,
             path = ?resolved_path,
-            "Resolving named import"
+            "Resolving import specifier"
         );
 
         match self.resolve_import(resolved_path, &imported_name, base_directory, 0) {
@@ -138,6 +187,16 @@ impl<'a> ImportResolver<'a> {
         }
     }
 
+    /// Resolves `name` as exported by the module at `file_path`, following barrel re-exports.
+    pub fn resolve_export(
+        &mut self,
+        file_path: &Path,
+        name: &str,
+        base_directory: &Path,
+    ) -> ImportResult<ResolvedImport> {
+        self.resolve_import(file_path, name, base_directory, 0)
+    }
+
     fn resolve_import(
         &mut self,
         file_path: &Path,
@@ -182,22 +241,13 @@ impl<'a> ImportResolver<'a> {
         )
     }
 
-    fn get_or_parse_file(&mut self, file_path: &Path) -> ImportResult<ParsedFile> {
-        if let Some(cached) = self.parsed_cache.get(file_path) {
-            return Ok(cached.clone());
-        }
-
-        let parsed =
-            self.parser
-                .parse_file(file_path)
-                .map_err(|_error| ImportError::ModuleNotFound {
-                    module: file_path.display().to_string(),
-                    source_path: file_path.to_path_buf(),
-                })?;
-
-        self.parsed_cache
-            .insert(file_path.to_path_buf(), parsed.clone());
-        Ok(parsed)
+    fn get_or_parse_file(&self, file_path: &Path) -> ImportResult<Arc<ParsedFile>> {
+        self.parse_cache
+            .get_or_parse(self.parser, file_path)
+            .map_err(|_error| ImportError::ModuleNotFound {
+                module: file_path.display().to_string(),
+                source_path: file_path.to_path_buf(),
+            })
     }
 
     fn resolve_barrel_file_import(
@@ -266,6 +316,9 @@ impl<'a> ImportResolver<'a> {
                     self.resolve_import(&export.source_path, name, base_directory, depth);
                 Ok(resolved.ok())
             }
+            BarrelExportMatch::Namespace(export) => {
+                Ok(Some(ResolvedImport::namespace(export.source_path)))
+            }
             BarrelExportMatch::None => Ok(None),
         }
     }
@@ -781,6 +834,63 @@ mod tests {
         assert!(!imports.contains_key("something"));
     }
 
+    #[test]
+    fn test_resolve_default_and_namespace_imports() {
+        let temp_directory = create_temp_project();
+        let base = temp_directory.path();
+
+        write_file(
+            base,
+            "schemas/user.ts",
+            r"
+            import { z } from 'zod';
+            const userSchema = z.object({ name: z.string() });
+            export default userSchema;
+        ",
+        );
+
+        write_file(
+            base,
+            "schemas/index.ts",
+            r"
+            export * as users from './user';
+        ",
+        );
+
+        let main_path = write_file(
+            base,
+            "main.ts",
+            r"
+            import userSchema from './schemas/user';
+            import * as userModule from './schemas/user';
+            import { users } from './schemas';
+        ",
+        );
+
+        let parser = TsParser::new();
+        let parsed = parser.parse_file(&main_path).expect("Failed to parse");
+
+        let mut resolver = ImportResolver::new(&parser);
+        let imports = resolver
+            .build_imports_map(&parsed, base)
+            .expect("Failed to build imports map");
+
+        let default_import = imports.get("userSchema").unwrap();
+        assert_eq!(default_import.name, DEFAULT_EXPORT);
+        assert_eq!(default_import.declaration_type, DeclarationType::Variable);
+        assert!(!default_import.is_named_export());
+
+        for namespace in ["userModule", "users"] {
+            let namespace_import = imports.get(namespace).unwrap();
+            assert_eq!(
+                namespace_import.declaration_type,
+                DeclarationType::Namespace
+            );
+            assert!(namespace_import.source_file.ends_with("user.ts"));
+            assert!(!namespace_import.is_named_export());
+        }
+    }
+
     #[test]
     fn test_max_depth_exceeded() {
         let parser = TsParser::new();
@@ -796,6 +906,7 @@ mod tests {
         assert_eq!(format!("{}", DeclarationType::Enum), "enum");
         assert_eq!(format!("{}", DeclarationType::Function), "function");
         assert_eq!(format!("{}", DeclarationType::TypeAlias), "type alias");
+        assert_eq!(format!("{}", DeclarationType::Namespace), "namespace");
         assert_eq!(format!("{}", DeclarationType::Unknown), "unknown");
     }
 
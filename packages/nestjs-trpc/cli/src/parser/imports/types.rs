@@ -1,18 +1,25 @@
 use crate::error::ImportError;
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use swc_common::Span;
 
 pub type ImportResult<T> = std::result::Result<T, ImportError>;
 
-#[derive(Debug, Clone)]
+/// The name a module's default export resolves under.
+pub const DEFAULT_EXPORT: &str = "default";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResolvedImport {
+    /// The exported name, [`DEFAULT_EXPORT`] for default imports, or `*` for namespaces.
     pub name: String,
     pub source_file: PathBuf,
+    /// Only meaningful within the source map that produced it, so it is not persisted.
+    #[serde(skip)]
     pub declaration_span: Span,
     pub declaration_type: DeclarationType,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DeclarationType {
     Variable,
     Class,
@@ -20,6 +27,8 @@ pub enum DeclarationType {
     Enum,
     Function,
     TypeAlias,
+    /// A whole module, imported with `import * as name` or re-exported with `export * as name`.
+    Namespace,
     Unknown,
 }
 
@@ -32,7 +41,25 @@ impl std::fmt::Display for DeclarationType {
             Self::Enum => write!(f, "enum"),
             Self::Function => write!(f, "function"),
             Self::TypeAlias => write!(f, "type alias"),
+            Self::Namespace => write!(f, "namespace"),
             Self::Unknown => write!(f, "unknown"),
         }
     }
 }
+
+impl ResolvedImport {
+    pub(crate) fn namespace(source_file: PathBuf) -> Self {
+        Self {
+            name: "*".to_string(),
+            source_file,
+            declaration_span: Span::default(),
+            declaration_type: DeclarationType::Namespace,
+        }
+    }
+
+    /// Whether `server.ts` can import this binding by name from its declaring file.
+    #[must_use]
+    pub fn is_named_export(&self) -> bool {
+        self.declaration_type != DeclarationType::Namespace && self.name != DEFAULT_EXPORT
+    }
+}
@@ -0,0 +1,157 @@
+use std::collections::HashSet;
+use std::iter;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use tracing::trace;
+
+use super::procedure::find_class_by_name;
+use super::{ParsedFile, ResolverContext};
+
+/// A class a router extends, with the file it is declared in.
+#[derive(Debug, Clone)]
+pub struct BaseClass {
+    pub class_name: String,
+    pub parsed_file: Arc<ParsedFile>,
+}
+
+/// Follows `extends` clauses to the classes they name, locally or through imports and barrel
+/// files.
+#[derive(Debug, Clone)]
+pub struct BaseClassResolver<'a> {
+    resolver: &'a ResolverContext<'a>,
+}
+
+impl<'a> BaseClassResolver<'a> {
+    #[must_use]
+    pub const fn new(resolver: &'a ResolverContext<'a>) -> Self {
+        Self { resolver }
+    }
+
+    /// The classes `class_name` inherits from, nearest first. Stops at the first base class that
+    /// can't be resolved, such as one from a package or a mixin call.
+    #[must_use]
+    pub fn resolve_base_classes(
+        &self,
+        parsed_file: &ParsedFile,
+        class_name: &str,
+    ) -> Vec<BaseClass> {
+        let mut visited: HashSet<(PathBuf, String)> =
+            HashSet::from([(parsed_file.file_path.clone(), class_name.to_string())]);
+
+        iter::successors(
+            self.find_base_class(parsed_file, class_name),
+            |base_class| self.find_base_class(&base_class.parsed_file, &base_class.class_name),
+        )
+        .take_while(|base_class| {
+            visited.insert((
+                base_class.parsed_file.file_path.clone(),
+                base_class.class_name.clone(),
+            ))
+        })
+        .collect()
+    }
+
+    fn find_base_class(&self, parsed_file: &ParsedFile, class_name: &str) -> Option<BaseClass> {
+        let class = find_class_by_name(&parsed_file.module.body, class_name)?;
+        let super_class = class.super_class.as_deref()?.unwrap_parens().as_ident()?;
+        let super_name = super_class.sym.as_ref();
+        trace!(class = %class_name, base = %super_name, "Following base class");
+
+        if find_class_by_name(&parsed_file.module.body, super_name).is_some() {
+            return self.parse(&parsed_file.file_path, super_name);
+        }
+
+        let resolved = self.resolver.resolve_import(parsed_file, super_name)?;
+        self.parse(&resolved.source_file, &resolved.name)
+    }
+
+    fn parse(&self, path: &Path, class_name: &str) -> Option<BaseClass> {
+        let parsed_file = self.resolver.parse(path)?;
+        find_class_by_name(&parsed_file.module.body, class_name)?;
+
+        Some(BaseClass {
+            class_name: class_name.to_string(),
+            parsed_file,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{ParseCache, TsParser};
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn resolve(directory: &Path, router_source: &str) -> Vec<(String, PathBuf)> {
+        let router_path = directory.join("users.router.ts");
+        fs::write(&router_path, router_source).unwrap();
+
+        let typescript_parser = TsParser::new();
+        let parse_cache = ParseCache::new();
+        let parsed = typescript_parser.parse_file(&router_path).unwrap();
+
+        let resolver = ResolverContext::new(&typescript_parser, &parse_cache, directory);
+        BaseClassResolver::new(&resolver)
+            .resolve_base_classes(&parsed, "UsersRouter")
+            .into_iter()
+            .map(|base_class| {
+                let file_name = base_class.parsed_file.file_path.file_name().unwrap().into();
+                (base_class.class_name, file_name)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_follows_imported_base_classes_through_barrels() {
+        let temporary_directory = TempDir::new().unwrap();
+        let directory = temporary_directory.path();
+        fs::write(
+            directory.join("base.router.ts"),
+            "class Auditable {}\nexport abstract class BaseCrudRouter<T> extends Auditable {}",
+        )
+        .unwrap();
+        fs::write(directory.join("index.ts"), "export * from './base.router';").unwrap();
+
+        let base_classes = resolve(
+            directory,
+            r"
+            import { BaseCrudRouter } from './index';
+            export class UsersRouter extends BaseCrudRouter<User> {}
+            ",
+        );
+
+        assert_eq!(
+            base_classes,
+            vec![
+                (
+                    "BaseCrudRouter".to_string(),
+                    PathBuf::from("base.router.ts")
+                ),
+                ("Auditable".to_string(), PathBuf::from("base.router.ts")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stops_at_unresolvable_and_circular_bases() {
+        let temporary_directory = TempDir::new().unwrap();
+        let directory = temporary_directory.path();
+
+        let external = resolve(
+            directory,
+            "import { Base } from 'some-package';\nexport class UsersRouter extends Base {}",
+        );
+        let circular = resolve(
+            directory,
+            "class A extends UsersRouter {}\nexport class UsersRouter extends A {}",
+        );
+
+        assert!(external.is_empty());
+        assert_eq!(
+            circular,
+            vec![("A".to_string(), PathBuf::from("users.router.ts"))]
+        );
+    }
+}
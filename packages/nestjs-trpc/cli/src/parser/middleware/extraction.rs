@@ -1,5 +1,3 @@
-use crate::parser::ParsedFile;
-use swc_common::Spanned;
 use swc_ecma_ast::{
     BlockStmt, CallExpr, Callee, Expr, Lit, MemberExpr, MemberProp, ObjectLit, Prop, PropName,
     PropOrSpread, Stmt,
@@ -120,11 +118,7 @@ impl MiddlewareParser {
         property.sym.as_ref() == "next"
     }
 
-    pub(super) fn extract_context_properties(
-        &self,
-        call: &CallExpr,
-        parsed_file: &ParsedFile,
-    ) -> Vec<ContextProperty> {
+    pub(super) fn extract_context_properties(&self, call: &CallExpr) -> Vec<ContextProperty> {
         let Some(first_argument) = call.args.first() else {
             return Vec::new();
         };
@@ -137,7 +131,7 @@ impl MiddlewareParser {
             return Vec::new();
         };
 
-        self.extract_properties_from_object(context_object, parsed_file)
+        self.extract_properties_from_object(context_object)
     }
 
     #[allow(clippy::unused_self)]
@@ -154,23 +148,15 @@ impl MiddlewareParser {
         result
     }
 
-    fn extract_properties_from_object(
-        &self,
-        object: &ObjectLit,
-        parsed_file: &ParsedFile,
-    ) -> Vec<ContextProperty> {
+    fn extract_properties_from_object(&self, object: &ObjectLit) -> Vec<ContextProperty> {
         object
             .props
             .iter()
-            .filter_map(|property| self.extract_single_property(property, parsed_file))
+            .filter_map(|property| self.extract_single_property(property))
             .collect()
     }
 
-    fn extract_single_property(
-        &self,
-        property: &PropOrSpread,
-        parsed_file: &ParsedFile,
-    ) -> Option<ContextProperty> {
+    fn extract_single_property(&self, property: &PropOrSpread) -> Option<ContextProperty> {
         let PropOrSpread::Prop(property_box) = property else {
             return None;
         };
@@ -178,7 +164,7 @@ impl MiddlewareParser {
         match &**property_box {
             Prop::KeyValue(key_value) => {
                 let name = self.get_property_key_name(&key_value.key)?;
-                let type_string = self.infer_type_from_expression(&key_value.value, parsed_file);
+                let type_string = self.infer_type_from_expression(&key_value.value);
                 Some(ContextProperty { name, type_string })
             }
             Prop::Shorthand(identifier) => {
@@ -201,13 +187,18 @@ impl MiddlewareParser {
         }
     }
 
-    fn infer_type_from_expression(&self, expression: &Expr, parsed_file: &ParsedFile) -> String {
+    /// The type of a `ctx` value, as far as it can be written without importing anything.
+    ///
+    /// Identifiers and object literals name values rather than types, so they become `unknown`.
+    fn infer_type_from_expression(&self, expression: &Expr) -> String {
         match expression {
             Expr::Lit(literal) => self.type_from_literal(literal),
-            Expr::Object(_) => parsed_file.get_source_text(expression.span()),
+            Expr::Tpl(_) => "string".to_string(),
             Expr::Array(_) => "unknown[]".to_string(),
             Expr::Arrow(_) | Expr::Fn(_) => "Function".to_string(),
-            Expr::Ident(identifier) => identifier.sym.to_string(),
+            Expr::Ident(identifier) if identifier.sym.as_ref() == "undefined" => {
+                "undefined".to_string()
+            }
             _ => "unknown".to_string(),
         }
     }
@@ -227,7 +218,13 @@ impl MiddlewareParser {
 
 #[must_use]
 pub fn extract_use_middlewares_names(decorator: &swc_ecma_ast::Decorator) -> Option<Vec<String>> {
-    let Expr::Call(call_expression) = &*decorator.expr else {
+    extract_use_middlewares_call_names(&decorator.expr)
+}
+
+/// Middleware names passed to a `UseMiddlewares(...)` call, wherever the call appears.
+#[must_use]
+pub fn extract_use_middlewares_call_names(expression: &Expr) -> Option<Vec<String>> {
+    let Expr::Call(call_expression) = expression else {
         return None;
     };
 
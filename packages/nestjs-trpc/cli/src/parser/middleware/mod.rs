@@ -2,10 +2,12 @@ mod extraction;
 
 use crate::parser::ParsedFile;
 use std::path::PathBuf;
-use swc_ecma_ast::{Class, ClassMember, ClassMethod, Decl, ModuleDecl, ModuleItem, PropName, Stmt};
+use swc_ecma_ast::{
+    Class, ClassMember, ClassMethod, Decl, Decorator, ModuleDecl, ModuleItem, PropName, Stmt,
+};
 use tracing::{debug, trace};
 
-pub use extraction::extract_use_middlewares_names;
+pub use extraction::{extract_use_middlewares_call_names, extract_use_middlewares_names};
 
 fn filter_use_method(member: &ClassMember) -> Option<&ClassMethod> {
     let ClassMember::Method(method) = member else {
@@ -141,7 +143,7 @@ impl MiddlewareParser {
 
         let context_properties = self
             .find_next_call(body)
-            .map(|next_call| self.extract_context_properties(next_call, parsed_file))
+            .map(|next_call| self.extract_context_properties(next_call))
             .unwrap_or_default();
 
         Some(MiddlewareInfo {
@@ -176,17 +178,22 @@ pub fn extract_middleware(
 
 #[must_use]
 pub fn extract_middleware_names_from_class(class: &Class) -> Vec<String> {
-    let class_middlewares = extract_class_level_middlewares(class);
+    let class_middlewares = extract_middleware_names_from_decorators(&class.decorators);
     let method_middlewares = extract_method_level_middlewares(class);
-    class_middlewares.chain(method_middlewares).collect()
+    class_middlewares
+        .into_iter()
+        .chain(method_middlewares)
+        .collect()
 }
 
-fn extract_class_level_middlewares(class: &Class) -> impl Iterator<Item = String> + '_ {
-    class
-        .decorators
+/// Collects `@UseMiddlewares` names from a decorator list, in declaration order.
+#[must_use]
+pub fn extract_middleware_names_from_decorators(decorators: &[Decorator]) -> Vec<String> {
+    decorators
         .iter()
         .filter_map(extract_use_middlewares_names)
         .flatten()
+        .collect()
 }
 
 fn extract_method_level_middlewares(class: &Class) -> impl Iterator<Item = String> + '_ {
@@ -198,17 +205,13 @@ fn extract_method_level_middlewares(class: &Class) -> impl Iterator<Item = Strin
 }
 
 fn extract_method_decorator_names(member: &ClassMember) -> Option<Vec<String>> {
-    let ClassMember::Method(method) = member else {
-        return None;
+    let decorators = match member {
+        ClassMember::Method(method) => &method.function.decorators,
+        ClassMember::ClassProp(property) => &property.decorators,
+        _ => return None,
     };
 
-    let names: Vec<String> = method
-        .function
-        .decorators
-        .iter()
-        .filter_map(extract_use_middlewares_names)
-        .flatten()
-        .collect();
+    let names = extract_middleware_names_from_decorators(decorators);
 
     (!names.is_empty()).then_some(names)
 }
@@ -361,7 +364,7 @@ mod tests {
         let info = info.unwrap();
         assert_eq!(info.context_properties.len(), 1);
         assert_eq!(info.context_properties[0].name, "user");
-        assert!(info.context_properties[0].type_string.contains("id:"));
+        assert_eq!(info.context_properties[0].type_string, "unknown");
     }
 
     #[test]
@@ -1,19 +1,26 @@
+pub mod cache;
+pub mod composed;
+pub mod constants;
 pub mod context;
 pub mod decorator;
 pub mod imports;
+pub mod inheritance;
 pub mod middleware;
 pub mod module;
 pub mod procedure;
+pub mod provenance;
+pub mod resolver_context;
 pub mod router;
 pub mod schema;
 
 use crate::error::ParserError;
+use rayon::prelude::*;
 use std::path::{Path, PathBuf};
-use swc_common::comments::SingleThreadedComments;
+use std::sync::Arc;
 use swc_common::input::StringInput;
 use swc_common::sync::Lrc;
 use swc_common::{FileName, SourceFile, SourceMap, SourceMapper, Spanned};
-use swc_ecma_ast::Module;
+use swc_ecma_ast::{Expr, Module};
 use swc_ecma_parser::lexer::Lexer;
 use swc_ecma_parser::{Parser, Syntax, TsSyntax};
 use tracing::{debug, trace, warn};
@@ -23,9 +30,8 @@ pub type ParserResult<T> = std::result::Result<T, ParserError>;
 #[derive(Clone)]
 pub struct ParsedFile {
     pub file_path: PathBuf,
-    pub module: Module,
+    pub module: Arc<Module>,
     pub source_map: Lrc<SourceMap>,
-    pub comments: SingleThreadedComments,
     pub source_file: Lrc<SourceFile>,
 }
 
@@ -35,7 +41,6 @@ impl std::fmt::Debug for ParsedFile {
             .field("file_path", &self.file_path)
             .field("module", &self.module)
             .field("source_map", &"<SourceMap>")
-            .field("comments", &"<SingleThreadedComments>")
             .field("source_file", &"<SourceFile>")
             .finish()
     }
@@ -54,6 +59,13 @@ impl ParsedFile {
         let loc = self.source_map.lookup_char_pos(span.lo);
         (loc.line, loc.col_display + 1)
     }
+
+    /// Byte offset and length of `span` within this file's source.
+    #[must_use]
+    pub fn get_byte_range(&self, span: swc_common::Span) -> (usize, usize) {
+        let offset = span.lo.0.saturating_sub(self.source_file.start_pos.0);
+        (offset as usize, (span.hi.0 - span.lo.0) as usize)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -120,13 +132,11 @@ impl TsParser {
             source.to_string(),
         );
 
-        let comments = SingleThreadedComments::default();
-
         let lexer = Lexer::new(
             Syntax::Typescript(self.syntax),
             swc_ecma_ast::EsVersion::EsNext,
             StringInput::from(&*source_file),
-            Some(&comments),
+            None,
         );
 
         let mut parser = Parser::new_from(lexer);
@@ -182,23 +192,75 @@ impl TsParser {
 
         Ok(ParsedFile {
             file_path: path.to_path_buf(),
-            module,
+            module: Arc::new(module),
             source_map,
-            comments,
             source_file: Lrc::clone(&source_file),
         })
     }
 
+    /// Parses `expression` as if it were written in `scope`, without parsing `scope` again.
+    ///
+    /// The returned file shares the declarations of `scope`, and its source map holds both the
+    /// scope's source and `expression`, so spans from either resolve to their text.
+    pub fn parse_expression_in(
+        &self,
+        scope: &ParsedFile,
+        expression: &str,
+    ) -> ParserResult<(ParsedFile, Box<Expr>)> {
+        trace!(path = ?scope.file_path, expression_len = expression.len(), "Parsing expression");
+
+        // A fresh source map places the scope's source at the same positions as its own map did.
+        let source_map: Lrc<SourceMap> = Lrc::default();
+        let source_file = source_map.new_source_file(
+            FileName::Real(scope.file_path.clone()).into(),
+            scope.source_file.src.clone(),
+        );
+        debug_assert_eq!(source_file.start_pos, scope.source_file.start_pos);
+        let expression_file =
+            source_map.new_source_file(FileName::Anon.into(), expression.to_string());
+
+        let lexer = Lexer::new(
+            Syntax::Typescript(self.syntax),
+            swc_ecma_ast::EsVersion::EsNext,
+            StringInput::from(&*expression_file),
+            None,
+        );
+        let parsed_expression = Parser::new_from(lexer).parse_expr().map_err(|e| {
+            let loc = source_map.lookup_char_pos(e.span().lo);
+            ParserError::SyntaxError {
+                path: scope.file_path.clone(),
+                line: loc.line,
+                column: loc.col_display + 1,
+                message: e.kind().msg().to_string(),
+            }
+        })?;
+
+        let parsed_file = ParsedFile {
+            file_path: scope.file_path.clone(),
+            module: Arc::clone(&scope.module),
+            source_map,
+            source_file,
+        };
+        Ok((parsed_file, parsed_expression))
+    }
+
+    /// Parses files across the rayon thread pool; results keep the input order.
     pub fn parse_files<I, P>(&self, paths: I) -> (Vec<ParsedFile>, Vec<ParserError>)
     where
         I: IntoIterator<Item = P>,
-        P: AsRef<Path>,
+        P: AsRef<Path> + Send,
     {
+        let paths: Vec<P> = paths.into_iter().collect();
+        let results: Vec<ParserResult<ParsedFile>> = paths
+            .into_par_iter()
+            .map(|path| self.parse_file(path))
+            .collect();
+
         let mut parsed = Vec::new();
         let mut errors = Vec::new();
 
-        for path in paths {
-            match self.parse_file(path) {
+        for result in results {
+            match result {
                 Ok(file) => parsed.push(file),
                 Err(e) => errors.push(e),
             }
@@ -218,19 +280,32 @@ pub fn parse_typescript_source(source: &str) -> ParserResult<ParsedFile> {
     parser.parse_source("<input>", source)
 }
 
+pub use cache::ParseCache;
+pub use composed::detect_composed_decorators;
+pub use constants::ConstantEvaluator;
 pub use context::{extract_context, ContextInfo, ContextParser};
-pub use decorator::{is_procedure_decorator, DecoratorParser, ProcedureDecoratorInfo};
+pub use decorator::{
+    is_procedure_decorator, DecoratorParser, ProcedureDecoratorAlias, ProcedureDecoratorInfo,
+};
+pub use inheritance::{BaseClass, BaseClassResolver};
 pub use middleware::{
-    extract_middleware, extract_middleware_names_from_class, ContextProperty, MiddlewareInfo,
-    MiddlewareParser,
+    extract_middleware, extract_middleware_names_from_class,
+    extract_middleware_names_from_decorators, ContextProperty, MiddlewareInfo, MiddlewareParser,
 };
 pub use module::{
     extract_trpc_options, resolve_context_file, resolve_transformer_import, ModuleParser,
     TransformerInfo, TrpcModuleOptions,
 };
-pub use procedure::extract_procedures_from_class;
+pub use procedure::{
+    extract_procedures_from_class, extract_procedures_from_hierarchy, ClassProcedures,
+};
+pub use provenance::DecoratorImports;
+pub use resolver_context::ResolverContext;
 pub use router::{extract_routers, RouterExportKind, RouterInfo, RouterParser};
-pub use schema::{flatten_zod_schema, ZodFlattener, ZodResult};
+pub use schema::{
+    check_zod_entry_points, detect_validator_imports, flatten_zod_schema, merge_validator_bindings,
+    ValidatorImport, ValidatorImportKind, ZodFlattener, ZodResult, ZodVersion,
+};
 
 #[cfg(test)]
 mod tests {
@@ -11,6 +11,7 @@ pub struct TrpcModuleOptions {
     pub context_class_name: Option<String>,
     pub auto_schema_file: Option<String>,
     pub transformer_identifier: Option<String>,
+    pub global_middleware_names: Vec<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -258,6 +259,7 @@ impl ModuleParser {
             "context" => self.extract_context_option(key_value, result),
             "autoSchemaFile" => self.extract_auto_schema_option(key_value, result),
             "transformer" => self.extract_transformer_option(key_value, result),
+            "globalMiddlewares" => self.extract_global_middlewares_option(key_value, result),
             _ => {}
         }
     }
@@ -302,6 +304,28 @@ impl ModuleParser {
         trace!(transformer = %identifier.sym, "Found transformer identifier");
     }
 
+    #[allow(clippy::unused_self)]
+    fn extract_global_middlewares_option(
+        &self,
+        key_value: &swc_ecma_ast::KeyValueProp,
+        result: &mut TrpcModuleOptions,
+    ) {
+        let Expr::Array(array_literal) = &*key_value.value else {
+            return;
+        };
+
+        result.global_middleware_names = array_literal
+            .elems
+            .iter()
+            .flatten()
+            .filter_map(|element| match &*element.expr {
+                Expr::Ident(identifier) => Some(identifier.sym.to_string()),
+                _ => None,
+            })
+            .collect();
+        trace!(middlewares = ?result.global_middleware_names, "Found global middlewares");
+    }
+
     #[allow(clippy::unused_self)]
     fn get_property_key_name(&self, key: &PropName) -> Option<String> {
         match key {
@@ -502,6 +526,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_extract_trpc_options_with_global_middlewares() {
+        let source = r"
+            import { Module } from '@nestjs/common';
+            import { TRPCModule } from 'nestjs-trpc';
+            import { LoggingMiddleware } from './logging.middleware';
+            import { TracingMiddleware } from './tracing.middleware';
+
+            @Module({
+                imports: [
+                    TRPCModule.forRoot({
+                        globalMiddlewares: [LoggingMiddleware, TracingMiddleware],
+                    }),
+                ],
+            })
+            export class AppModule {}
+        ";
+
+        let options = parse_and_extract(source).unwrap();
+        assert_eq!(
+            options.global_middleware_names,
+            vec!["LoggingMiddleware", "TracingMiddleware"]
+        );
+    }
+
     #[test]
     fn test_no_trpc_module() {
         let source = r"
@@ -1,6 +1,19 @@
+use super::inheritance::BaseClass;
+use super::middleware::extract_middleware_names_from_decorators;
 use super::{DecoratorParser, ParsedFile};
 use crate::ProcedureMetadata;
-use swc_ecma_ast::{Class, ClassMember, Decl, ModuleDecl, ModuleItem, Stmt};
+use std::collections::{BTreeSet, HashSet};
+use std::iter;
+use swc_ecma_ast::{
+    Class, ClassMember, Decl, Decorator, Expr, Lit, ModuleDecl, ModuleItem, PropName, Stmt,
+};
+
+/// The procedures one class of a router's hierarchy contributes, with the file declaring them.
+#[derive(Debug)]
+pub struct ClassProcedures<'a> {
+    pub parsed_file: &'a ParsedFile,
+    pub procedures: Vec<ProcedureMetadata>,
+}
 
 #[must_use]
 pub fn extract_procedures_from_class(
@@ -12,10 +25,63 @@ pub fn extract_procedures_from_class(
         return Vec::new();
     };
 
-    extract_procedures_from_class_body(class, decorator_parser, parsed_file)
+    let class_middlewares = extract_middleware_names_from_decorators(&class.decorators);
+    extract_procedures_from_class_body(class, &class_middlewares, decorator_parser, parsed_file)
+}
+
+/// Procedures of `class_name` followed by those inherited from `base_classes` (nearest first).
+///
+/// As at runtime, a method redeclared by a subclass hides the inherited one even without a
+/// decorator, and router middlewares come from the nearest class that declares any.
+pub fn extract_procedures_from_hierarchy<'a>(
+    parsed_file: &'a ParsedFile,
+    class_name: &str,
+    base_classes: &'a [BaseClass],
+    decorator_parser_for: impl Fn(&ParsedFile) -> DecoratorParser,
+) -> Vec<ClassProcedures<'a>> {
+    let classes: Vec<(&ParsedFile, &Class)> = iter::once((parsed_file, class_name))
+        .chain(
+            base_classes
+                .iter()
+                .map(|base_class| (&*base_class.parsed_file, base_class.class_name.as_str())),
+        )
+        .filter_map(|(file, name)| {
+            find_class_by_name(&file.module.body, name).map(|class| (file, class))
+        })
+        .collect();
+    let class_middlewares = classes
+        .iter()
+        .map(|(_, class)| extract_middleware_names_from_decorators(&class.decorators))
+        .find(|middlewares| !middlewares.is_empty())
+        .unwrap_or_default();
+
+    let mut declared_methods = HashSet::new();
+    classes
+        .into_iter()
+        .map(|(file, class)| {
+            let procedures = extract_procedures_from_class_body(
+                class,
+                &class_middlewares,
+                &decorator_parser_for(file),
+                file,
+            )
+            .into_iter()
+            .filter(|procedure| !declared_methods.contains(&procedure.name))
+            .collect();
+            declared_methods.extend(class_members(class).map(|(name, _)| name));
+
+            ClassProcedures {
+                parsed_file: file,
+                procedures,
+            }
+        })
+        .collect()
 }
 
-fn find_class_by_name<'a>(body: &'a [ModuleItem], target_name: &str) -> Option<&'a Class> {
+pub(crate) fn find_class_by_name<'a>(
+    body: &'a [ModuleItem],
+    target_name: &str,
+) -> Option<&'a Class> {
     body.iter()
         .find_map(|item| match_class_in_item(item, target_name))
 }
@@ -76,30 +142,53 @@ fn match_default_exported_class<'a>(
     Some(&class_expression.class)
 }
 
+/// Instance methods and properties, e.g. `getUser = async () => {...}`, with their decorators.
+pub(crate) fn class_members(class: &Class) -> impl Iterator<Item = (String, &[Decorator])> {
+    class.body.iter().filter_map(|member| match member {
+        ClassMember::Method(method) if !method.is_static => Some((
+            member_name(&method.key)?,
+            method.function.decorators.as_slice(),
+        )),
+        ClassMember::ClassProp(property) if !property.is_static => {
+            Some((member_name(&property.key)?, property.decorators.as_slice()))
+        }
+        _ => None,
+    })
+}
+
+/// The name of `getUser`, `'get-user'` or `['get-user']`; other computed keys aren't static.
+fn member_name(key: &PropName) -> Option<String> {
+    match key {
+        PropName::Ident(identifier) => Some(identifier.sym.to_string()),
+        PropName::Str(string) => Some(string.value.to_string_lossy().into_owned()),
+        PropName::Computed(computed) => match &*computed.expr {
+            Expr::Lit(Lit::Str(string)) => Some(string.value.to_string_lossy().into_owned()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
 fn extract_procedures_from_class_body(
     class: &Class,
+    class_middlewares: &[String],
     decorator_parser: &DecoratorParser,
     parsed_file: &ParsedFile,
 ) -> Vec<ProcedureMetadata> {
     let mut procedures = Vec::new();
 
-    for member in &class.body {
-        let ClassMember::Method(method) = member else {
-            continue;
-        };
-
-        let Some(method_name) = method
-            .key
-            .as_ident()
-            .map(|identifier| identifier.sym.to_string())
-        else {
-            continue;
-        };
-
+    for (method_name, decorators) in class_members(class) {
         let decorator_infos =
-            decorator_parser.extract_procedure_decorators(&method.function.decorators, parsed_file);
+            decorator_parser.extract_procedure_decorators(decorators, parsed_file);
+        let method_middlewares = extract_middleware_names_from_decorators(decorators);
 
         for info in decorator_infos {
+            let middlewares = class_middlewares
+                .iter()
+                .chain(&info.middlewares)
+                .chain(&method_middlewares)
+                .cloned()
+                .collect();
             procedures.push(ProcedureMetadata {
                 name: method_name.clone(),
                 procedure_type: info.procedure_type,
@@ -109,6 +198,10 @@ fn extract_procedures_from_class_body(
                 output_schema_ref: info.output_ref,
                 schema_identifiers: info.schema_identifiers,
                 output_inference: None,
+                meta: info.meta,
+                middlewares,
+                hoisted_declarations: Vec::new(),
+                schema_sources: BTreeSet::new(),
             });
         }
     }
@@ -170,6 +263,37 @@ mod tests {
         assert!(procedures[1].input_schema.is_some());
     }
 
+    #[test]
+    fn test_extract_procedures_collects_middleware_stack() {
+        let source = r"
+            import { Router, Query, UseMiddlewares } from 'nestjs-trpc';
+
+            @Router()
+            @UseMiddlewares(AuthMiddleware)
+            export class UserRouter {
+                @Query()
+                async getUser() {}
+
+                @UseMiddlewares(RolesMiddleware, AuditMiddleware)
+                @Query()
+                async getAdmin() {}
+            }
+        ";
+        let (_temp, path) = create_temp_file(source);
+
+        let parser = TsParser::new();
+        let parsed = parser.parse_file(&path).expect("Failed to parse");
+        let decorator_parser = DecoratorParser::new();
+
+        let procedures = extract_procedures_from_class(&parsed, "UserRouter", &decorator_parser);
+
+        assert_eq!(procedures[0].middlewares, vec!["AuthMiddleware"]);
+        assert_eq!(
+            procedures[1].middlewares,
+            vec!["AuthMiddleware", "RolesMiddleware", "AuditMiddleware"]
+        );
+    }
+
     #[test]
     fn test_extract_procedures_from_non_exported_class() {
         let source = r"
@@ -416,4 +540,86 @@ mod tests {
         assert_eq!(procedures.len(), 1);
         assert_eq!(procedures[0].name, "normalMethod");
     }
+
+    #[test]
+    fn test_extract_procedures_from_properties_and_literal_keys() {
+        let source = r"
+            import { Router, Query, Mutation } from 'nestjs-trpc';
+            import { z } from 'zod';
+
+            @Router()
+            export class TestRouter {
+                @Query({ input: z.string() })
+                getUser = async (input: string) => ({ id: input });
+
+                @Mutation()
+                'delete-user'() {}
+
+                @Query()
+                ['list-users']() {}
+
+                @Query()
+                [dynamicName]() {}
+
+                @Query()
+                static helper = () => null;
+            }
+        ";
+        let (_temp, path) = create_temp_file(source);
+
+        let parser = TsParser::new();
+        let parsed = parser.parse_file(&path).expect("Failed to parse");
+        let procedures =
+            extract_procedures_from_class(&parsed, "TestRouter", &DecoratorParser::new());
+
+        let names: Vec<&str> = procedures
+            .iter()
+            .map(|procedure| procedure.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["getUser", "delete-user", "list-users"]);
+        assert_eq!(procedures[0].input_schema, Some("z.string()".to_string()));
+    }
+
+    #[test]
+    fn test_extract_procedures_from_hierarchy_lets_subclasses_win() {
+        let source = r"
+            @UseMiddlewares(AuthMiddleware)
+            abstract class BaseRouter {
+                @Query()
+                list() {}
+
+                @Mutation()
+                delete() {}
+            }
+
+            @Router()
+            export class UserRouter extends BaseRouter {
+                @Query()
+                me() {}
+
+                delete() {}
+            }
+        ";
+
+        let (_temp_dir, file_path) = create_temp_file(source);
+        let parser = TsParser::new();
+        let parsed = parser.parse_file(&file_path).unwrap();
+        let base_classes = [BaseClass {
+            class_name: "BaseRouter".to_string(),
+            parsed_file: std::sync::Arc::new(parsed.clone()),
+        }];
+
+        let classes =
+            extract_procedures_from_hierarchy(&parsed, "UserRouter", &base_classes, |_| {
+                DecoratorParser::new()
+            });
+        let procedures: Vec<(&str, &[String])> = classes
+            .iter()
+            .flat_map(|class| &class.procedures)
+            .map(|procedure| (procedure.name.as_str(), procedure.middlewares.as_slice()))
+            .collect();
+
+        let auth = ["AuthMiddleware".to_string()];
+        assert_eq!(procedures, vec![("me", &auth[..]), ("list", &auth[..])]);
+    }
 }
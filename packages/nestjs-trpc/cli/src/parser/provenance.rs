@@ -0,0 +1,232 @@
+use std::collections::{HashMap, HashSet};
+use swc_ecma_ast::{
+    Callee, Decorator, Expr, ImportDecl, ImportSpecifier, MemberExpr, MemberProp, Module,
+    ModuleDecl, ModuleExportName, ModuleItem,
+};
+
+const NESTJS_TRPC_PACKAGE: &str = "nestjs-trpc";
+
+/// How a file imports its decorators, so `@Query` from `@nestjs/graphql` isn't mistaken for the
+/// `nestjs-trpc` one.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DecoratorImports {
+    /// Local name to exported name, for named imports from `nestjs-trpc`.
+    named: HashMap<String, String>,
+    /// Local names of `import * as trpc from 'nestjs-trpc'`.
+    namespaces: HashSet<String>,
+    /// Local names imported from any other module.
+    foreign: HashSet<String>,
+}
+
+impl DecoratorImports {
+    #[must_use]
+    pub fn from_module(module: &Module) -> Self {
+        let mut imports = Self::default();
+        module
+            .body
+            .iter()
+            .filter_map(|item| match item {
+                ModuleItem::ModuleDecl(ModuleDecl::Import(import_declaration)) => {
+                    Some(import_declaration)
+                }
+                _ => None,
+            })
+            .for_each(|import_declaration| imports.add_import(import_declaration));
+        imports
+    }
+
+    fn add_import(&mut self, import_declaration: &ImportDecl) {
+        let source = import_declaration.src.value.to_string_lossy();
+        let is_trpc =
+            source == NESTJS_TRPC_PACKAGE || source.starts_with(&format!("{NESTJS_TRPC_PACKAGE}/"));
+
+        for specifier in &import_declaration.specifiers {
+            self.add_specifier(specifier, is_trpc);
+        }
+    }
+
+    fn add_specifier(&mut self, specifier: &ImportSpecifier, is_trpc: bool) {
+        match specifier {
+            ImportSpecifier::Named(named) if is_trpc => {
+                let imported = named
+                    .imported
+                    .as_ref()
+                    .map_or_else(|| named.local.sym.to_string(), export_name);
+                self.named.insert(named.local.sym.to_string(), imported);
+            }
+            ImportSpecifier::Namespace(namespace) if is_trpc => {
+                self.namespaces.insert(namespace.local.sym.to_string());
+            }
+            _ => {
+                self.foreign.insert(specifier.local().sym.to_string());
+            }
+        }
+    }
+
+    /// The `nestjs-trpc` export a decorator refers to, e.g. `Query` for `@Query()`,
+    /// `@TrpcQuery()` (renamed import) or `@trpc.Query()` (namespace import).
+    ///
+    /// Names that aren't imported at all resolve to themselves, so ambient or locally declared
+    /// decorators keep working; names imported from another package resolve to `None`.
+    #[must_use]
+    pub fn resolve<'a>(&'a self, decorator: &'a Decorator) -> Option<&'a str> {
+        self.resolve_expression(&decorator.expr)
+    }
+
+    /// Like [`Self::resolve`], for a decorator expression without the `@`, e.g. an argument of
+    /// `applyDecorators(...)`.
+    #[must_use]
+    pub fn resolve_expression<'a>(&'a self, expression: &'a Expr) -> Option<&'a str> {
+        match expression {
+            Expr::Call(call_expression) => match &call_expression.callee {
+                Callee::Expr(callee) => self.resolve_reference(callee),
+                _ => None,
+            },
+            reference => self.resolve_reference(reference),
+        }
+    }
+
+    fn resolve_reference<'a>(&'a self, expression: &'a Expr) -> Option<&'a str> {
+        match expression {
+            Expr::Ident(identifier) => self.resolve_identifier(identifier.sym.as_ref()),
+            Expr::Member(member_expression) => self.resolve_namespace_member(member_expression),
+            _ => None,
+        }
+    }
+
+    fn resolve_identifier<'a>(&'a self, local_name: &'a str) -> Option<&'a str> {
+        if let Some(imported) = self.named.get(local_name) {
+            return Some(imported);
+        }
+
+        let is_bound = self.foreign.contains(local_name) || self.namespaces.contains(local_name);
+        (!is_bound).then_some(local_name)
+    }
+
+    fn resolve_namespace_member<'a>(&self, member_expression: &'a MemberExpr) -> Option<&'a str> {
+        let Expr::Ident(object) = &*member_expression.obj else {
+            return None;
+        };
+        if !self.namespaces.contains(object.sym.as_ref()) {
+            return None;
+        }
+
+        match &member_expression.prop {
+            MemberProp::Ident(property) => Some(property.sym.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// The identifier a decorator is written with, e.g. `AdminMutation` for `@AdminMutation({ ... })`.
+#[must_use]
+pub fn decorator_name(decorator: &Decorator) -> Option<&str> {
+    let expression = match &*decorator.expr {
+        Expr::Call(call_expression) => call_expression.callee.as_expr()?,
+        expression => expression,
+    };
+    expression
+        .as_ident()
+        .map(|identifier| identifier.sym.as_ref())
+}
+
+fn export_name(name: &ModuleExportName) -> String {
+    match name {
+        ModuleExportName::Ident(identifier) => identifier.sym.to_string(),
+        ModuleExportName::Str(string) => string.value.to_string_lossy().into_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_typescript_source;
+    use swc_ecma_ast::{ClassMember, Decl, Stmt};
+
+    fn resolve_method_decorators(source: &str) -> Vec<Option<String>> {
+        let parsed = parse_typescript_source(source).unwrap();
+        let imports = DecoratorImports::from_module(&parsed.module);
+
+        parsed
+            .module
+            .body
+            .iter()
+            .filter_map(|item| match item {
+                ModuleItem::Stmt(Stmt::Decl(Decl::Class(class_declaration))) => {
+                    Some(&class_declaration.class)
+                }
+                _ => None,
+            })
+            .flat_map(|class| &class.body)
+            .filter_map(|member| match member {
+                ClassMember::Method(method) => Some(&method.function.decorators),
+                _ => None,
+            })
+            .flatten()
+            .map(|decorator| imports.resolve(decorator).map(String::from))
+            .collect()
+    }
+
+    #[test]
+    fn test_rejects_decorators_from_other_packages() {
+        let resolved = resolve_method_decorators(
+            r"
+            import { Query } from '@nestjs/graphql';
+            class UserResolver {
+                @Query()
+                users() {}
+            }
+            ",
+        );
+
+        assert_eq!(resolved, vec![None]);
+    }
+
+    #[test]
+    fn test_resolves_renamed_and_namespace_imports() {
+        let resolved = resolve_method_decorators(
+            r"
+            import { Query } from '@nestjs/graphql';
+            import { Query as TrpcQuery } from 'nestjs-trpc';
+            import * as trpc from 'nestjs-trpc';
+            class UserResolver {
+                @TrpcQuery()
+                renamed() {}
+
+                @trpc.Mutation
+                namespaced() {}
+
+                @Query()
+                graphql() {}
+            }
+            ",
+        );
+
+        assert_eq!(
+            resolved,
+            vec![
+                Some("Query".to_string()),
+                Some("Mutation".to_string()),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unimported_names_resolve_to_themselves() {
+        let resolved = resolve_method_decorators(
+            r"
+            import * as graphql from '@nestjs/graphql';
+            class UserRouter {
+                @Query()
+                local() {}
+
+                @graphql.Query()
+                foreign() {}
+            }
+            ",
+        );
+
+        assert_eq!(resolved, vec![Some("Query".to_string()), None]);
+    }
+}
@@ -0,0 +1,89 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
+
+use tracing::warn;
+
+use super::imports::{ImportResolver, ResolvedImport};
+use super::{ParseCache, ParsedFile, TsParser};
+
+/// Parses the files that bindings are followed into and resolves their imports, shared by the
+/// resolvers that follow bindings across files.
+///
+/// Each file's imports map is built at most once per context, so following several bindings out
+/// of one file resolves its imports only once.
+#[derive(Debug)]
+pub struct ResolverContext<'a> {
+    typescript_parser: &'a TsParser,
+    parse_cache: &'a ParseCache,
+    base_directory: &'a Path,
+    imports_maps: RefCell<HashMap<PathBuf, Rc<HashMap<String, ResolvedImport>>>>,
+}
+
+impl<'a> ResolverContext<'a> {
+    #[must_use]
+    pub fn new(
+        typescript_parser: &'a TsParser,
+        parse_cache: &'a ParseCache,
+        base_directory: &'a Path,
+    ) -> Self {
+        Self {
+            typescript_parser,
+            parse_cache,
+            base_directory,
+            imports_maps: RefCell::default(),
+        }
+    }
+
+    /// Reuses an imports map the caller already built for `parsed_file`.
+    #[must_use]
+    pub fn with_imports_map(
+        self,
+        parsed_file: &ParsedFile,
+        imports_map: HashMap<String, ResolvedImport>,
+    ) -> Self {
+        self.imports_maps
+            .borrow_mut()
+            .insert(parsed_file.file_path.clone(), Rc::new(imports_map));
+        self
+    }
+
+    #[must_use]
+    pub fn parse(&self, path: &Path) -> Option<Arc<ParsedFile>> {
+        self.parse_cache
+            .get_or_parse(self.typescript_parser, path)
+            .ok()
+    }
+
+    /// What the local binding `name` of `parsed_file` is imported as, if it is imported.
+    #[must_use]
+    pub fn resolve_import(&self, parsed_file: &ParsedFile, name: &str) -> Option<ResolvedImport> {
+        self.imports_map(parsed_file).get(name).cloned()
+    }
+
+    /// The imports map of `parsed_file`, empty when its imports can't be resolved.
+    #[must_use]
+    pub fn imports_map(&self, parsed_file: &ParsedFile) -> Rc<HashMap<String, ResolvedImport>> {
+        if let Some(imports_map) = self.imports_maps.borrow().get(&parsed_file.file_path) {
+            return Rc::clone(imports_map);
+        }
+
+        let imports_map = ImportResolver::new(self.typescript_parser)
+            .with_parse_cache(self.parse_cache.clone())
+            .build_imports_map(parsed_file, self.base_directory)
+            .unwrap_or_else(|error| {
+                warn!(
+                    "Failed to build imports map for {:?}: {}",
+                    parsed_file.file_path, error
+                );
+                HashMap::new()
+            });
+        let imports_map = Rc::new(imports_map);
+        self.imports_maps
+            .borrow_mut()
+            .insert(parsed_file.file_path.clone(), Rc::clone(&imports_map));
+        imports_map
+    }
+}
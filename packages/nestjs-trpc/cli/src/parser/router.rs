@@ -1,8 +1,10 @@
+use crate::parser::imports::DEFAULT_EXPORT;
+use crate::parser::provenance::DecoratorImports;
 use crate::parser::ParsedFile;
 use std::path::{Path, PathBuf};
 use swc_ecma_ast::{
-    CallExpr, Callee, Class, Decl, Decorator, Expr, ExprOrSpread, Lit, ModuleDecl, ModuleItem,
-    ObjectLit, Prop, PropName, PropOrSpread, Stmt,
+    Class, Decl, Decorator, ExportSpecifier, Expr, ExprOrSpread, Lit, Module, ModuleDecl,
+    ModuleExportName, ModuleItem, ObjectLit, Prop, PropName, PropOrSpread, Stmt,
 };
 use tracing::{debug, trace};
 
@@ -19,6 +21,10 @@ pub struct RouterInfo {
 
     pub alias: Option<String>,
 
+    /// An alias given as something other than a string literal, e.g. `ROUTES.users` or a
+    /// template with substitutions, left for [`crate::ConstantEvaluator`] to resolve.
+    pub alias_expression: Option<Box<Expr>>,
+
     pub file_path: PathBuf,
 
     pub export_kind: RouterExportKind,
@@ -35,13 +41,16 @@ impl RouterParser {
 
     pub fn extract_routers(&self, parsed_file: &ParsedFile) -> Vec<RouterInfo> {
         debug!(path = ?parsed_file.file_path, "Extracting routers from file");
+        let imports = DecoratorImports::from_module(&parsed_file.module);
 
         let routers: Vec<RouterInfo> = parsed_file
             .module
             .body
             .iter()
             .filter_map(|item| {
-                let router_info = Self::extract_router_from_item(item, &parsed_file.file_path)?;
+                let mut router_info =
+                    Self::extract_router_from_item(item, &imports, &parsed_file.file_path)?;
+                router_info.export_kind = export_kind(&parsed_file.module, &router_info);
                 debug!(
                     class = %router_info.class_name,
                     alias = ?router_info.alias,
@@ -60,27 +69,36 @@ impl RouterParser {
         routers
     }
 
-    fn extract_router_from_item(item: &ModuleItem, file_path: &Path) -> Option<RouterInfo> {
+    fn extract_router_from_item(
+        item: &ModuleItem,
+        imports: &DecoratorImports,
+        file_path: &Path,
+    ) -> Option<RouterInfo> {
         match item {
             ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_declaration)) => {
-                Self::extract_from_export_declaration(&export_declaration.decl, file_path)
+                Self::extract_from_export_declaration(&export_declaration.decl, imports, file_path)
             }
             ModuleItem::Stmt(Stmt::Decl(Decl::Class(class_declaration))) => {
                 Self::extract_router_from_class(
                     &class_declaration.ident.sym,
                     &class_declaration.class,
+                    imports,
                     file_path,
                     RouterExportKind::None,
                 )
             }
             ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(export_default)) => {
-                Self::extract_from_default_export(&export_default.decl, file_path)
+                Self::extract_from_default_export(&export_default.decl, imports, file_path)
             }
             _ => None,
         }
     }
 
-    fn extract_from_export_declaration(declaration: &Decl, file_path: &Path) -> Option<RouterInfo> {
+    fn extract_from_export_declaration(
+        declaration: &Decl,
+        imports: &DecoratorImports,
+        file_path: &Path,
+    ) -> Option<RouterInfo> {
         let Decl::Class(class_declaration) = declaration else {
             return None;
         };
@@ -88,6 +106,7 @@ impl RouterParser {
         Self::extract_router_from_class(
             &class_declaration.ident.sym,
             &class_declaration.class,
+            imports,
             file_path,
             RouterExportKind::Named,
         )
@@ -95,6 +114,7 @@ impl RouterParser {
 
     fn extract_from_default_export(
         declaration: &swc_ecma_ast::DefaultDecl,
+        imports: &DecoratorImports,
         file_path: &Path,
     ) -> Option<RouterInfo> {
         let swc_ecma_ast::DefaultDecl::Class(class_expression) = declaration else {
@@ -102,13 +122,14 @@ impl RouterParser {
         };
 
         let class_name = class_expression.ident.as_ref().map_or_else(
-            || "default".to_string(),
+            || DEFAULT_EXPORT.to_string(),
             |identifier| identifier.sym.to_string(),
         );
 
         Self::extract_router_from_class(
             &class_name,
             &class_expression.class,
+            imports,
             file_path,
             RouterExportKind::Default,
         )
@@ -117,6 +138,7 @@ impl RouterParser {
     fn extract_router_from_class(
         class_name: &str,
         class: &Class,
+        imports: &DecoratorImports,
         file_path: &Path,
         export_kind: RouterExportKind,
     ) -> Option<RouterInfo> {
@@ -125,58 +147,44 @@ impl RouterParser {
         class
             .decorators
             .iter()
-            .find_map(Self::extract_router_decorator_info)
-            .map(|alias| RouterInfo {
-                class_name: class_name.to_string(),
-                alias,
-                file_path: file_path.to_path_buf(),
-                export_kind,
+            .find_map(|decorator| Self::extract_router_decorator_info(decorator, imports))
+            .map(|alias_value| {
+                let alias = alias_value.as_ref().and_then(extract_string_value);
+                let alias_expression = alias_value.filter(|_| alias.is_none()).map(Box::new);
+                RouterInfo {
+                    class_name: class_name.to_string(),
+                    alias,
+                    alias_expression,
+                    file_path: file_path.to_path_buf(),
+                    export_kind,
+                }
             })
     }
 
     #[allow(clippy::option_option)] // Intentional: None=not Router, Some(None)=no alias, Some(Some)=alias
-    fn extract_router_decorator_info(decorator: &Decorator) -> Option<Option<String>> {
-        match &*decorator.expr {
-            Expr::Call(call_expr) => Self::extract_from_call_expr(call_expr),
-            Expr::Ident(ident) if ident.sym.as_ref() == "Router" => {
-                trace!("Found @Router decorator without arguments");
-                Some(None)
-            }
-            _ => None,
-        }
-    }
-
-    #[allow(clippy::option_option)]
-    fn extract_from_call_expr(call_expression: &CallExpr) -> Option<Option<String>> {
-        let is_router_call = Self::is_router_callee(&call_expression.callee);
-
-        if !is_router_call {
+    fn extract_router_decorator_info(
+        decorator: &Decorator,
+        imports: &DecoratorImports,
+    ) -> Option<Option<Expr>> {
+        if imports.resolve(decorator)? != "Router" {
             return None;
         }
 
-        trace!("Found @Router() call expression");
-
-        if call_expression.args.is_empty() {
+        let Expr::Call(call_expression) = &*decorator.expr else {
+            trace!("Found @Router decorator without arguments");
             return Some(None);
-        }
-
-        let first_argument = &call_expression.args[0];
-        Some(Self::extract_alias_from_argument(first_argument))
-    }
-
-    fn is_router_callee(callee: &Callee) -> bool {
-        let Callee::Expr(expression) = callee else {
-            return false;
         };
 
-        let Expr::Ident(identifier) = &**expression else {
-            return false;
-        };
-
-        identifier.sym.as_ref() == "Router"
+        trace!("Found @Router() call expression");
+        Some(
+            call_expression
+                .args
+                .first()
+                .and_then(Self::extract_alias_from_argument),
+        )
     }
 
-    fn extract_alias_from_argument(argument: &ExprOrSpread) -> Option<String> {
+    fn extract_alias_from_argument(argument: &ExprOrSpread) -> Option<Expr> {
         let Expr::Object(object_literal) = &*argument.expr else {
             return None;
         };
@@ -184,7 +192,7 @@ impl RouterParser {
         Self::extract_alias_from_object(object_literal)
     }
 
-    fn extract_alias_from_object(object: &ObjectLit) -> Option<String> {
+    fn extract_alias_from_object(object: &ObjectLit) -> Option<Expr> {
         object
             .props
             .iter()
@@ -193,6 +201,61 @@ impl RouterParser {
     }
 }
 
+fn export_kind(module: &Module, router_info: &RouterInfo) -> RouterExportKind {
+    match router_info.export_kind {
+        RouterExportKind::None => exported_separately(module, &router_info.class_name),
+        export_kind => export_kind,
+    }
+}
+
+/// How a class declared without `export` is exported further down, e.g. `export { UserRouter }`
+/// or `export default UserRouter`. Exports under another name don't count, since `server.ts`
+/// imports router types by class name.
+fn exported_separately(module: &Module, class_name: &str) -> RouterExportKind {
+    module
+        .body
+        .iter()
+        .find_map(|item| match item {
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(export_default)) => export_default
+                .expr
+                .as_ident()
+                .filter(|identifier| identifier.sym.as_ref() == class_name)
+                .map(|_| RouterExportKind::Default),
+            ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(export_named))
+                if export_named.src.is_none() =>
+            {
+                export_named
+                    .specifiers
+                    .iter()
+                    .find_map(|specifier| specifier_export_kind(specifier, class_name))
+            }
+            _ => None,
+        })
+        .unwrap_or(RouterExportKind::None)
+}
+
+/// `Named` for `{ UserRouter }`, `Default` for `{ UserRouter as default }`.
+fn specifier_export_kind(
+    specifier: &ExportSpecifier,
+    class_name: &str,
+) -> Option<RouterExportKind> {
+    let ExportSpecifier::Named(named) = specifier else {
+        return None;
+    };
+    let is_class = |name: &ModuleExportName| matches!(name, ModuleExportName::Ident(identifier) if identifier.sym.as_ref() == class_name);
+    if !is_class(&named.orig) {
+        return None;
+    }
+
+    match named.exported.as_ref() {
+        None => Some(RouterExportKind::Named),
+        Some(ModuleExportName::Ident(exported)) if exported.sym.as_ref() == DEFAULT_EXPORT => {
+            Some(RouterExportKind::Default)
+        }
+        Some(_) => None,
+    }
+}
+
 fn extract_property_box(property_or_spread: &PropOrSpread) -> Option<&Prop> {
     let PropOrSpread::Prop(property) = property_or_spread else {
         return None;
@@ -200,25 +263,17 @@ fn extract_property_box(property_or_spread: &PropOrSpread) -> Option<&Prop> {
     Some(property)
 }
 
-fn extract_alias_from_property(property: &Prop) -> Option<String> {
+fn extract_alias_from_property(property: &Prop) -> Option<Expr> {
     match property {
-        Prop::KeyValue(key_value) => extract_alias_from_key_value(key_value),
+        Prop::KeyValue(key_value) if is_alias_key(&key_value.key) => Some(*key_value.value.clone()),
         Prop::Shorthand(identifier) if identifier.sym.as_ref() == "alias" => {
             trace!("Found shorthand alias property (variable reference)");
-            None
+            Some(Expr::Ident(identifier.clone()))
         }
         _ => None,
     }
 }
 
-fn extract_alias_from_key_value(key_value: &swc_ecma_ast::KeyValueProp) -> Option<String> {
-    let is_alias_key = is_alias_key(&key_value.key);
-    if !is_alias_key {
-        return None;
-    }
-    extract_string_value(&key_value.value)
-}
-
 fn is_alias_key(key: &PropName) -> bool {
     match key {
         PropName::Ident(ident) => &*ident.sym == "alias",
@@ -240,7 +295,7 @@ fn extract_string_value(expr: &Expr) -> Option<String> {
             Some(value)
         }
         _ => {
-            trace!("Alias is not a string literal");
+            trace!("Alias is not a string literal, deferring to constant evaluation");
             None
         }
     }
@@ -366,6 +421,38 @@ mod tests {
         assert_eq!(routers[0].export_kind, RouterExportKind::None);
     }
 
+    #[test]
+    fn test_routers_exported_after_their_declaration() {
+        let source = r"
+            import { Router } from 'nestjs-trpc';
+
+            @Router()
+            class UserRouter {}
+
+            @Router()
+            class AdminRouter {}
+
+            @Router()
+            class AuditRouter {}
+
+            export { UserRouter, AuditRouter as Audits };
+            export default AdminRouter;
+        ";
+
+        let routers = parse_and_extract(source);
+
+        let export_kinds: Vec<RouterExportKind> =
+            routers.iter().map(|router| router.export_kind).collect();
+        assert_eq!(
+            export_kinds,
+            [
+                RouterExportKind::Named,
+                RouterExportKind::Default,
+                RouterExportKind::None
+            ]
+        );
+    }
+
     #[test]
     fn test_extract_multiple_routers() {
         let source = r"
@@ -517,17 +604,49 @@ mod tests {
         assert_eq!(routers[0].class_name, "TestRouter");
     }
 
+    #[test]
+    fn test_router_with_non_literal_alias() {
+        let source = r"
+            function Router(opts?: { alias?: string }): ClassDecorator {
+                return (target) => target;
+            }
+
+            const alias = 'users';
+
+            @Router({ alias })
+            export class UserRouter {}
+
+            @Router({ alias: `${ROUTES.admin}.posts` })
+            export class PostRouter {}
+        ";
+
+        let routers = parse_and_extract(source);
+
+        assert_eq!(routers.len(), 2);
+        assert!(routers.iter().all(|router| router.alias.is_none()));
+        assert!(matches!(
+            routers[0].alias_expression.as_deref(),
+            Some(Expr::Ident(identifier)) if identifier.sym.as_ref() == "alias"
+        ));
+        assert!(matches!(
+            routers[1].alias_expression.as_deref(),
+            Some(Expr::Tpl(_))
+        ));
+    }
+
     #[test]
     fn test_router_info_equality() {
         let info1 = RouterInfo {
             class_name: "Test".to_string(),
             alias: Some("test".to_string()),
+            alias_expression: None,
             file_path: PathBuf::from("test.ts"),
             export_kind: RouterExportKind::Named,
         };
         let info2 = RouterInfo {
             class_name: "Test".to_string(),
             alias: Some("test".to_string()),
+            alias_expression: None,
             file_path: PathBuf::from("test.ts"),
             export_kind: RouterExportKind::Named,
         };
@@ -539,6 +658,7 @@ mod tests {
         let info = RouterInfo {
             class_name: "Test".to_string(),
             alias: Some("test".to_string()),
+            alias_expression: None,
             file_path: PathBuf::from("test.ts"),
             export_kind: RouterExportKind::Named,
         };
@@ -551,6 +671,7 @@ mod tests {
         let info = RouterInfo {
             class_name: "Test".to_string(),
             alias: Some("test".to_string()),
+            alias_expression: None,
             file_path: PathBuf::from("test.ts"),
             export_kind: RouterExportKind::Named,
         };
@@ -631,4 +752,24 @@ mod tests {
         assert_eq!(routers[0].alias, Some("default".to_string()));
         assert_eq!(routers[0].export_kind, RouterExportKind::Default);
     }
+
+    #[test]
+    fn test_router_from_namespace_import() {
+        let source = r"
+            import * as trpc from 'nestjs-trpc';
+            import { Router } from 'some-other-library';
+
+            @trpc.Router({ alias: 'users' })
+            export class UserRouter {}
+
+            @Router()
+            export class OtherRouter {}
+        ";
+
+        let routers = parse_and_extract(source);
+
+        assert_eq!(routers.len(), 1);
+        assert_eq!(routers[0].class_name, "UserRouter");
+        assert_eq!(routers[0].alias, Some("users".to_string()));
+    }
 }
@@ -1,7 +1,9 @@
+use super::member::Root;
 use super::ZodFlattener;
 use super::ZodResult;
 use crate::parser::imports::ResolvedImport;
 use crate::parser::schema::helpers::ZodHelpers;
+use crate::parser::schema::validator::expression_root;
 use crate::parser::ParsedFile;
 use std::collections::HashMap;
 use swc_ecma_ast::{Callee, Expr, Prop, PropOrSpread};
@@ -111,6 +113,13 @@ impl ZodFlattener<'_> {
         mut schema: String,
         depth: usize,
     ) -> ZodResult<String> {
+        if let Some(inlined) =
+            self.inline_local_factory(call_expression, source_file, imports_map, depth)?
+        {
+            let call_text = self.get_source_text(source_file, call_expression.span);
+            return Ok(schema.replace(&call_text, &inlined));
+        }
+
         schema = self.flatten_callee_member_if_needed(
             &call_expression.callee,
             source_file,
@@ -130,6 +139,33 @@ impl ZodFlattener<'_> {
         Ok(schema)
     }
 
+    /// Inlines `factory(...)` when `factory` is neither a validator nor importable by `server.ts`.
+    fn inline_local_factory(
+        &mut self,
+        call_expression: &swc_ecma_ast::CallExpr,
+        source_file: &ParsedFile,
+        imports_map: &HashMap<String, ResolvedImport>,
+        depth: usize,
+    ) -> ZodResult<Option<String>> {
+        let Some(callee) = call_expression
+            .callee
+            .as_expr()
+            .and_then(|callee| callee.as_ident())
+        else {
+            return Ok(None);
+        };
+        let name = callee.sym.as_ref();
+
+        let is_opaque = self.importable_identifiers.contains(name)
+            || self.resolving.iter().any(|resolving| resolving == name)
+            || self.is_validator_binding(source_file, name);
+        if is_opaque {
+            return Ok(None);
+        }
+
+        self.inline_factory_call(name, call_expression, source_file, imports_map, depth)
+    }
+
     fn flatten_callee_member_if_needed(
         &mut self,
         callee: &Callee,
@@ -146,13 +182,18 @@ impl ZodFlattener<'_> {
             return Ok(schema);
         };
 
-        let member_text = self.get_expression_text_for_replacement(&member.obj, source_file);
-        let is_zod_prefix = member_text.starts_with("z.") || member_text.starts_with("z[");
-
-        if is_zod_prefix {
+        let is_validator_chain = expression_root(&member.obj)
+            .is_some_and(|root| self.is_validator_binding(source_file, root));
+        // Earlier links like `.max(LIMITS.max)` in `z.string().max(LIMITS.max).min(1)`.
+        if let (true, Expr::Call(chained)) = (is_validator_chain, &*member.obj) {
+            return self.flatten_call(chained, source_file, imports_map, schema, depth + 1);
+        }
+        if is_validator_chain {
             return Ok(schema);
         }
 
+        let member_text = self.get_expression_text_for_replacement(&member.obj, source_file);
+
         let base_flattened = self.flatten_expression(
             &member.obj,
             source_file,
@@ -213,6 +254,48 @@ impl ZodFlattener<'_> {
         Ok(schema)
     }
 
+    /// Flattens the body of `() => categorySchema` in `z.lazy(...)`. Arrows with parameters are
+    /// left alone, since their parameters could shadow the names they use.
+    pub(super) fn flatten_arrow(
+        &mut self,
+        arrow: &swc_ecma_ast::ArrowExpr,
+        source_file: &ParsedFile,
+        imports_map: &HashMap<String, ResolvedImport>,
+        schema: String,
+        depth: usize,
+    ) -> ZodResult<String> {
+        let swc_ecma_ast::BlockStmtOrExpr::Expr(body) = &*arrow.body else {
+            return Ok(schema);
+        };
+        if !arrow.params.is_empty() {
+            return Ok(schema);
+        }
+        self.flatten_expression(body, source_file, imports_map, schema, depth + 1)
+    }
+
+    /// Flattens both operands, e.g. the `Scope.Own` in an enum member's `Scope.Own << 1`.
+    pub(super) fn flatten_binary(
+        &mut self,
+        binary_expression: &swc_ecma_ast::BinExpr,
+        source_file: &ParsedFile,
+        imports_map: &HashMap<String, ResolvedImport>,
+        mut schema: String,
+        depth: usize,
+    ) -> ZodResult<String> {
+        for operand in [&binary_expression.left, &binary_expression.right] {
+            let operand_text = self.get_expression_text_for_replacement(operand, source_file);
+            let flattened = self.flatten_expression(
+                operand,
+                source_file,
+                imports_map,
+                operand_text.clone(),
+                depth + 1,
+            )?;
+            schema = schema.replace(&operand_text, &flattened);
+        }
+        Ok(schema)
+    }
+
     pub(super) fn flatten_member(
         &mut self,
         member_expression: &swc_ecma_ast::MemberExpr,
@@ -221,6 +304,15 @@ impl ZodFlattener<'_> {
         mut schema: String,
         depth: usize,
     ) -> ZodResult<String> {
+        let root = Root {
+            source_file,
+            imports_map,
+        };
+        if let Some(flattened) = self.flatten_member_reference(member_expression, root, depth)? {
+            let member_text = self.get_source_text(source_file, member_expression.span);
+            return Ok(schema.replace(&member_text, &flattened));
+        }
+
         let object_text =
             self.get_expression_text_for_replacement(&member_expression.obj, source_file);
 
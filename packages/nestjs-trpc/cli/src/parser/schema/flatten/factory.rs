@@ -0,0 +1,428 @@
+use super::ZodFlattener;
+use super::ZodResult;
+use crate::parser::imports::ResolvedImport;
+use crate::parser::schema::helpers::ZodHelpers;
+use crate::parser::ParsedFile;
+use std::collections::HashMap;
+use swc_common::Span;
+use swc_ecma_ast::{
+    BlockStmt, BlockStmtOrExpr, CallExpr, Decl, Expr, ExprOrSpread, ModuleDecl, ModuleItem, Pat,
+    Prop, PropName, PropOrSpread, Stmt,
+};
+use tracing::trace;
+
+/// A function whose body is a single returned expression, such as
+/// `const paginated = (item) => z.object({ items: z.array(item) })`.
+struct SchemaFactory<'a> {
+    parameters: Vec<(String, Option<&'a Expr>)>,
+    body: &'a Expr,
+}
+
+/// Where a parameter is referenced in a factory body; shorthand properties expand to `name: value`.
+struct ParameterReference {
+    span: Span,
+    parameter: usize,
+    is_shorthand: bool,
+}
+
+impl ZodFlattener<'_> {
+    /// Inlines a call to a schema factory that `server.ts` can't import, such as
+    /// `paginated(userSchema)`, by substituting its arguments into the expression it returns.
+    pub(super) fn inline_factory_call(
+        &mut self,
+        name: &str,
+        call_expression: &CallExpr,
+        source_file: &ParsedFile,
+        imports_map: &HashMap<String, ResolvedImport>,
+        depth: usize,
+    ) -> ZodResult<Option<String>> {
+        if find_factory(source_file, name).is_some() {
+            return self.expand_factory_call(
+                name,
+                call_expression,
+                source_file,
+                imports_map,
+                source_file,
+                depth,
+            );
+        }
+
+        let Some(import) = imports_map.get(name) else {
+            return Ok(None);
+        };
+        let factory_file = self.get_or_parse_file(&import.source_file)?;
+        self.expand_factory_call(
+            &import.name,
+            call_expression,
+            source_file,
+            imports_map,
+            &factory_file,
+            depth,
+        )
+    }
+
+    fn expand_factory_call(
+        &mut self,
+        name: &str,
+        call_expression: &CallExpr,
+        source_file: &ParsedFile,
+        imports_map: &HashMap<String, ResolvedImport>,
+        factory_file: &ParsedFile,
+        depth: usize,
+    ) -> ZodResult<Option<String>> {
+        let Some(factory) = find_factory(factory_file, name) else {
+            return Ok(None);
+        };
+        let Some(arguments) = self.factory_arguments(
+            &factory,
+            &call_expression.args,
+            source_file,
+            imports_map,
+            factory_file,
+            depth,
+        )?
+        else {
+            return Ok(None);
+        };
+        trace!(factory = %name, path = ?factory_file.file_path, "Inlining schema factory");
+
+        let Some(expanded) = self.substitute_parameters(&factory, &arguments, factory_file) else {
+            trace!(factory = %name, "Factory body can't be substituted, leaving the call");
+            return Ok(None);
+        };
+        self.resolving.push(name.to_string());
+        let result = self.flatten_text(&expanded, factory_file, depth + 1);
+        self.resolving.pop();
+        result.map(Some)
+    }
+
+    /// The factory body with its parameters replaced by `arguments`, or `None` when it contains
+    /// callbacks whose parameter references can't all be found.
+    fn substitute_parameters(
+        &self,
+        factory: &SchemaFactory<'_>,
+        arguments: &[String],
+        factory_file: &ParsedFile,
+    ) -> Option<String> {
+        let body_span = self.get_expression_span(factory.body);
+        let mut text = factory_file.get_source_text(body_span);
+        let (body_start, _) = factory_file.get_byte_range(body_span);
+
+        let names: Vec<&str> = factory
+            .parameters
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        let mut references = Vec::new();
+        collect_parameter_references(factory.body, &names, &mut references)?;
+        references.sort_by_key(|reference| std::cmp::Reverse(reference.span.lo));
+
+        for reference in references {
+            let (start, length) = factory_file.get_byte_range(reference.span);
+            let start = start - body_start;
+            let argument = &arguments[reference.parameter];
+            let replacement = reference
+                .is_shorthand
+                .then(|| format!("{}: {argument}", names[reference.parameter]));
+            text.replace_range(
+                start..start + length,
+                replacement.as_ref().unwrap_or(argument),
+            );
+        }
+        Some(text)
+    }
+
+    /// The flattened text passed for each parameter, falling back to its default value. `None`
+    /// when the call spreads its arguments.
+    fn factory_arguments(
+        &mut self,
+        factory: &SchemaFactory<'_>,
+        arguments: &[ExprOrSpread],
+        source_file: &ParsedFile,
+        imports_map: &HashMap<String, ResolvedImport>,
+        factory_file: &ParsedFile,
+        depth: usize,
+    ) -> ZodResult<Option<Vec<String>>> {
+        if arguments.iter().any(|argument| argument.spread.is_some()) {
+            return Ok(None);
+        }
+
+        factory
+            .parameters
+            .iter()
+            .enumerate()
+            .map(|(index, (_, default_value))| match arguments.get(index) {
+                Some(argument) => {
+                    self.flatten_argument(&argument.expr, source_file, imports_map, depth)
+                }
+                None => Ok(self.default_argument(*default_value, factory_file)),
+            })
+            .collect::<ZodResult<Vec<_>>>()
+            .map(Some)
+    }
+
+    fn default_argument(&self, default_value: Option<&Expr>, factory_file: &ParsedFile) -> String {
+        default_value.map_or_else(
+            || "undefined".to_string(),
+            |default_value| {
+                parenthesized(
+                    default_value,
+                    self.get_source_text(factory_file, self.get_expression_span(default_value)),
+                )
+            },
+        )
+    }
+
+    fn flatten_argument(
+        &mut self,
+        argument: &Expr,
+        source_file: &ParsedFile,
+        imports_map: &HashMap<String, ResolvedImport>,
+        depth: usize,
+    ) -> ZodResult<String> {
+        let argument_text = self.get_expression_text_for_replacement(argument, source_file);
+        let flattened =
+            self.flatten_expression(argument, source_file, imports_map, argument_text, depth + 1)?;
+        Ok(parenthesized(argument, flattened))
+    }
+}
+
+fn find_factory<'a>(parsed_file: &'a ParsedFile, name: &str) -> Option<SchemaFactory<'a>> {
+    parsed_file
+        .module
+        .body
+        .iter()
+        .find_map(|item| factory_in_item(item, name))
+}
+
+fn factory_in_item<'a>(item: &'a ModuleItem, name: &str) -> Option<SchemaFactory<'a>> {
+    let declaration = match item {
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_declaration)) => {
+            &export_declaration.decl
+        }
+        ModuleItem::Stmt(Stmt::Decl(declaration)) => declaration,
+        _ => return None,
+    };
+
+    match declaration {
+        Decl::Fn(function_declaration) if function_declaration.ident.sym.as_ref() == name => {
+            let function = &function_declaration.function;
+            let parameters = function.params.iter().map(|parameter| &parameter.pat);
+            factory_from(parameters, returned_expression(function.body.as_ref()?)?)
+        }
+        Decl::Var(variable_declaration) => variable_declaration
+            .decls
+            .iter()
+            .find(|declarator| {
+                declarator
+                    .name
+                    .as_ident()
+                    .is_some_and(|identifier| identifier.sym.as_ref() == name)
+            })
+            .and_then(|declarator| declarator.init.as_deref())
+            .and_then(factory_from_initializer),
+        _ => None,
+    }
+}
+
+fn factory_from_initializer(initializer: &Expr) -> Option<SchemaFactory<'_>> {
+    match initializer.unwrap_parens() {
+        Expr::Arrow(arrow) => {
+            let body = match &*arrow.body {
+                BlockStmtOrExpr::Expr(expression) => expression,
+                BlockStmtOrExpr::BlockStmt(block) => returned_expression(block)?,
+            };
+            factory_from(arrow.params.iter(), body)
+        }
+        Expr::Fn(function_expression) => {
+            let function = &function_expression.function;
+            let parameters = function.params.iter().map(|parameter| &parameter.pat);
+            factory_from(parameters, returned_expression(function.body.as_ref()?)?)
+        }
+        _ => None,
+    }
+}
+
+/// Only plain and defaulted identifier parameters can be substituted.
+fn factory_from<'a>(
+    parameters: impl Iterator<Item = &'a Pat>,
+    body: &'a Expr,
+) -> Option<SchemaFactory<'a>> {
+    let parameters = parameters
+        .map(|parameter| match parameter {
+            Pat::Ident(binding) => Some((binding.id.sym.to_string(), None)),
+            Pat::Assign(assignment) => assignment
+                .left
+                .as_ident()
+                .map(|binding| (binding.id.sym.to_string(), Some(&*assignment.right))),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(SchemaFactory { parameters, body })
+}
+
+fn returned_expression(block: &BlockStmt) -> Option<&Expr> {
+    match block.stmts.as_slice() {
+        [Stmt::Return(return_statement)] => return_statement.arg.as_deref(),
+        _ => None,
+    }
+}
+
+/// Records every reference to `names` in `expression`. `None` when it holds a callback whose body
+/// isn't a single expression, since references in its statements would be missed.
+fn collect_parameter_references(
+    expression: &Expr,
+    names: &[&str],
+    references: &mut Vec<ParameterReference>,
+) -> Option<()> {
+    let mut visit = |expression: &Expr| collect_parameter_references(expression, names, references);
+
+    match expression {
+        Expr::Ident(identifier) => {
+            if let Some(parameter) = names
+                .iter()
+                .position(|name| identifier.sym.as_ref() == *name)
+            {
+                references.push(ParameterReference {
+                    span: identifier.span,
+                    parameter,
+                    is_shorthand: false,
+                });
+            }
+            Some(())
+        }
+        Expr::Member(member) => {
+            visit(&member.obj)?;
+            member
+                .prop
+                .as_computed()
+                .map_or(Some(()), |computed| visit(&computed.expr))
+        }
+        Expr::Call(call) => {
+            if let Some(callee) = call.callee.as_expr() {
+                visit(callee)?;
+            }
+            call.args
+                .iter()
+                .try_for_each(|argument| visit(&argument.expr))
+        }
+        Expr::New(new) => {
+            visit(&new.callee)?;
+            new.args
+                .iter()
+                .flatten()
+                .try_for_each(|argument| visit(&argument.expr))
+        }
+        Expr::Object(object) => object
+            .props
+            .iter()
+            .try_for_each(|property| collect_property_references(property, names, references)),
+        Expr::Array(array) => array
+            .elems
+            .iter()
+            .flatten()
+            .try_for_each(|element| visit(&element.expr)),
+        Expr::Arrow(arrow) => {
+            let body = match &*arrow.body {
+                BlockStmtOrExpr::Expr(body) => body,
+                BlockStmtOrExpr::BlockStmt(block) => returned_expression(block)?,
+            };
+            collect_callback_references(arrow.params.iter(), body, names, references)
+        }
+        Expr::Fn(function_expression) => {
+            let function = &function_expression.function;
+            let body = returned_expression(function.body.as_ref()?)?;
+            let parameters = function.params.iter().map(|parameter| &parameter.pat);
+            collect_callback_references(parameters, body, names, references)
+        }
+        Expr::Cond(conditional) => {
+            visit(&conditional.test)?;
+            visit(&conditional.cons)?;
+            visit(&conditional.alt)
+        }
+        Expr::Bin(binary) => {
+            visit(&binary.left)?;
+            visit(&binary.right)
+        }
+        Expr::Tpl(template) => template.exprs.iter().try_for_each(|part| visit(part)),
+        Expr::Unary(unary) => visit(&unary.arg),
+        Expr::Paren(parenthesized) => visit(&parenthesized.expr),
+        Expr::TsAs(assertion) => visit(&assertion.expr),
+        Expr::TsSatisfies(satisfies) => visit(&satisfies.expr),
+        Expr::TsConstAssertion(assertion) => visit(&assertion.expr),
+        Expr::TsNonNull(non_null) => visit(&non_null.expr),
+        _ => Some(()),
+    }
+}
+
+fn collect_property_references(
+    property: &PropOrSpread,
+    names: &[&str],
+    references: &mut Vec<ParameterReference>,
+) -> Option<()> {
+    let property = match property {
+        PropOrSpread::Spread(spread) => {
+            return collect_parameter_references(&spread.expr, names, references);
+        }
+        PropOrSpread::Prop(property) => &**property,
+    };
+
+    match property {
+        Prop::KeyValue(key_value) => {
+            if let PropName::Computed(computed) = &key_value.key {
+                collect_parameter_references(&computed.expr, names, references)?;
+            }
+            collect_parameter_references(&key_value.value, names, references)
+        }
+        Prop::Shorthand(identifier) => {
+            if let Some(parameter) = names
+                .iter()
+                .position(|name| identifier.sym.as_ref() == *name)
+            {
+                references.push(ParameterReference {
+                    span: identifier.span,
+                    parameter,
+                    is_shorthand: true,
+                });
+            }
+            Some(())
+        }
+        _ => Some(()),
+    }
+}
+
+/// Callbacks such as `.refine((value) => value.length <= max)` see the factory's parameters
+/// except those their own parameters shadow.
+fn collect_callback_references<'a>(
+    parameters: impl Iterator<Item = &'a Pat>,
+    body: &Expr,
+    names: &[&str],
+    references: &mut Vec<ParameterReference>,
+) -> Option<()> {
+    let shadowed: Vec<&str> = parameters
+        .filter_map(|parameter| parameter.as_ident())
+        .map(|binding| binding.id.sym.as_ref())
+        .collect();
+    // Shadowed names are blanked rather than removed, so positions still index the parameters.
+    let visible: Vec<&str> = names
+        .iter()
+        .map(|name| if shadowed.contains(name) { "" } else { name })
+        .collect();
+    collect_parameter_references(body, &visible, references)
+}
+
+/// Wraps argument text that would bind differently once substituted, such as `a ?? b`.
+fn parenthesized(expression: &Expr, text: String) -> String {
+    match expression {
+        Expr::Ident(_)
+        | Expr::Lit(_)
+        | Expr::Call(_)
+        | Expr::Member(_)
+        | Expr::Object(_)
+        | Expr::Array(_)
+        | Expr::Paren(_)
+        | Expr::Tpl(_) => text,
+        _ => format!("({text})"),
+    }
+}
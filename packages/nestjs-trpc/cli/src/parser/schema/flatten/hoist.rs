@@ -0,0 +1,251 @@
+use super::identifier::binding_initializer;
+use super::replace_identifier;
+use super::ZodFlattener;
+use super::ZodResult;
+use crate::parser::imports::module_path::normalize_path;
+use crate::parser::imports::{DeclarationType, ResolvedImport, DEFAULT_EXPORT};
+use crate::parser::schema::helpers::ZodHelpers;
+use crate::parser::ParsedFile;
+use crate::{HoistedDeclaration, HoistedKind};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use swc_common::Span;
+use swc_ecma_ast::{Decl, Expr, ModuleDecl, ModuleItem, Stmt, TsEnumDecl};
+use tracing::trace;
+use xxhash_rust::xxh3::xxh3_64;
+
+impl ZodFlattener<'_> {
+    /// Flattens the schema variable `name` refers to as a declaration procedures can share, and
+    /// returns the placeholder to use in its place. The placeholder depends on where the schema
+    /// is declared, so every router referring to it shares one declaration.
+    pub fn hoist_schema(
+        &mut self,
+        name: &str,
+        source_file: &ParsedFile,
+    ) -> ZodResult<Option<String>> {
+        let Some((origin_name, origin_path)) = self.schema_origin(name, source_file)? else {
+            return Ok(None);
+        };
+        let placeholder = schema_placeholder(&origin_name, &origin_path);
+        if self.is_hoisted(&placeholder) {
+            return Ok(Some(placeholder));
+        }
+
+        let schema = self.flatten_schema(name, source_file)?;
+        if schema == name {
+            return Ok(None);
+        }
+        // Recursive schemas are hoisted while they are flattened.
+        if self.is_hoisted(&placeholder) {
+            return Ok(Some(placeholder));
+        }
+
+        self.hoisted.push(HoistedDeclaration {
+            name: flattened_name(&origin_name, name),
+            source_file: origin_path,
+            placeholder: placeholder.clone(),
+            declaration: schema,
+            kind: HoistedKind::Schema,
+            schema_type: None,
+        });
+        Ok(Some(placeholder))
+    }
+
+    /// Declares `schema`, the flattened `name`, since it refers to itself, e.g. through
+    /// `z.lazy(() => categorySchema)`, and can't be inlined into itself. Returns the placeholder
+    /// its references to `name` are replaced with.
+    pub(super) fn hoist_recursive_schema(
+        &mut self,
+        name: &str,
+        origin_name: &str,
+        origin_path: &Path,
+        schema: &str,
+    ) -> String {
+        let placeholder = schema_placeholder(origin_name, origin_path);
+        if self.is_hoisted(&placeholder) {
+            return placeholder;
+        }
+
+        trace!(name = %name, path = ?origin_path, "Hoisting recursive schema");
+        self.hoisted.push(HoistedDeclaration {
+            name: flattened_name(origin_name, name),
+            source_file: origin_path.to_path_buf(),
+            placeholder: placeholder.clone(),
+            declaration: replace_identifier(schema, name, &placeholder)
+                .unwrap_or_else(|| schema.to_string()),
+            kind: HoistedKind::RecursiveSchema,
+            schema_type: None,
+        });
+        placeholder
+    }
+
+    /// The name and file of the variable `name` is declared as, following imports, when it is
+    /// initialized like a schema, e.g. `z.object(...)` or `baseSchema.extend(...)`, rather than
+    /// being an object or function holding schemas.
+    fn schema_origin(
+        &self,
+        name: &str,
+        source_file: &ParsedFile,
+    ) -> ZodResult<Option<(String, PathBuf)>> {
+        if let Some(initializer) = binding_initializer(source_file, name) {
+            let origin = (name.to_string(), source_file.file_path.clone());
+            return Ok(is_schema_expression(&initializer).then_some(origin));
+        }
+
+        let imports_map = self.imports_map(source_file);
+        let Some(import) = imports_map
+            .get(name)
+            .filter(|import| import.declaration_type != DeclarationType::Namespace)
+        else {
+            return Ok(None);
+        };
+        let imported_file = self.get_or_parse_file(&import.source_file)?;
+        let is_schema = binding_initializer(&imported_file, &import.name)
+            .is_some_and(|initializer| is_schema_expression(&initializer));
+        Ok(is_schema.then(|| (import.name.clone(), import.source_file.clone())))
+    }
+
+    /// Enums can't be inlined like constants, so the non-exported enum `name` refers to is copied
+    /// into the generated file instead. Returns the placeholder the schema should refer to it by.
+    pub(super) fn hoist_enum(
+        &mut self,
+        name: &str,
+        source_file: &ParsedFile,
+        imports_map: &HashMap<String, ResolvedImport>,
+        depth: usize,
+    ) -> ZodResult<Option<String>> {
+        if find_enum(source_file, name).is_some() {
+            return self.hoist_enum_from(name, source_file, imports_map, depth);
+        }
+
+        let Some(import) = imports_map.get(name) else {
+            return Ok(None);
+        };
+        let imported_file = self.get_or_parse_file(&import.source_file)?;
+        let imported_imports_map = self.imports_map(&imported_file);
+        self.hoist_enum_from(&import.name, &imported_file, &imported_imports_map, depth)
+    }
+
+    fn hoist_enum_from(
+        &mut self,
+        name: &str,
+        file: &ParsedFile,
+        imports_map: &HashMap<String, ResolvedImport>,
+        depth: usize,
+    ) -> ZodResult<Option<String>> {
+        let Some(declaration) = find_enum(file, name) else {
+            return Ok(None);
+        };
+
+        let placeholder = hoisted_placeholder(name, &file.file_path);
+        if self.resolving.contains(&placeholder) || self.is_hoisted(&placeholder) {
+            return Ok(Some(placeholder));
+        }
+
+        trace!(name = %name, path = ?file.file_path, "Hoisting enum");
+
+        self.resolving.push(placeholder.clone());
+        let text = self.hoisted_enum_text(declaration, &placeholder, file, imports_map, depth);
+        self.resolving.pop();
+
+        // Pushed after its members are flattened, so the enums it depends on come first.
+        self.hoisted.push(HoistedDeclaration {
+            name: name.to_string(),
+            source_file: file.file_path.clone(),
+            placeholder: placeholder.clone(),
+            declaration: text?,
+            kind: HoistedKind::Enum,
+            schema_type: None,
+        });
+        Ok(Some(placeholder))
+    }
+
+    fn is_hoisted(&self, placeholder: &str) -> bool {
+        self.hoisted
+            .iter()
+            .any(|hoisted| hoisted.placeholder == placeholder)
+    }
+
+    /// The enum's source with its name swapped for `placeholder` and its member initializers
+    /// flattened, so constants they use are inlined and enums they use are hoisted too.
+    fn hoisted_enum_text(
+        &mut self,
+        declaration: &TsEnumDecl,
+        placeholder: &str,
+        file: &ParsedFile,
+        imports_map: &HashMap<String, ResolvedImport>,
+        depth: usize,
+    ) -> ZodResult<String> {
+        let initializers = declaration
+            .members
+            .iter()
+            .filter_map(|member| member.init.as_deref())
+            .map(|initializer| self.flatten_initializer(initializer, file, imports_map, depth))
+            .collect::<ZodResult<Vec<_>>>()?;
+
+        let mut replacements: Vec<(Span, String)> = initializers.into_iter().flatten().collect();
+        replacements.push((declaration.id.span, placeholder.to_string()));
+        replacements.sort_by_key(|(span, _)| std::cmp::Reverse(span.lo));
+
+        let mut text = file.get_source_text(declaration.span);
+        let (declaration_start, _) = file.get_byte_range(declaration.span);
+        for (span, replacement) in replacements {
+            let (start, length) = file.get_byte_range(span);
+            let start = start - declaration_start;
+            text.replace_range(start..start + length, &replacement);
+        }
+        Ok(text)
+    }
+
+    fn flatten_initializer(
+        &mut self,
+        initializer: &Expr,
+        file: &ParsedFile,
+        imports_map: &HashMap<String, ResolvedImport>,
+        depth: usize,
+    ) -> ZodResult<Option<(Span, String)>> {
+        let text = self.get_expression_text_for_replacement(initializer, file);
+        let flattened =
+            self.flatten_expression(initializer, file, imports_map, text.clone(), depth + 1)?;
+        Ok((flattened != text).then(|| (self.get_expression_span(initializer), flattened)))
+    }
+}
+
+fn schema_placeholder(origin_name: &str, origin_path: &Path) -> String {
+    hoisted_placeholder(&format!("{origin_name}_flat"), origin_path)
+}
+
+/// Named after the declaration, unless it is a default export and has no name of its own.
+fn flattened_name(origin_name: &str, local_name: &str) -> String {
+    let declared_name = if origin_name == DEFAULT_EXPORT {
+        local_name
+    } else {
+        origin_name
+    };
+    format!("{declared_name}_flat")
+}
+
+fn is_schema_expression(expression: &Expr) -> bool {
+    matches!(expression.unwrap_parens(), Expr::Call(_) | Expr::Member(_))
+}
+
+/// A stand-in name unique to `name` in `path`, e.g. `$hoisted$Role$9f86d081884c7d65`. The prefix
+/// and fixed-length hash keep one placeholder from ever containing another.
+fn hoisted_placeholder(name: &str, path: &Path) -> String {
+    let path_hash = xxh3_64(normalize_path(path).to_string_lossy().as_bytes());
+    format!("$hoisted${name}${path_hash:016x}")
+}
+
+fn find_enum<'a>(file: &'a ParsedFile, name: &str) -> Option<&'a TsEnumDecl> {
+    file.module.body.iter().find_map(|item| {
+        let declaration = match item {
+            ModuleItem::Stmt(Stmt::Decl(declaration)) => declaration,
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => &export.decl,
+            _ => return None,
+        };
+        let Decl::TsEnum(enum_declaration) = declaration else {
+            return None;
+        };
+        (enum_declaration.id.sym.as_ref() == name).then_some(&**enum_declaration)
+    })
+}
@@ -1,13 +1,14 @@
 use super::ZodFlattener;
 use super::ZodResult;
 use crate::error::GeneratorError;
-use crate::parser::imports::{ImportResolver, ResolvedImport};
+use crate::parser::imports::{ResolvedImport, DEFAULT_EXPORT};
 use crate::parser::schema::helpers::ZodHelpers;
 use crate::parser::ParsedFile;
 use std::collections::HashMap;
 use std::path::Path;
-use swc_ecma_ast::{Decl, Expr, ModuleDecl, ModuleItem, Pat, Stmt};
-use tracing::{trace, warn};
+use std::sync::Arc;
+use swc_ecma_ast::{Decl, ExportSpecifier, Expr, ModuleDecl, ModuleItem, Pat, Stmt};
+use tracing::trace;
 
 impl ZodFlattener<'_> {
     pub(super) fn flatten_identifier(
@@ -22,11 +23,12 @@ impl ZodFlattener<'_> {
         trace!(identifier = %name, "Processing identifier");
 
         if self.resolving.contains(&name) {
-            warn!(identifier = %name, "Circular schema reference detected");
+            trace!(identifier = %name, "Recursive schema reference");
+            self.recursive.insert(name);
             return Ok(schema);
         }
 
-        if name == "z" {
+        if self.is_validator_binding(source_file, &name) {
             return Ok(schema);
         }
 
@@ -34,7 +36,7 @@ impl ZodFlattener<'_> {
             return Ok(schema);
         }
 
-        self.try_resolve_identifier(&name, source_file, imports_map, schema, depth)
+        self.try_resolve_identifier(&name, source_file, imports_map, &schema, depth)
     }
 
     fn try_resolve_identifier(
@@ -42,7 +44,7 @@ impl ZodFlattener<'_> {
         name: &str,
         source_file: &ParsedFile,
         imports_map: &HashMap<String, ResolvedImport>,
-        schema: String,
+        schema: &str,
         depth: usize,
     ) -> ZodResult<String> {
         if let Some(resolved_schema) =
@@ -51,25 +53,28 @@ impl ZodFlattener<'_> {
             return Ok(schema.replace(name, &resolved_schema));
         }
 
-        self.try_resolve_from_imports(name, imports_map, schema, depth)
+        if let Some(resolved_schema) = self.try_resolve_from_imports(name, imports_map, depth)? {
+            return Ok(schema.replace(name, &resolved_schema));
+        }
+
+        let placeholder = self.hoist_enum(name, source_file, imports_map, depth)?;
+        Ok(placeholder.map_or_else(
+            || schema.to_string(),
+            |placeholder| schema.replace(name, &placeholder),
+        ))
     }
 
     fn try_resolve_from_imports(
         &mut self,
         name: &str,
         imports_map: &HashMap<String, ResolvedImport>,
-        schema: String,
         depth: usize,
-    ) -> ZodResult<String> {
+    ) -> ZodResult<Option<String>> {
         let Some(import) = imports_map.get(name) else {
-            return Ok(schema);
-        };
-
-        let Some(resolved_schema) = self.resolve_imported_identifier(name, import, depth)? else {
-            return Ok(schema);
+            return Ok(None);
         };
 
-        Ok(schema.replace(name, &resolved_schema))
+        self.resolve_imported_identifier(name, import, depth)
     }
 
     fn resolve_local_identifier(
@@ -84,7 +89,23 @@ impl ZodFlattener<'_> {
         let result = self.search_local_identifier(name, source_file, imports_map, depth);
 
         self.resolving.pop();
-        result
+        Ok(result?
+            .map(|schema| self.hoist_if_recursive(name, name, &source_file.file_path, schema)))
+    }
+
+    /// Replaces a schema that referred to `name` while being flattened with a placeholder for
+    /// its hoisted declaration.
+    fn hoist_if_recursive(
+        &mut self,
+        name: &str,
+        origin_name: &str,
+        origin_path: &Path,
+        schema: String,
+    ) -> String {
+        if !self.recursive.remove(name) {
+            return schema;
+        }
+        self.hoist_recursive_schema(name, origin_name, origin_path, &schema)
     }
 
     fn search_local_identifier(
@@ -128,32 +149,24 @@ impl ZodFlattener<'_> {
     ) -> ZodResult<Option<String>> {
         self.resolving.push(name.to_string());
 
-        let result = self.search_imported_identifier(name, import, depth);
+        let result = self.search_imported_identifier(import, depth);
 
         self.resolving.pop();
-        result
+        Ok(result?
+            .map(|schema| self.hoist_if_recursive(name, &import.name, &import.source_file, schema)))
     }
 
     fn search_imported_identifier(
         &mut self,
-        name: &str,
         import: &ResolvedImport,
         depth: usize,
     ) -> ZodResult<Option<String>> {
         let imported_file = self.get_or_parse_file(&import.source_file)?;
 
-        let mut resolver = ImportResolver::new(self.parser);
-        let imported_imports_map = resolver
-            .build_imports_map(&imported_file, &self.base_directory)
-            .unwrap_or_default();
+        let imported_imports_map = self.imports_map(&imported_file);
 
-        let initializer = imported_file
-            .module
-            .body
-            .iter()
-            .find_map(|item| Self::find_variable_declaration_initializer(item, name));
-
-        let Some(initializer_expression) = initializer else {
+        // Aliased and default imports are declared under their exported name, not the local one.
+        let Some(initializer_expression) = binding_initializer(&imported_file, &import.name) else {
             return Ok(None);
         };
 
@@ -183,6 +196,50 @@ impl ZodFlattener<'_> {
     }
 }
 
+/// The expression bound to `name` in `file`, where [`DEFAULT_EXPORT`] is its default export.
+pub(super) fn binding_initializer(file: &ParsedFile, name: &str) -> Option<Expr> {
+    if name == DEFAULT_EXPORT {
+        return file
+            .module
+            .body
+            .iter()
+            .find_map(|item| default_export_initializer(item, file));
+    }
+
+    file.module
+        .body
+        .iter()
+        .find_map(|item| ZodFlattener::find_variable_declaration_initializer(item, name))
+}
+
+fn default_export_initializer(item: &ModuleItem, file: &ParsedFile) -> Option<Expr> {
+    match item {
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(export)) => {
+            match export.expr.unwrap_parens() {
+                Expr::Ident(identifier) => binding_initializer(file, identifier.sym.as_ref()),
+                expression => Some(expression.clone()),
+            }
+        }
+        ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(export)) if export.src.is_none() => {
+            let local_name = export
+                .specifiers
+                .iter()
+                .find_map(default_export_specifier)?;
+            binding_initializer(file, &local_name)
+        }
+        _ => None,
+    }
+}
+
+/// The local name in `export { userSchema as default }`.
+fn default_export_specifier(specifier: &ExportSpecifier) -> Option<String> {
+    let ExportSpecifier::Named(named) = specifier else {
+        return None;
+    };
+    let exported = named.exported.as_ref().unwrap_or(&named.orig);
+    (exported.atom().as_ref() == DEFAULT_EXPORT).then(|| named.orig.atom().to_string())
+}
+
 fn extract_variable_declaration(item: &ModuleItem) -> Option<&swc_ecma_ast::VarDecl> {
     match item {
         ModuleItem::Stmt(Stmt::Decl(Decl::Var(variable))) => Some(variable),
@@ -215,21 +272,13 @@ fn extract_matching_initializer(
 }
 
 impl ZodFlattener<'_> {
-    fn get_or_parse_file(&mut self, path: &Path) -> ZodResult<ParsedFile> {
-        if let Some(cached) = self.parsed_cache.get(path) {
-            return Ok(cached.clone());
-        }
-
-        let parsed =
-            self.parser
-                .parse_file(path)
-                .map_err(|error| GeneratorError::SchemaFlattenFailed {
-                    path: path.to_path_buf(),
-                    schema: String::new(),
-                    message: format!("Failed to parse file: {error}"),
-                })?;
-
-        self.parsed_cache.insert(path.to_path_buf(), parsed.clone());
-        Ok(parsed)
+    pub(super) fn get_or_parse_file(&self, path: &Path) -> ZodResult<Arc<ParsedFile>> {
+        self.parse_cache
+            .get_or_parse(self.parser, path)
+            .map_err(|error| GeneratorError::SchemaFlattenFailed {
+                path: path.to_path_buf(),
+                schema: String::new(),
+                message: format!("Failed to parse file: {error}"),
+            })
     }
 }
@@ -0,0 +1,304 @@
+use super::identifier::binding_initializer;
+use super::ZodFlattener;
+use super::ZodResult;
+use crate::parser::imports::{DeclarationType, ImportResolver, ResolvedImport, DEFAULT_EXPORT};
+use crate::parser::procedure::find_class_by_name;
+use crate::parser::schema::helpers::ZodHelpers;
+use crate::parser::ParsedFile;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use swc_ecma_ast::{
+    Class, ClassMember, DefaultDecl, Expr, Lit, MemberExpr, MemberProp, ModuleDecl, ModuleItem,
+    ObjectLit, Prop, PropName, PropOrSpread,
+};
+use tracing::trace;
+
+/// The file a member chain is followed through; `None` is the file being flattened.
+type Location = Option<Arc<ParsedFile>>;
+
+/// What a name or member expression refers to while following a chain like `S.users.create`.
+enum Binding {
+    /// A module imported with `import * as S` or re-exported with `export * as S`.
+    Module(PathBuf),
+    Class(Box<Class>, Location),
+    Expression(Expr, Location),
+}
+
+/// The file being flattened and the imports it resolves names against.
+#[derive(Clone, Copy)]
+pub(super) struct Root<'a> {
+    pub(super) source_file: &'a ParsedFile,
+    pub(super) imports_map: &'a HashMap<String, ResolvedImport>,
+}
+
+impl ZodFlattener<'_> {
+    /// Flattens references like `S.createUser`, `UserSchemas.create` or `schemas.users.create`
+    /// that `server.ts` can't import, by following namespace imports, object literal properties
+    /// and static class properties to the schema they name.
+    pub(super) fn flatten_member_reference(
+        &mut self,
+        member_expression: &MemberExpr,
+        root: Root<'_>,
+        depth: usize,
+    ) -> ZodResult<Option<String>> {
+        let reference = self.get_source_text(root.source_file, member_expression.span);
+        if self.resolving.contains(&reference) {
+            return Ok(None);
+        }
+
+        let Some(Binding::Expression(expression, location)) =
+            self.resolve_member(member_expression, root, &None, depth)?
+        else {
+            return Ok(None);
+        };
+        trace!(reference = %reference, "Resolved member reference");
+
+        self.resolving.push(reference);
+        let result = self.flatten_at(&expression, root, location.as_deref(), depth);
+        self.resolving.pop();
+        result.map(Some)
+    }
+
+    fn flatten_at(
+        &mut self,
+        expression: &Expr,
+        root: Root<'_>,
+        file: Option<&ParsedFile>,
+        depth: usize,
+    ) -> ZodResult<String> {
+        let Some(file) = file else {
+            let text = self.get_expression_text_for_replacement(expression, root.source_file);
+            return self.flatten_expression(
+                expression,
+                root.source_file,
+                root.imports_map,
+                text,
+                depth + 1,
+            );
+        };
+
+        let imports_map = self.imports_map(file);
+        let text = self.get_expression_text_for_replacement(expression, file);
+        self.flatten_expression(expression, file, &imports_map, text, depth + 1)
+    }
+
+    fn resolve_member(
+        &self,
+        member_expression: &MemberExpr,
+        root: Root<'_>,
+        location: &Location,
+        depth: usize,
+    ) -> ZodResult<Option<Binding>> {
+        let Some(property) = member_property_name(&member_expression.prop) else {
+            return Ok(None);
+        };
+        if depth >= self.max_depth {
+            return Ok(None);
+        }
+
+        let object = match strip_type_wrappers(&member_expression.obj) {
+            Expr::Ident(identifier) => {
+                self.resolve_binding(identifier.sym.as_ref(), root, location)?
+            }
+            Expr::Member(nested) => self.resolve_member(nested, root, location, depth + 1)?,
+            object => Some(Binding::Expression(object.clone(), location.clone())),
+        };
+
+        let Some(object) = object else {
+            return Ok(None);
+        };
+        self.resolve_property(object, &property, root, depth + 1)
+    }
+
+    fn resolve_binding(
+        &self,
+        name: &str,
+        root: Root<'_>,
+        location: &Location,
+    ) -> ZodResult<Option<Binding>> {
+        // `server.ts` imports these itself, so the member access can stay as written.
+        let is_importable = location.is_none() && self.importable_identifiers.contains(name);
+        let file = location.as_deref().unwrap_or(root.source_file);
+        let imports_map = self.imports_map(file);
+        if is_importable
+            || self.resolving.iter().any(|resolving| resolving == name)
+            || self.is_validator_binding(file, name)
+        {
+            return Ok(None);
+        }
+
+        let Some(import) = imports_map.get(name) else {
+            return Ok(declaration_binding(file, name, location));
+        };
+        if import.declaration_type == DeclarationType::Namespace {
+            return Ok(Some(Binding::Module(import.source_file.clone())));
+        }
+
+        let imported_file = self.get_or_parse_file(&import.source_file)?;
+        Ok(declaration_binding(
+            &imported_file,
+            &import.name,
+            &Some(Arc::clone(&imported_file)),
+        ))
+    }
+
+    fn resolve_property(
+        &self,
+        binding: Binding,
+        property: &str,
+        root: Root<'_>,
+        depth: usize,
+    ) -> ZodResult<Option<Binding>> {
+        if depth >= self.max_depth {
+            return Ok(None);
+        }
+
+        match binding {
+            Binding::Module(module_path) => self.resolve_module_export(&module_path, property),
+            Binding::Class(class, location) => Ok(static_property(&class, property)
+                .map(|value| Binding::Expression(value.clone(), location))),
+            Binding::Expression(expression, location) => {
+                self.resolve_expression_property(&expression, location, property, root, depth)
+            }
+        }
+    }
+
+    fn resolve_expression_property(
+        &self,
+        expression: &Expr,
+        location: Location,
+        property: &str,
+        root: Root<'_>,
+        depth: usize,
+    ) -> ZodResult<Option<Binding>> {
+        let owner = match strip_type_wrappers(expression) {
+            Expr::Object(object) => {
+                return Ok(object_property(object, property)
+                    .map(|value| Binding::Expression(value, location)));
+            }
+            Expr::Ident(identifier) => {
+                self.resolve_binding(identifier.sym.as_ref(), root, &location)?
+            }
+            Expr::Member(member_expression) => {
+                self.resolve_member(member_expression, root, &location, depth + 1)?
+            }
+            _ => None,
+        };
+
+        let Some(owner) = owner else {
+            return Ok(None);
+        };
+        self.resolve_property(owner, property, root, depth + 1)
+    }
+
+    fn resolve_module_export(
+        &self,
+        module_path: &std::path::Path,
+        name: &str,
+    ) -> ZodResult<Option<Binding>> {
+        let resolved = ImportResolver::new(self.parser)
+            .with_parse_cache(self.parse_cache.clone())
+            .resolve_export(module_path, name, &self.base_directory);
+        let Ok(resolved) = resolved else {
+            return Ok(None);
+        };
+        if resolved.declaration_type == DeclarationType::Namespace {
+            return Ok(Some(Binding::Module(resolved.source_file)));
+        }
+
+        let file = self.get_or_parse_file(&resolved.source_file)?;
+        Ok(declaration_binding(
+            &file,
+            &resolved.name,
+            &Some(Arc::clone(&file)),
+        ))
+    }
+}
+
+/// What `name` is declared as in `file`: a class, or the expression a variable is initialized to.
+fn declaration_binding(file: &ParsedFile, name: &str, location: &Location) -> Option<Binding> {
+    let class = if name == DEFAULT_EXPORT {
+        default_exported_class(file)
+    } else {
+        find_class_by_name(&file.module.body, name)
+    };
+    if let Some(class) = class {
+        return Some(Binding::Class(Box::new(class.clone()), location.clone()));
+    }
+
+    binding_initializer(file, name)
+        .map(|initializer| Binding::Expression(initializer, location.clone()))
+}
+
+fn default_exported_class(file: &ParsedFile) -> Option<&Class> {
+    file.module.body.iter().find_map(|item| {
+        let ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(export)) = item else {
+            return None;
+        };
+        let DefaultDecl::Class(class_expression) = &export.decl else {
+            return None;
+        };
+        Some(&*class_expression.class)
+    })
+}
+
+fn member_property_name(property: &MemberProp) -> Option<String> {
+    match property {
+        MemberProp::Ident(identifier) => Some(identifier.sym.to_string()),
+        MemberProp::Computed(computed) => match &*computed.expr {
+            Expr::Lit(Lit::Str(string)) => Some(string.value.to_string_lossy().into_owned()),
+            _ => None,
+        },
+        MemberProp::PrivateName(_) => None,
+    }
+}
+
+/// Looks through `(...)`, `as const` and `satisfies` so `{ ... } as const` still reads as an object.
+fn strip_type_wrappers(expression: &Expr) -> &Expr {
+    match expression {
+        Expr::Paren(parenthesized) => strip_type_wrappers(&parenthesized.expr),
+        Expr::TsConstAssertion(assertion) => strip_type_wrappers(&assertion.expr),
+        Expr::TsAs(assertion) => strip_type_wrappers(&assertion.expr),
+        Expr::TsSatisfies(satisfies) => strip_type_wrappers(&satisfies.expr),
+        _ => expression,
+    }
+}
+
+fn object_property(object: &ObjectLit, property: &str) -> Option<Expr> {
+    object.props.iter().find_map(|property_or_spread| {
+        let PropOrSpread::Prop(object_property) = property_or_spread else {
+            return None;
+        };
+        match &**object_property {
+            Prop::KeyValue(key_value) if property_key_matches(&key_value.key, property) => {
+                Some((*key_value.value).clone())
+            }
+            Prop::Shorthand(identifier) if identifier.sym.as_ref() == property => {
+                Some(Expr::Ident(identifier.clone()))
+            }
+            _ => None,
+        }
+    })
+}
+
+fn static_property<'a>(class: &'a Class, property: &str) -> Option<&'a Expr> {
+    class.body.iter().find_map(|member| {
+        let ClassMember::ClassProp(class_property) = member else {
+            return None;
+        };
+        let is_match =
+            class_property.is_static && property_key_matches(&class_property.key, property);
+        is_match
+            .then_some(class_property.value.as_deref())
+            .flatten()
+    })
+}
+
+fn property_key_matches(key: &PropName, property: &str) -> bool {
+    match key {
+        PropName::Ident(identifier) => identifier.sym.as_ref() == property,
+        PropName::Str(string) => string.value.to_string_lossy() == property,
+        _ => false,
+    }
+}
@@ -1,13 +1,21 @@
 mod expression;
+mod factory;
+mod hoist;
 mod identifier;
+mod member;
 
 use super::helpers::ZodHelpers;
 use crate::error::GeneratorError;
-use crate::parser::imports::ResolvedImport;
+use crate::parser::cache::ParseCache;
+use crate::parser::imports::{ImportResolver, ResolvedImport};
+use crate::parser::schema::validator::validator_bindings;
 use crate::parser::{ParsedFile, TsParser};
-use std::collections::{HashMap, HashSet};
+use crate::HoistedDeclaration;
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use swc_ecma_ast::{Decl, Expr, ModuleItem, Stmt};
+use std::rc::Rc;
+use swc_ecma_ast::Expr;
 use tracing::{debug, trace, warn};
 
 const DEFAULT_MAX_SCHEMA_FLATTEN_DEPTH: usize = 20;
@@ -18,10 +26,19 @@ pub type ZodResult<T> = std::result::Result<T, GeneratorError>;
 pub struct ZodFlattener<'a> {
     pub(super) parser: &'a TsParser,
     pub(super) base_directory: PathBuf,
-    pub(super) parsed_cache: HashMap<PathBuf, ParsedFile>,
+    pub(super) parse_cache: ParseCache,
     pub(super) max_depth: usize,
     pub(super) resolving: Vec<String>,
+    /// Names referred to while they were being resolved, i.e. recursive schemas.
+    pub(super) recursive: HashSet<String>,
     pub(super) importable_identifiers: HashSet<String>,
+    pub(super) hoisted: Vec<HoistedDeclaration>,
+    /// Files the flattened schemas were copied from.
+    source_files: BTreeSet<PathBuf>,
+    /// Names each file imports from validator packages, detected once per file.
+    validator_bindings: RefCell<HashMap<PathBuf, HashSet<String>>>,
+    /// Imports maps of the files schemas are flattened in, built once per file.
+    imports_maps: RefCell<HashMap<PathBuf, Rc<HashMap<String, ResolvedImport>>>>,
 }
 
 impl<'a> ZodFlattener<'a> {
@@ -30,10 +47,15 @@ impl<'a> ZodFlattener<'a> {
         Self {
             parser,
             base_directory: base_directory.to_path_buf(),
-            parsed_cache: HashMap::new(),
+            parse_cache: ParseCache::new(),
             max_depth: DEFAULT_MAX_SCHEMA_FLATTEN_DEPTH,
             resolving: Vec::new(),
+            recursive: HashSet::new(),
             importable_identifiers: HashSet::new(),
+            hoisted: Vec::new(),
+            source_files: BTreeSet::new(),
+            validator_bindings: RefCell::default(),
+            imports_maps: RefCell::default(),
         }
     }
 
@@ -43,12 +65,44 @@ impl<'a> ZodFlattener<'a> {
         self
     }
 
+    /// Shares parsed files with other flatteners and import resolvers, including across threads.
+    #[must_use]
+    pub fn with_parse_cache(mut self, parse_cache: ParseCache) -> Self {
+        self.parse_cache = parse_cache;
+        self
+    }
+
+    /// Reuses an imports map the caller already built for `parsed_file`.
+    #[must_use]
+    pub fn with_imports_map(
+        self,
+        parsed_file: &ParsedFile,
+        imports_map: HashMap<String, ResolvedImport>,
+    ) -> Self {
+        self.imports_maps
+            .borrow_mut()
+            .insert(parsed_file.file_path.clone(), Rc::new(imports_map));
+        self
+    }
+
     #[must_use]
     pub fn with_importable_identifiers(mut self, identifiers: HashSet<String>) -> Self {
         self.importable_identifiers = identifiers;
         self
     }
 
+    /// Enums and schemas the schemas flattened so far refer to by placeholder, dependencies first.
+    #[must_use]
+    pub fn hoisted_declarations(&self) -> &[HoistedDeclaration] {
+        &self.hoisted
+    }
+
+    /// Files the schemas flattened so far were copied from, including their own.
+    #[must_use]
+    pub const fn source_files(&self) -> &BTreeSet<PathBuf> {
+        &self.source_files
+    }
+
     pub fn flatten_schema(
         &mut self,
         schema_text: &str,
@@ -56,36 +110,10 @@ impl<'a> ZodFlattener<'a> {
     ) -> ZodResult<String> {
         debug!(schema = %schema_text, "Flattening Zod schema");
 
-        let temporary_code = format!("const __temp = {schema_text};");
-        let temporary_parsed = self
-            .parser
-            .parse_source("<schema>", &temporary_code)
-            .map_err(|error| GeneratorError::SchemaFlattenFailed {
-                path: source_file.file_path.clone(),
-                schema: schema_text.to_string(),
-                message: format!("Failed to parse schema: {error}"),
-            })?;
-
-        let expression =
-            Self::extract_temporary_expression(&temporary_parsed).ok_or_else(|| {
-                GeneratorError::SchemaFlattenFailed {
-                    path: source_file.file_path.clone(),
-                    schema: schema_text.to_string(),
-                    message: "Could not extract expression from schema".to_string(),
-                }
-            })?;
-
-        let imports_map = self.build_imports_map_for_file(source_file);
-
         self.resolving.clear();
+        self.recursive.clear();
 
-        let result = self.flatten_expression(
-            &expression,
-            source_file,
-            &imports_map,
-            schema_text.to_string(),
-            0,
-        )?;
+        let result = self.flatten_text(schema_text, source_file, 0)?;
 
         debug!(
             original = %schema_text,
@@ -96,37 +124,62 @@ impl<'a> ZodFlattener<'a> {
         Ok(result)
     }
 
-    fn extract_temporary_expression(parsed: &ParsedFile) -> Option<Expr> {
-        parsed
-            .module
-            .body
-            .iter()
-            .find_map(extract_variable_initializer)
-    }
-}
-
-fn extract_variable_initializer(item: &ModuleItem) -> Option<Expr> {
-    let ModuleItem::Stmt(Stmt::Decl(Decl::Var(variable_declaration))) = item else {
-        return None;
-    };
+    /// Flattens `schema_text` as if it were written in `source_file`, so the bindings it refers
+    /// to resolve against that file. Only the schema text is parsed.
+    pub(super) fn flatten_text(
+        &mut self,
+        schema_text: &str,
+        source_file: &ParsedFile,
+        depth: usize,
+    ) -> ZodResult<String> {
+        let (scope, expression) = self
+            .parser
+            .parse_expression_in(source_file, schema_text)
+            .map_err(|error| GeneratorError::SchemaFlattenFailed {
+                path: source_file.file_path.clone(),
+                schema: schema_text.to_string(),
+                message: format!("Failed to parse schema: {error}"),
+            })?;
+        let imports_map = self.imports_map(source_file);
 
-    variable_declaration
-        .decls
-        .iter()
-        .find_map(|declarator| declarator.init.as_ref())
-        .map(|initializer| (*initializer.clone()).clone())
+        self.flatten_expression(
+            &expression,
+            &scope,
+            &imports_map,
+            schema_text.to_string(),
+            depth,
+        )
+    }
 }
 
 impl ZodFlattener<'_> {
-    fn build_imports_map_for_file(
+    /// Whether `name` is imported from a validator package in `parsed_file`, e.g. `z` or `v`.
+    pub(super) fn is_validator_binding(&self, parsed_file: &ParsedFile, name: &str) -> bool {
+        self.validator_bindings
+            .borrow_mut()
+            .entry(parsed_file.file_path.clone())
+            .or_insert_with(|| validator_bindings(parsed_file))
+            .contains(name)
+    }
+
+    /// The imports map of `source_file`, empty when its imports can't be resolved.
+    pub(super) fn imports_map(
         &self,
         source_file: &ParsedFile,
-    ) -> HashMap<String, ResolvedImport> {
-        use crate::parser::imports::ImportResolver;
-        let mut resolver = ImportResolver::new(self.parser);
-        resolver
+    ) -> Rc<HashMap<String, ResolvedImport>> {
+        if let Some(imports_map) = self.imports_maps.borrow().get(&source_file.file_path) {
+            return Rc::clone(imports_map);
+        }
+
+        let imports_map = ImportResolver::new(self.parser)
+            .with_parse_cache(self.parse_cache.clone())
             .build_imports_map(source_file, &self.base_directory)
-            .unwrap_or_default()
+            .unwrap_or_default();
+        let imports_map = Rc::new(imports_map);
+        self.imports_maps
+            .borrow_mut()
+            .insert(source_file.file_path.clone(), Rc::clone(&imports_map));
+        imports_map
     }
 
     pub(super) fn flatten_expression(
@@ -145,6 +198,9 @@ impl ZodFlattener<'_> {
             );
             return Ok(schema);
         }
+        if !self.source_files.contains(&source_file.file_path) {
+            self.source_files.insert(source_file.file_path.clone());
+        }
 
         trace!(
             depth,
@@ -187,6 +243,18 @@ impl ZodFlattener<'_> {
                     depth + 1,
                 )?;
             }
+            Expr::Arrow(arrow) => {
+                schema = self.flatten_arrow(arrow, source_file, imports_map, schema, depth)?;
+            }
+            Expr::Bin(binary_expression) => {
+                schema = self.flatten_binary(
+                    binary_expression,
+                    source_file,
+                    imports_map,
+                    schema,
+                    depth,
+                )?;
+            }
             _ => {}
         }
 
@@ -194,10 +262,52 @@ impl ZodFlattener<'_> {
     }
 }
 
+/// Replaces `identifier` where it stands on its own, skipping property accesses like `.Role`
+/// and longer names that contain it, such as `UserRole` or an earlier hoisting placeholder.
+pub fn replace_identifier(text: &str, identifier: &str, replacement: &str) -> Option<String> {
+    let mut replaced = String::with_capacity(text.len());
+    let mut end = 0;
+    for start in standalone_occurrences(text, identifier) {
+        replaced.push_str(&text[end..start]);
+        replaced.push_str(replacement);
+        end = start + identifier.len();
+    }
+    if end == 0 {
+        return None;
+    }
+    replaced.push_str(&text[end..]);
+    Some(replaced)
+}
+
+/// How many times `identifier` stands on its own in `text`, as [`replace_identifier`] sees it.
+pub fn count_identifier(text: &str, identifier: &str) -> usize {
+    standalone_occurrences(text, identifier).count()
+}
+
+/// Byte offsets where `identifier` appears neither after a `.` nor inside a longer name.
+fn standalone_occurrences<'a>(
+    text: &'a str,
+    identifier: &'a str,
+) -> impl Iterator<Item = usize> + 'a {
+    let is_identifier_char =
+        |character: char| character.is_alphanumeric() || "_$".contains(character);
+
+    text.match_indices(identifier)
+        .map(|(index, _)| index)
+        .filter(move |&index| {
+            let (before, after) = (&text[..index], &text[index + identifier.len()..]);
+            !before
+                .chars()
+                .next_back()
+                .is_some_and(|character| is_identifier_char(character) || character == '.')
+                && !after.chars().next().is_some_and(is_identifier_char)
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::flatten_zod_schema;
+    use crate::{flatten_zod_schema, HoistedKind};
     use std::fs;
     use tempfile::TempDir;
 
@@ -485,6 +595,47 @@ mod tests {
         assert!(result.contains("z.string()"));
     }
 
+    #[test]
+    fn test_flatten_hoists_lazy_recursive_schema() {
+        let temporary_directory = create_temporary_project();
+        let base = temporary_directory.path();
+
+        let main_path = write_file(
+            base,
+            "main.ts",
+            r"
+            import { z } from 'zod';
+            const nodeSchema = z.object({ children: z.lazy(() => z.array(nodeSchema)) });
+            const schema = z.object({ root: nodeSchema });
+        ",
+        );
+
+        let parser = TsParser::new();
+        let parsed = parser.parse_file(&main_path).expect("Failed to parse");
+
+        let mut flattener = ZodFlattener::new(&parser, base);
+        let result = flattener
+            .flatten_schema("schema", &parsed)
+            .expect("Failed to flatten");
+
+        let [declaration] = flattener.hoisted_declarations() else {
+            panic!("Expected one hoisted declaration");
+        };
+        assert_eq!(declaration.kind, HoistedKind::RecursiveSchema);
+        assert_eq!(declaration.name, "nodeSchema_flat");
+        assert_eq!(
+            result,
+            format!("z.object({{ root: {} }})", declaration.placeholder)
+        );
+        assert_eq!(
+            declaration.declaration,
+            format!(
+                "z.object({{ children: z.lazy(() => z.array({})) }})",
+                declaration.placeholder
+            )
+        );
+    }
+
     #[test]
     fn test_flatten_unknown_identifier() {
         let temporary_directory = create_temporary_project();
@@ -611,4 +762,147 @@ mod tests {
         assert!(result.contains("active"));
         assert!(result.contains("inactive"));
     }
+
+    #[test]
+    fn test_flatten_inlines_local_arrow_factory() {
+        let temporary_directory = create_temporary_project();
+        let base = temporary_directory.path();
+
+        let main_path = write_file(
+            base,
+            "main.ts",
+            r"
+            import { z } from 'zod';
+            const userSchema = z.object({ name: z.string() });
+            const paginated = (item, limit = 20) =>
+                z.object({ items: z.array(item), limit: z.literal(limit) });
+        ",
+        );
+
+        let parser = TsParser::new();
+        let parsed = parser.parse_file(&main_path).expect("Failed to parse");
+
+        let mut flattener = ZodFlattener::new(&parser, base);
+        let result = flattener
+            .flatten_schema("paginated(userSchema)", &parsed)
+            .expect("Failed to flatten");
+
+        assert_eq!(
+            result,
+            "z.object({ items: z.array(z.object({ name: z.string() })), limit: z.literal(20) })"
+        );
+    }
+
+    #[test]
+    fn test_flatten_inlines_function_declaration_with_shorthand_property() {
+        let temporary_directory = create_temporary_project();
+        let base = temporary_directory.path();
+
+        let main_path = write_file(
+            base,
+            "main.ts",
+            r"
+            import { z } from 'zod';
+            function withTimestamps(data) {
+                return z.object({ data, createdAt: z.date() });
+            }
+        ",
+        );
+
+        let parser = TsParser::new();
+        let parsed = parser.parse_file(&main_path).expect("Failed to parse");
+
+        let mut flattener = ZodFlattener::new(&parser, base);
+        let result = flattener
+            .flatten_schema("withTimestamps(z.string())", &parsed)
+            .expect("Failed to flatten");
+
+        assert_eq!(
+            result,
+            "z.object({ data: z.string(), createdAt: z.date() })"
+        );
+    }
+
+    #[test]
+    fn test_flatten_substitutes_parameters_in_block_body_callback() {
+        let temporary_directory = create_temporary_project();
+        let base = temporary_directory.path();
+
+        let main_path = write_file(
+            base,
+            "main.ts",
+            r"
+            import { z } from 'zod';
+            const bounded = (max) =>
+                z.string().refine((value) => {
+                    return value.length <= max;
+                });
+        ",
+        );
+
+        let parser = TsParser::new();
+        let parsed = parser.parse_file(&main_path).expect("Failed to parse");
+
+        let mut flattener = ZodFlattener::new(&parser, base);
+        let result = flattener
+            .flatten_schema("bounded(10)", &parsed)
+            .expect("Failed to flatten");
+
+        assert!(result.contains("value.length <= 10"), "{result}");
+        assert!(!result.contains("max"), "{result}");
+    }
+
+    #[test]
+    fn test_flatten_leaves_factory_with_multi_statement_callback() {
+        let temporary_directory = create_temporary_project();
+        let base = temporary_directory.path();
+
+        let main_path = write_file(
+            base,
+            "main.ts",
+            r"
+            import { z } from 'zod';
+            const bounded = (max) =>
+                z.string().refine((value) => {
+                    const length = value.length;
+                    return length <= max;
+                });
+        ",
+        );
+
+        let parser = TsParser::new();
+        let parsed = parser.parse_file(&main_path).expect("Failed to parse");
+
+        let mut flattener = ZodFlattener::new(&parser, base);
+        let result = flattener
+            .flatten_schema("bounded(10)", &parsed)
+            .expect("Failed to flatten");
+
+        assert_eq!(result, "bounded(10)");
+    }
+
+    #[test]
+    fn test_flatten_stops_on_recursive_factory() {
+        let temporary_directory = create_temporary_project();
+        let base = temporary_directory.path();
+
+        let main_path = write_file(
+            base,
+            "main.ts",
+            r"
+            import { z } from 'zod';
+            const nested = (item) => z.array(nested(item));
+        ",
+        );
+
+        let parser = TsParser::new();
+        let parsed = parser.parse_file(&main_path).expect("Failed to parse");
+
+        let mut flattener = ZodFlattener::new(&parser, base);
+        let result = flattener
+            .flatten_schema("nested(z.string())", &parsed)
+            .expect("Failed to flatten");
+
+        assert_eq!(result, "z.array(nested(z.string()))");
+    }
 }
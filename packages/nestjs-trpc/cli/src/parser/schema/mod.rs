@@ -1,5 +1,6 @@
 mod flatten;
 mod helpers;
+pub mod validator;
 
 use crate::error::GeneratorError;
 use crate::parser::{ParsedFile, TsParser};
@@ -7,6 +8,11 @@ use std::collections::HashSet;
 use std::path::Path;
 
 pub use flatten::ZodFlattener;
+pub(crate) use flatten::{count_identifier, replace_identifier};
+pub use validator::{
+    check_zod_entry_points, detect_validator_imports, merge_validator_bindings, ValidatorImport,
+    ValidatorImportKind, ZodVersion,
+};
 
 pub type ZodResult<T> = std::result::Result<T, GeneratorError>;
 
@@ -0,0 +1,573 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use swc_ecma_ast::{
+    Expr, ImportDecl, ImportSpecifier, MemberExpr, ModuleDecl, ModuleExportName, ModuleItem,
+};
+
+use crate::error::GeneratorError;
+use crate::generator::GeneratorResult;
+use crate::parser::ParsedFile;
+
+const ZOD_PACKAGE: &str = "zod";
+
+/// Packages whose imports build schemas rather than being schemas themselves. Subpaths such as
+/// `zod/v4` count as the package.
+pub const VALIDATOR_PACKAGES: &[&str] = &[
+    "zod",
+    "valibot",
+    "arktype",
+    "yup",
+    "superstruct",
+    "@sinclair/typebox",
+    "@effect/schema",
+    "runtypes",
+];
+
+/// How a binding was imported from a validator package.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ValidatorImportKind {
+    /// `import { imported as local } from '...'`, holding the exported name.
+    Named(String),
+    /// `import * as local from '...'`.
+    Namespace,
+    /// `import local from '...'`.
+    Default,
+}
+
+/// A binding imported from a validator package, such as `z` from `zod` or `v` from `valibot`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ValidatorImport {
+    pub local_name: String,
+    pub kind: ValidatorImportKind,
+    pub module_specifier: String,
+}
+
+impl ValidatorImport {
+    /// `import { z } from 'zod'`, assumed for schemas that use `z` without a detected import.
+    #[must_use]
+    pub fn zod() -> Self {
+        Self {
+            local_name: "z".to_string(),
+            kind: ValidatorImportKind::Named("z".to_string()),
+            module_specifier: "zod".to_string(),
+        }
+    }
+
+    /// The type a schema with the given output type is annotated with through this binding, e.g.
+    /// `z.ZodType<Category>`, when it is Zod's namespace rather than a single export like `string`.
+    ///
+    /// A differing `input` type goes where the `installed` release expects it: after `ZodTypeDef`
+    /// in Zod 3, and second in Zod 4. `None` when that release isn't known.
+    #[must_use]
+    pub fn zod_schema_type(
+        &self,
+        output: &str,
+        input: Option<&str>,
+        installed: Option<ZodVersion>,
+    ) -> Option<String> {
+        if !self.is_zod_namespace() {
+            return None;
+        }
+        let api = ZodApi::of(&self.module_specifier, installed)?;
+        let namespace = &self.local_name;
+        let type_name = if api.mini { "ZodMiniType" } else { "ZodType" };
+        let arguments = match (input, api.major) {
+            (None, _) => output.to_string(),
+            (Some(input), Some(3)) => format!("{output}, {namespace}.ZodTypeDef, {input}"),
+            (Some(input), Some(_)) => format!("{output}, {input}"),
+            (Some(_), None) => return None,
+        };
+        Some(format!("{namespace}.{type_name}<{arguments}>"))
+    }
+
+    /// `import { z } from 'zod'` or `import * as z from 'zod'`, which bind the same namespace.
+    fn is_zod_namespace(&self) -> bool {
+        let is_namespace = match &self.kind {
+            ValidatorImportKind::Named(name) => name == "z",
+            ValidatorImportKind::Namespace => true,
+            ValidatorImportKind::Default => false,
+        };
+        is_namespace && ZodApi::of(&self.module_specifier, None).is_some()
+    }
+
+    /// The validator package the binding comes from, e.g. `zod` for `zod/v4`.
+    fn package(&self) -> Option<&'static str> {
+        VALIDATOR_PACKAGES
+            .iter()
+            .copied()
+            .find(|package| is_validator_package_of(&self.module_specifier, package))
+    }
+
+    /// Whether `other` binds the same name to another package or export. Zod entry points are
+    /// checked by [`check_zod_entry_points`] instead.
+    fn conflicts_with(&self, other: &Self) -> bool {
+        let same_export =
+            self.kind == other.kind || (self.is_zod_namespace() && other.is_zod_namespace());
+        self.local_name == other.local_name && (self.package() != other.package() || !same_export)
+    }
+}
+
+#[must_use]
+pub fn is_validator_package(module_specifier: &str) -> bool {
+    VALIDATOR_PACKAGES
+        .iter()
+        .any(|package| is_validator_package_of(module_specifier, package))
+}
+
+fn is_validator_package_of(module_specifier: &str, package: &str) -> bool {
+    module_specifier
+        .strip_prefix(package)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// The value imports of `parsed_file` that come from validator packages, in source order.
+#[must_use]
+pub fn detect_validator_imports(parsed_file: &ParsedFile) -> Vec<ValidatorImport> {
+    parsed_file
+        .module
+        .body
+        .iter()
+        .filter_map(|item| match item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(import_declaration))
+                if !import_declaration.type_only =>
+            {
+                Some(import_declaration)
+            }
+            _ => None,
+        })
+        .filter(|import_declaration| {
+            is_validator_package(&import_declaration.src.value.to_string_lossy())
+        })
+        .flat_map(validator_imports_of)
+        .collect()
+}
+
+fn validator_imports_of(import_declaration: &ImportDecl) -> Vec<ValidatorImport> {
+    let module_specifier = import_declaration.src.value.to_string_lossy().into_owned();
+
+    import_declaration
+        .specifiers
+        .iter()
+        .filter_map(|specifier| {
+            let kind = match specifier {
+                ImportSpecifier::Named(named) if named.is_type_only => return None,
+                ImportSpecifier::Named(named) => ValidatorImportKind::Named(
+                    named
+                        .imported
+                        .as_ref()
+                        .map_or_else(|| named.local.sym.to_string(), export_name),
+                ),
+                ImportSpecifier::Namespace(_) => ValidatorImportKind::Namespace,
+                ImportSpecifier::Default(_) => ValidatorImportKind::Default,
+            };
+            Some(ValidatorImport {
+                local_name: specifier.local().sym.to_string(),
+                kind,
+                module_specifier: module_specifier.clone(),
+            })
+        })
+        .collect()
+}
+
+fn export_name(name: &ModuleExportName) -> String {
+    match name {
+        ModuleExportName::Ident(identifier) => identifier.sym.to_string(),
+        ModuleExportName::Str(string) => string.value.to_string_lossy().into_owned(),
+    }
+}
+
+/// The names `parsed_file` imports from validator packages, e.g. `z` or `v`.
+#[must_use]
+pub fn validator_bindings(parsed_file: &ParsedFile) -> HashSet<String> {
+    detect_validator_imports(parsed_file)
+        .into_iter()
+        .map(|validator_import| validator_import.local_name)
+        .collect()
+}
+
+/// One import per validator binding in `validator_imports`, keyed by importing file.
+///
+/// The first import of a name is kept, except that Zod entry points of the same API resolve to
+/// the one matching the `installed` release. Fails when two files bind one name to different
+/// packages or exports, since `server.ts` declares each binding once.
+pub fn merge_validator_bindings(
+    validator_imports: Vec<(PathBuf, ValidatorImport)>,
+    installed: Option<ZodVersion>,
+) -> GeneratorResult<Vec<ValidatorImport>> {
+    let mut merged: Vec<(PathBuf, ValidatorImport)> = Vec::new();
+    for (path, validator_import) in validator_imports {
+        let existing = merged
+            .iter_mut()
+            .find(|(_, existing)| existing.local_name == validator_import.local_name);
+        match existing {
+            Some((first_path, first)) if first.conflicts_with(&validator_import) => {
+                return Err(GeneratorError::ConflictingValidatorImports {
+                    name: validator_import.local_name,
+                    first_path: first_path.clone(),
+                    first_module: first.module_specifier.clone(),
+                    second_path: path,
+                    second_module: validator_import.module_specifier,
+                });
+            }
+            Some((_, first))
+                if prefers_entry_point(
+                    &validator_import.module_specifier,
+                    &first.module_specifier,
+                    installed,
+                ) =>
+            {
+                *first = validator_import;
+            }
+            Some(_) => {}
+            None => merged.push((path, validator_import)),
+        }
+    }
+    Ok(merged
+        .into_iter()
+        .map(|(_, validator_import)| validator_import)
+        .collect())
+}
+
+/// The identifier a call or member chain starts from: `v` for `v.pipe(v.string()).optional`.
+#[must_use]
+pub fn expression_root(expression: &Expr) -> Option<&str> {
+    match expression.unwrap_parens() {
+        Expr::Ident(identifier) => Some(identifier.sym.as_ref()),
+        Expr::Member(MemberExpr { obj, .. }) => expression_root(obj),
+        Expr::Call(call_expression) => call_expression
+            .callee
+            .as_expr()
+            .and_then(|callee| expression_root(callee)),
+        _ => None,
+    }
+}
+
+/// The `zod` release installed for a project.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ZodVersion {
+    pub major: u64,
+    pub minor: u64,
+}
+
+impl ZodVersion {
+    /// Reads `node_modules/zod/package.json` in the closest ancestor of `base_directory` that has one.
+    #[must_use]
+    pub fn detect(base_directory: &Path) -> Option<Self> {
+        let manifest_path = base_directory
+            .ancestors()
+            .map(|directory| directory.join("node_modules/zod/package.json"))
+            .find(|candidate| candidate.is_file())?;
+        let manifest: Value =
+            serde_json::from_str(&fs::read_to_string(manifest_path).ok()?).ok()?;
+        Self::parse(manifest.get("version")?.as_str()?)
+    }
+
+    fn parse(version: &str) -> Option<Self> {
+        let mut parts = version.split(['.', '-']);
+        Some(Self {
+            major: parts.next()?.parse().ok()?,
+            minor: parts.next()?.parse().ok()?,
+        })
+    }
+
+    /// Whether this release ships `entry_point`; the `zod/v4` and `zod/mini` subpaths arrived in 3.25.
+    #[must_use]
+    pub fn provides(self, entry_point: &str) -> bool {
+        entry_point == ZOD_PACKAGE || self.major >= 4 || (self.major == 3 && self.minor >= 25)
+    }
+}
+
+impl std::fmt::Display for ZodVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// The Zod API an entry point exposes: its major version, when known, and whether it is Zod Mini.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ZodApi {
+    major: Option<u64>,
+    mini: bool,
+}
+
+impl ZodApi {
+    fn of(entry_point: &str, installed: Option<ZodVersion>) -> Option<Self> {
+        let (major, mini) = match entry_point {
+            "zod" => (installed.map(|version| version.major), false),
+            "zod/v3" => (Some(3), false),
+            "zod/v4" => (Some(4), false),
+            "zod/mini" | "zod/v4-mini" | "zod/v4/mini" => (Some(4), true),
+            _ => return None,
+        };
+        Some(Self { major, mini })
+    }
+
+    fn is_compatible_with(self, other: Self) -> bool {
+        self.mini == other.mini
+            && self
+                .major
+                .zip(other.major)
+                .map_or(true, |(major, other_major)| major == other_major)
+    }
+}
+
+/// Whether `server.ts` should import Zod from `entry_point` rather than the compatible `other`:
+/// bare `zod` when the installed release is known, since it then is that API, and otherwise the
+/// entry point that names its major.
+fn prefers_entry_point(entry_point: &str, other: &str, installed: Option<ZodVersion>) -> bool {
+    let is_bare = |specifier: &str| specifier == ZOD_PACKAGE;
+    let is_zod = |specifier: &str| ZodApi::of(specifier, installed).is_some();
+    if !is_zod(entry_point) || !is_zod(other) || is_bare(entry_point) == is_bare(other) {
+        return false;
+    }
+    is_bare(entry_point) == installed.is_some()
+}
+
+/// Fails when `validator_imports`, keyed by importing file, bind Zod to different APIs.
+///
+/// `zod/v3` and `zod/v4` conflict, as do `zod` and `zod/mini`; a bare `zod` import matches
+/// whichever major is `installed`. Entry points the installed release doesn't ship also fail.
+pub fn check_zod_entry_points(
+    validator_imports: &[(PathBuf, ValidatorImport)],
+    installed: Option<ZodVersion>,
+) -> GeneratorResult<()> {
+    let zod_imports: Vec<(&PathBuf, &str, ZodApi)> = validator_imports
+        .iter()
+        .filter_map(|(path, validator_import)| {
+            let entry_point = validator_import.module_specifier.as_str();
+            ZodApi::of(entry_point, installed).map(|api| (path, entry_point, api))
+        })
+        .collect();
+
+    let unsupported = installed.and_then(|version| {
+        zod_imports
+            .iter()
+            .find(|(_, entry_point, _)| !version.provides(entry_point))
+            .map(
+                |&(path, entry_point, _)| GeneratorError::UnsupportedZodEntryPoint {
+                    path: path.clone(),
+                    entry_point: entry_point.to_string(),
+                    version: version.to_string(),
+                },
+            )
+    });
+    let mixed = zod_imports.iter().enumerate().find_map(|(index, first)| {
+        zod_imports[index + 1..]
+            .iter()
+            .find(|second| !second.2.is_compatible_with(first.2))
+            .map(|second| GeneratorError::MixedZodEntryPoints {
+                first_path: first.0.clone(),
+                first_entry_point: first.1.to_string(),
+                second_path: second.0.clone(),
+                second_entry_point: second.1.to_string(),
+            })
+    });
+
+    unsupported.or(mixed).map_or(Ok(()), Err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::TsParser;
+
+    fn detect(source: &str) -> Vec<ValidatorImport> {
+        let parsed = TsParser::new()
+            .parse_source("users.router.ts", source)
+            .unwrap();
+        detect_validator_imports(&parsed)
+    }
+
+    #[test]
+    fn test_detects_named_namespace_and_default_imports() {
+        let imports = detect(
+            r"
+            import * as v from 'valibot';
+            import { type as arkType } from 'arktype';
+            import yup from 'yup';
+            import { z } from 'zod/v4';
+            import type { Infer } from 'superstruct';
+            import { Injectable } from '@nestjs/common';
+            ",
+        );
+
+        let summary: Vec<(&str, &ValidatorImportKind, &str)> = imports
+            .iter()
+            .map(|validator_import| {
+                (
+                    validator_import.local_name.as_str(),
+                    &validator_import.kind,
+                    validator_import.module_specifier.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("v", &ValidatorImportKind::Namespace, "valibot"),
+                (
+                    "arkType",
+                    &ValidatorImportKind::Named("type".to_string()),
+                    "arktype"
+                ),
+                ("yup", &ValidatorImportKind::Default, "yup"),
+                ("z", &ValidatorImportKind::Named("z".to_string()), "zod/v4"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_schema_type_places_the_input_type_for_the_release() {
+        let zod = ValidatorImport::zod();
+        let zod_3 = Some(ZodVersion {
+            major: 3,
+            minor: 23,
+        });
+        let zod_4 = Some(ZodVersion { major: 4, minor: 1 });
+
+        assert_eq!(
+            zod.zod_schema_type("Tree", None, None).as_deref(),
+            Some("z.ZodType<Tree>")
+        );
+        assert_eq!(
+            zod.zod_schema_type("Tree", Some("TreeInput"), zod_3)
+                .as_deref(),
+            Some("z.ZodType<Tree, z.ZodTypeDef, TreeInput>")
+        );
+        assert_eq!(
+            zod.zod_schema_type("Tree", Some("TreeInput"), zod_4)
+                .as_deref(),
+            Some("z.ZodType<Tree, TreeInput>")
+        );
+        assert_eq!(zod.zod_schema_type("Tree", Some("TreeInput"), None), None);
+
+        let mini = ValidatorImport {
+            module_specifier: "zod/mini".to_string(),
+            ..ValidatorImport::zod()
+        };
+        assert_eq!(
+            mini.zod_schema_type("Tree", Some("TreeInput"), None)
+                .as_deref(),
+            Some("z.ZodMiniType<Tree, TreeInput>")
+        );
+    }
+
+    #[test]
+    fn test_package_matching_requires_a_path_boundary() {
+        assert!(is_validator_package("zod"));
+        assert!(is_validator_package("zod/mini"));
+        assert!(!is_validator_package("zod-to-json-schema"));
+        assert!(!is_validator_package("./zod"));
+    }
+
+    fn zod_imports(entry_points: &[&str]) -> Vec<(PathBuf, ValidatorImport)> {
+        entry_points
+            .iter()
+            .enumerate()
+            .map(|(index, entry_point)| {
+                let validator_import = ValidatorImport {
+                    module_specifier: (*entry_point).to_string(),
+                    ..ValidatorImport::zod()
+                };
+                (
+                    PathBuf::from(format!("{index}.router.ts")),
+                    validator_import,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_merging_keeps_the_first_of_equivalent_bindings() {
+        let namespace = ValidatorImport {
+            kind: ValidatorImportKind::Namespace,
+            ..ValidatorImport::zod()
+        };
+        let merged = merge_validator_bindings(
+            vec![
+                (PathBuf::from("users.router.ts"), ValidatorImport::zod()),
+                (PathBuf::from("users.schema.ts"), namespace),
+            ],
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(merged, vec![ValidatorImport::zod()]);
+    }
+
+    #[test]
+    fn test_merging_rejects_a_name_bound_to_different_imports() {
+        let valibot = ValidatorImport {
+            local_name: "z".to_string(),
+            kind: ValidatorImportKind::Namespace,
+            module_specifier: "valibot".to_string(),
+        };
+        let merged = merge_validator_bindings(
+            vec![
+                (PathBuf::from("users.router.ts"), ValidatorImport::zod()),
+                (PathBuf::from("posts.schema.ts"), valibot),
+            ],
+            None,
+        );
+
+        assert!(matches!(
+            merged,
+            Err(GeneratorError::ConflictingValidatorImports { ref name, ref second_module, .. })
+                if name == "z" && second_module == "valibot"
+        ));
+    }
+
+    #[test]
+    fn test_merging_picks_the_entry_point_for_the_installed_release() {
+        let merge = |installed| {
+            merge_validator_bindings(zod_imports(&["zod/v4", "zod"]), installed)
+                .unwrap()
+                .remove(0)
+                .module_specifier
+        };
+
+        assert_eq!(merge(ZodVersion::parse("4.1.5")), "zod");
+        assert_eq!(merge(None), "zod/v4");
+    }
+
+    #[test]
+    fn test_bare_zod_matches_the_installed_major() {
+        let zod_4 = ZodVersion::parse("4.1.5");
+        let zod_3 = ZodVersion::parse("3.25.76");
+
+        assert!(check_zod_entry_points(&zod_imports(&["zod", "zod/v4"]), zod_4).is_ok());
+        assert!(check_zod_entry_points(&zod_imports(&["zod", "zod/v3"]), zod_3).is_ok());
+        assert!(check_zod_entry_points(&zod_imports(&["zod", "zod/v4"]), None).is_ok());
+        assert!(matches!(
+            check_zod_entry_points(&zod_imports(&["zod", "zod/v4"]), zod_3),
+            Err(GeneratorError::MixedZodEntryPoints { ref second_entry_point, .. })
+                if second_entry_point == "zod/v4"
+        ));
+    }
+
+    #[test]
+    fn test_rejects_mixed_apis_and_missing_entry_points() {
+        let mixed_mini = check_zod_entry_points(&zod_imports(&["zod/v4", "zod/mini"]), None);
+        let mixed_majors = check_zod_entry_points(&zod_imports(&["zod", "zod/v3", "zod/v4"]), None);
+        let too_old =
+            check_zod_entry_points(&zod_imports(&["zod/v4"]), ZodVersion::parse("3.22.4"));
+
+        assert!(matches!(
+            mixed_mini,
+            Err(GeneratorError::MixedZodEntryPoints { .. })
+        ));
+        assert!(matches!(
+            mixed_majors,
+            Err(GeneratorError::MixedZodEntryPoints { ref first_entry_point, .. })
+                if first_entry_point == "zod/v3"
+        ));
+        assert!(matches!(
+            too_old,
+            Err(GeneratorError::UnsupportedZodEntryPoint { ref version, .. }) if version == "3.22"
+        ));
+    }
+}
@@ -79,6 +79,13 @@ impl FileScanner {
         Some(path.to_path_buf())
     }
 
+    /// Returns whether `path` would be picked up by [`Self::scan`] with `pattern`.
+    pub fn matches(&self, path: &Path, pattern: &str) -> ScannerResult<bool> {
+        let matcher = Self::compile_pattern(pattern)?;
+        let relative_path = path.strip_prefix(&self.base_dir).unwrap_or(path);
+        Ok(matcher.is_match(relative_path))
+    }
+
     pub fn scan_absolute(&self, pattern: &str) -> ScannerResult<Vec<PathBuf>> {
         let files = self.scan(pattern)?;
 
@@ -223,6 +230,18 @@ mod tests {
         assert_eq!(file_name, "auth.router.ts");
     }
 
+    #[test]
+    fn test_matches_relative_to_base_directory() {
+        let temp_dir = setup_test_dir();
+        let scanner = FileScanner::new(temp_dir.path()).unwrap();
+
+        let router = temp_dir.path().join("src/routers/auth.router.ts");
+        let service = temp_dir.path().join("src/services/user.service.ts");
+
+        assert!(scanner.matches(&router, "src/routers/*.router.ts").unwrap());
+        assert!(!scanner.matches(&service, "**/*.router.ts").unwrap());
+    }
+
     #[test]
     fn test_scan_skips_hidden_directories() {
         let temp_dir = setup_test_dir();
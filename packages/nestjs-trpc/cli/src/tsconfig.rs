@@ -6,12 +6,22 @@ use jsonc_parser::{parse_to_serde_value, ParseOptions};
 use serde_json::{Map, Value};
 use tracing::debug;
 
+use crate::parser::imports::module_path::normalize_path;
+
+/// Compiler option holding the directory `paths` are resolved against when
+/// `baseUrl` is unset; named after the equivalent internal TypeScript option.
+pub const PATHS_BASE_PATH_KEY: &str = "pathsBasePath";
+
 /// Resolves `compilerOptions` from the nearest `tsconfig.json`, following
 /// the full `extends` chain exactly like TypeScript.
 ///
 /// Walks upward from `start_directory` through ancestor directories until a
 /// `tsconfig.json` is found, mirroring the way `tsc` discovers its config.
 ///
+/// `baseUrl` is made absolute relative to the config that declares it, and the
+/// directory of the config declaring `paths` is recorded under
+/// [`PATHS_BASE_PATH_KEY`], so aliases survive merging across the chain.
+///
 /// Returns `None` if no `tsconfig.json` exists anywhere in the tree or it
 /// cannot be parsed.
 #[must_use]
@@ -28,6 +38,30 @@ pub fn resolve_compiler_options(start_directory: &Path) -> Option<Map<String, Va
     None
 }
 
+/// Every file [`resolve_compiler_options`] reads or looks for from `start_directory`.
+///
+/// That is the missing `tsconfig.json` candidates below the nearest one, any of which would take
+/// over if created, followed by the nearest one and every config it extends.
+#[must_use]
+pub fn tsconfig_files(start_directory: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for directory in start_directory.ancestors() {
+        let tsconfig_path = directory.join("tsconfig.json");
+        if !tsconfig_path.is_file() {
+            files.push(tsconfig_path);
+            continue;
+        }
+
+        let mut seen = HashSet::new();
+        resolve_tsconfig_internal(&tsconfig_path, &mut seen);
+        let mut chain: Vec<PathBuf> = seen.into_iter().collect();
+        chain.sort();
+        files.extend(chain);
+        break;
+    }
+    files
+}
+
 fn resolve_tsconfig_internal(
     tsconfig_path: &Path,
     seen: &mut HashSet<PathBuf>,
@@ -49,11 +83,12 @@ fn resolve_tsconfig_internal(
     let obj = parsed.as_object()?;
     let tsconfig_directory = canonical.parent()?;
 
-    let child_options = obj
+    let mut child_options = obj
         .get("compilerOptions")
         .and_then(|value| value.as_object())
         .cloned()
         .unwrap_or_default();
+    anchor_path_options(&mut child_options, tsconfig_directory);
 
     let extends_value = match obj.get("extends") {
         Some(Value::String(string)) => Some(string.as_str().to_owned()),
@@ -83,6 +118,23 @@ fn resolve_tsconfig_internal(
     Some(merge_compiler_options(options, child_options))
 }
 
+fn anchor_path_options(options: &mut Map<String, Value>, tsconfig_directory: &Path) {
+    if let Some(base_url) = options.get("baseUrl").and_then(Value::as_str) {
+        let anchored = normalize_path(&tsconfig_directory.join(base_url));
+        options.insert(
+            "baseUrl".to_string(),
+            Value::String(anchored.to_string_lossy().into_owned()),
+        );
+    }
+
+    if options.contains_key("paths") {
+        options.insert(
+            PATHS_BASE_PATH_KEY.to_string(),
+            Value::String(tsconfig_directory.to_string_lossy().into_owned()),
+        );
+    }
+}
+
 fn merge_extends_array(
     array: &[Value],
     tsconfig_directory: &Path,
@@ -144,7 +196,7 @@ fn try_resolve_path(base_directory: &Path, path: &Path) -> Option<PathBuf> {
     None
 }
 
-fn split_package_reference(extends: &str) -> (String, String) {
+pub(crate) fn split_package_reference(extends: &str) -> (String, String) {
     if let Some(stripped) = extends.strip_prefix('@') {
         return split_scoped_package(stripped, extends);
     }
@@ -660,6 +712,59 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_base_url_anchored_to_declaring_config() {
+        let temporary_directory = TempDir::new().unwrap();
+        let root = temporary_directory.path().canonicalize().unwrap();
+        let app = root.join("apps/api");
+        fs::create_dir_all(&app).unwrap();
+
+        fs::write(
+            root.join("tsconfig.base.json"),
+            r#"{ "compilerOptions": { "baseUrl": "./", "paths": { "@lib/*": ["libs/*"] } } }"#,
+        )
+        .unwrap();
+        fs::write(
+            app.join("tsconfig.json"),
+            r#"{ "extends": "../../tsconfig.base.json" }"#,
+        )
+        .unwrap();
+
+        let result = resolve_compiler_options(&app).unwrap();
+        assert_eq!(
+            result.get("baseUrl").and_then(|v| v.as_str()),
+            Some(root.to_string_lossy().as_ref())
+        );
+        assert_eq!(
+            result.get(PATHS_BASE_PATH_KEY).and_then(|v| v.as_str()),
+            Some(root.to_string_lossy().as_ref())
+        );
+    }
+
+    #[test]
+    fn test_tsconfig_files_cover_missing_candidates_and_extends_chain() {
+        let temporary_directory = TempDir::new().unwrap();
+        let root = temporary_directory.path().canonicalize().unwrap();
+        let app = root.join("apps/api");
+        fs::create_dir_all(app.join("src")).unwrap();
+
+        fs::write(root.join("tsconfig.base.json"), "{}").unwrap();
+        fs::write(
+            app.join("tsconfig.json"),
+            r#"{ "extends": "../../tsconfig.base.json" }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            tsconfig_files(&app.join("src")),
+            [
+                app.join("src/tsconfig.json"),
+                app.join("tsconfig.json"),
+                root.join("tsconfig.base.json"),
+            ]
+        );
+    }
+
     #[test]
     fn test_empty_tsconfig() {
         let temporary_directory = TempDir::new().unwrap();
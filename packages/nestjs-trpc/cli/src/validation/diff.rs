@@ -14,9 +14,27 @@ const DIFF_CONTEXT_LINES: usize = 3;
 
 #[must_use]
 pub fn compute_diff(old_content: Option<&str>, new_content: &str, file_path: &str) -> DiffResult {
-    let old = old_content.unwrap_or("");
+    let old_header = if old_content.is_some() {
+        format!("a/{file_path}")
+    } else {
+        "/dev/null".to_string()
+    };
+    diff_contents(
+        old_content.unwrap_or(""),
+        new_content,
+        &old_header,
+        &format!("b/{file_path}"),
+    )
+}
 
-    if old == new_content {
+/// Diff of a file that generation would delete.
+#[must_use]
+pub fn compute_removal_diff(old_content: &str, file_path: &str) -> DiffResult {
+    diff_contents(old_content, "", &format!("a/{file_path}"), "/dev/null")
+}
+
+fn diff_contents(old: &str, new: &str, old_header: &str, new_header: &str) -> DiffResult {
+    if old == new {
         return DiffResult {
             has_changes: false,
             lines_added: 0,
@@ -25,7 +43,7 @@ pub fn compute_diff(old_content: Option<&str>, new_content: &str, file_path: &st
         };
     }
 
-    let text_diff = TextDiff::from_lines(old, new_content);
+    let text_diff = TextDiff::from_lines(old, new);
 
     let mut lines_added = 0;
     let mut lines_removed = 0;
@@ -38,17 +56,10 @@ pub fn compute_diff(old_content: Option<&str>, new_content: &str, file_path: &st
         }
     }
 
-    let old_header = if old_content.is_some() {
-        format!("a/{file_path}")
-    } else {
-        "/dev/null".to_string()
-    };
-    let new_header = format!("b/{file_path}");
-
     let unified_diff = text_diff
         .unified_diff()
         .context_radius(DIFF_CONTEXT_LINES)
-        .header(&old_header, &new_header)
+        .header(old_header, new_header)
         .to_string();
 
     DiffResult {
@@ -107,6 +118,17 @@ mod tests {
         assert!(result.unified_diff.contains("b/src/router.ts"));
     }
 
+    #[test]
+    fn removal_diff_deletes_every_line() {
+        let result = compute_removal_diff("line 1\nline 2\n", "types.ts");
+
+        assert!(result.has_changes);
+        assert_eq!(result.lines_added, 0);
+        assert_eq!(result.lines_removed, 2);
+        assert!(result.unified_diff.contains("a/types.ts"));
+        assert!(result.unified_diff.contains("/dev/null"));
+    }
+
     #[test]
     fn handles_empty_old_content() {
         let new_content = "first line\n";
@@ -1,5 +1,5 @@
 mod diff;
 mod tsc;
 
-pub use diff::{compute_diff, DiffResult};
+pub use diff::{compute_diff, compute_removal_diff, DiffResult};
 pub use tsc::{find_tsc, run_tsc_validation, TscError, TscResult, TscSeverity};
@@ -4,9 +4,11 @@ pub mod progress;
 
 use anyhow::Result;
 use console::Term;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 use crate::parser::module::TransformerInfo;
+use crate::{ProcedureDecoratorAlias, UntypedOutput};
 
 pub use event_loop::*;
 pub use paths::{find_watchable_files, should_watch_path};
@@ -37,6 +39,18 @@ pub struct WatchConfig {
 
     /// Add .js extension to local import paths for ESM compatibility
     pub import_extension: bool,
+
+    /// Module containing `TRPCModule.forRoot()`, used to generate `types.ts`
+    pub root_module: Option<PathBuf>,
+
+    /// Custom procedure decorators from the config file
+    pub procedure_decorators: BTreeMap<String, ProcedureDecoratorAlias>,
+
+    /// How outputs are typed when they have no schema and can't be inferred from their router
+    pub untyped_output: UntypedOutput,
+
+    /// Type of procedure `meta` from the config file
+    pub meta_type: Option<String>,
 }
 
 impl WatchConfig {
@@ -55,6 +69,10 @@ impl WatchConfig {
             verbose: false,
             transformer: None,
             import_extension: false,
+            root_module: None,
+            procedure_decorators: BTreeMap::new(),
+            untyped_output: UntypedOutput::Any,
+            meta_type: None,
         }
     }
 
@@ -85,6 +103,51 @@ impl WatchConfig {
         self.import_extension = import_extension;
         self
     }
+
+    /// Sets the root module used to resolve context and global middlewares.
+    #[must_use]
+    pub fn with_root_module(mut self, root_module: Option<PathBuf>) -> Self {
+        self.root_module = root_module;
+        self
+    }
+
+    /// Sets the custom procedure decorators recognised alongside the built-in ones.
+    #[must_use]
+    pub fn with_procedure_decorators(
+        mut self,
+        procedure_decorators: BTreeMap<String, ProcedureDecoratorAlias>,
+    ) -> Self {
+        self.procedure_decorators = procedure_decorators;
+        self
+    }
+
+    /// Sets how outputs that can't be typed are generated, or whether they fail generation.
+    #[must_use]
+    pub const fn with_untyped_output(mut self, untyped_output: UntypedOutput) -> Self {
+        self.untyped_output = untyped_output;
+        self
+    }
+
+    /// Sets the type of procedure `meta`, instead of the default.
+    #[must_use]
+    pub fn with_meta_type(mut self, meta_type: Option<String>) -> Self {
+        self.meta_type = meta_type;
+        self
+    }
+
+    fn generation_options(&self) -> crate::GenerationOptions {
+        crate::GenerationOptions::new(
+            &self.base_directory,
+            &self.output_directory,
+            &self.router_pattern,
+        )
+        .with_transformer(self.transformer.clone())
+        .with_import_extension(self.import_extension)
+        .with_root_module(self.root_module.clone())
+        .with_procedure_decorators(self.procedure_decorators.clone())
+        .with_untyped_output(self.untyped_output)
+        .with_meta_type(self.meta_type.clone())
+    }
 }
 
 /// A watch session that monitors files and triggers regeneration on changes.
@@ -124,7 +187,7 @@ impl WatchSession {
         self.terminal.clear_screen()?;
         print_startup_message(watchable_files.len(), &self.config.router_pattern);
 
-        let generation_result = self.run_initial_generation();
+        let (mut generation, generation_result) = self.run_initial_generation();
         self.print_generation_result(&generation_result);
 
         let (file_event_sender, file_event_receiver) = bounded(100);
@@ -138,26 +201,25 @@ impl WatchSession {
             .watch(&self.config.base_directory, RecursiveMode::Recursive)
             .context("Failed to start watching directory")?;
 
-        let config_clone = self.config.clone();
-
         run_event_loop(
             file_event_receiver,
             shutdown_receiver,
             &self.config.output_directory,
-            move |_changed_paths| handle_file_change(&config_clone),
+            move |changed_paths| handle_file_change(&mut generation, changed_paths),
         )?;
 
         Ok(())
     }
 
-    fn run_initial_generation(&self) -> Result<crate::GenerationResult> {
-        crate::run_generation(
-            &self.config.base_directory,
-            &self.config.output_directory,
-            &self.config.router_pattern,
-            self.config.transformer.as_ref(),
-            self.config.import_extension,
-        )
+    fn run_initial_generation(
+        &self,
+    ) -> (
+        crate::IncrementalGeneration,
+        Result<crate::GenerationResult>,
+    ) {
+        let mut generation = crate::IncrementalGeneration::new(self.config.generation_options());
+        let result = generation.rebuild();
+        (generation, result)
     }
 
     #[allow(clippy::unused_self)]
@@ -188,19 +250,15 @@ impl WatchSession {
 }
 
 #[allow(clippy::unnecessary_wraps)]
-fn handle_file_change(config: &WatchConfig) -> Result<()> {
-    let generation_result = regenerate_with_spinner(|| {
-        crate::run_generation(
-            &config.base_directory,
-            &config.output_directory,
-            &config.router_pattern,
-            config.transformer.as_ref(),
-            config.import_extension,
-        )
-    });
+fn handle_file_change(
+    generation: &mut crate::IncrementalGeneration,
+    changed_paths: &[PathBuf],
+) -> Result<()> {
+    let generation_result = regenerate_with_spinner(|| generation.update(changed_paths));
 
     match generation_result {
-        Ok(result) => print_success(
+        Ok(None) => {}
+        Ok(Some(result)) => print_success(
             result.router_count,
             result.procedure_count,
             result.duration_milliseconds,
@@ -292,7 +350,7 @@ mod tests {
         let session = WatchSession::new(config).unwrap();
 
         // Test initial generation works
-        let result = session.run_initial_generation();
+        let (_generation, result) = session.run_initial_generation();
         assert!(result.is_ok());
     }
 
@@ -156,3 +156,131 @@ fn import_extension_none_is_accepted() {
 fn multiple_verbose_flags_accepted() {
     cli_command().arg("-vvv").arg("--help").assert().success();
 }
+
+fn create_project_with_config(config_source: &str) -> TempDir {
+    let project_directory = TempDir::new().unwrap();
+    let source_directory = project_directory.path().join("src");
+    std::fs::create_dir_all(&source_directory).unwrap();
+
+    std::fs::copy(
+        fixtures_directory().join("valid/simple-router/user.router.ts"),
+        source_directory.join("user.router.ts"),
+    )
+    .unwrap();
+    std::fs::write(
+        source_directory.join("app.module.ts"),
+        "export class AppModule {}",
+    )
+    .unwrap();
+    std::fs::write(
+        project_directory.path().join("nestjs-trpc.config.ts"),
+        config_source,
+    )
+    .unwrap();
+
+    project_directory
+}
+
+#[test]
+fn generate_reads_config_file_from_current_directory() {
+    let project = create_project_with_config(
+        r"
+        export default defineConfig({
+            generation: { outputPath: './generated' },
+        });
+        ",
+    );
+
+    cli_command()
+        .current_dir(project.path())
+        .arg("generate")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Generated server.ts successfully"));
+
+    assert!(
+        project.path().join("generated/server.ts").exists(),
+        "server.ts should be written to the configured outputPath"
+    );
+}
+
+#[test]
+fn generate_cli_flags_override_config_file() {
+    let project = create_project_with_config(
+        r"
+        export default {
+            generation: { outputPath: './generated', routerPattern: 'missing/**/*.router.ts' },
+        };
+        ",
+    );
+    let output_directory = TempDir::new().unwrap();
+
+    cli_command()
+        .current_dir(project.path())
+        .arg("generate")
+        .arg("--router-pattern")
+        .arg("src/**/*.router.ts")
+        .arg("--output")
+        .arg(output_directory.path())
+        .assert()
+        .success();
+
+    assert!(output_directory.path().join("server.ts").exists());
+    assert!(!project.path().join("generated").exists());
+}
+
+#[test]
+fn generate_with_missing_configured_root_module_fails() {
+    let project = create_project_with_config(
+        r"
+        export default {
+            generation: { rootModule: './src/missing.module.ts' },
+        };
+        ",
+    );
+
+    cli_command()
+        .current_dir(project.path())
+        .arg("generate")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("missing.module.ts"))
+        .stderr(predicate::str::contains("does not exist"));
+}
+
+#[test]
+fn generate_with_missing_config_flag_fails() {
+    let project = TempDir::new().unwrap();
+
+    cli_command()
+        .current_dir(project.path())
+        .arg("generate")
+        .arg("--config")
+        .arg("nestjs-trpc.config.ts")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("Configuration file not found"));
+}
+
+#[test]
+fn generate_writes_cache_unless_disabled() {
+    let project = create_project_with_config("export default {};");
+    let cache_directory = project.path().join("node_modules/.cache/nestjs-trpc");
+    std::fs::create_dir_all(project.path().join("node_modules")).unwrap();
+
+    cli_command()
+        .current_dir(project.path())
+        .args(["generate", "--no-cache"])
+        .assert()
+        .success();
+    assert!(!cache_directory.exists());
+
+    cli_command()
+        .current_dir(project.path())
+        .arg("generate")
+        .assert()
+        .success();
+    assert!(cache_directory.exists());
+}
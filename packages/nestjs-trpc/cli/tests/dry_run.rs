@@ -184,3 +184,108 @@ fn dry_run_json_reports_success_true_for_valid_input() {
         "success should be true for valid input"
     );
 }
+
+fn check_command(output_path: &std::path::Path) -> Command {
+    let fixture = fixtures_directory().join("valid/simple-router");
+    let mut command = cli_command();
+    command
+        .arg("generate")
+        .arg("--entrypoint")
+        .arg(fixture.join("user.router.ts"))
+        .arg("--output")
+        .arg(output_path)
+        .arg("--no-cache");
+    command
+}
+
+#[test]
+fn check_passes_when_output_is_up_to_date() {
+    let output_directory = TempDir::new().unwrap();
+
+    check_command(output_directory.path()).assert().success();
+
+    check_command(output_directory.path())
+        .arg("--check")
+        .assert()
+        .code(0)
+        .stdout(predicate::str::contains("up to date"));
+}
+
+#[test]
+fn check_fails_with_dedicated_exit_code_when_output_is_stale() {
+    let output_directory = TempDir::new().unwrap();
+    let server_path = output_directory.path().join("server.ts");
+
+    check_command(output_directory.path()).assert().success();
+    let generated = std::fs::read_to_string(&server_path).unwrap();
+    std::fs::write(&server_path, generated.replace("appRouter", "staleRouter")).unwrap();
+
+    check_command(output_directory.path())
+        .arg("--check")
+        .assert()
+        .code(3)
+        .stdout(predicate::str::contains("out of date"))
+        .stdout(predicate::str::contains("-const staleRouter"));
+
+    assert!(
+        std::fs::read_to_string(&server_path)
+            .unwrap()
+            .contains("staleRouter"),
+        "--check must not rewrite the output"
+    );
+}
+
+#[test]
+fn check_json_reports_changed_files() {
+    let output_directory = TempDir::new().unwrap();
+
+    let output = check_command(output_directory.path())
+        .arg("--check")
+        .arg("--json")
+        .output()
+        .expect("Failed to execute command");
+
+    assert_eq!(output.status.code(), Some(3));
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let parsed: serde_json::Value =
+        serde_json::from_str(&stdout).expect("--json output should be valid JSON");
+
+    assert_eq!(
+        parsed.get("success").and_then(serde_json::Value::as_bool),
+        Some(false)
+    );
+    let changed_files = parsed["changedFiles"].as_array().unwrap();
+    assert_eq!(changed_files.len(), 1);
+    assert!(changed_files[0]["file"]
+        .as_str()
+        .unwrap()
+        .ends_with("server.ts"));
+    assert!(changed_files[0]["unifiedDiff"]
+        .as_str()
+        .unwrap()
+        .contains("+++ b/"));
+}
+
+#[test]
+fn check_reports_generated_files_that_would_be_removed() {
+    let output_directory = TempDir::new().unwrap();
+    let types_path = output_directory.path().join("types.ts");
+
+    check_command(output_directory.path()).assert().success();
+    std::fs::write(
+        &types_path,
+        "/**\n * AUTO-GENERATED FILE - DO NOT EDIT!\n */\nexport type Context = {};\n",
+    )
+    .unwrap();
+
+    check_command(output_directory.path())
+        .arg("--check")
+        .assert()
+        .code(3)
+        .stdout(predicate::str::contains("out of date"))
+        .stdout(predicate::str::contains("+++ /dev/null"))
+        .stdout(predicate::str::contains("-export type Context = {};"));
+
+    assert!(types_path.exists(), "--check must not remove the output");
+}
@@ -0,0 +1,7 @@
+import { applyDecorators } from '@nestjs/common';
+import { Mutation, UseMiddlewares } from 'nestjs-trpc';
+import { AdminMiddleware } from './admin.middleware';
+
+export function AdminMutation(options?: Parameters<typeof Mutation>[0]) {
+    return applyDecorators(Mutation(options), UseMiddlewares(AdminMiddleware));
+}
@@ -0,0 +1,3 @@
+import { createProcedureDecorator } from './decorator-factory';
+
+export const AuditedQuery = createProcedureDecorator('query', 'audit');
@@ -0,0 +1,26 @@
+import { Router, Query } from 'nestjs-trpc';
+import { z } from 'zod';
+import { AdminMutation } from './admin.decorator';
+import { AuditedQuery } from './audited.decorator';
+
+@Router({ alias: 'users' })
+export class UsersRouter {
+    @Query({
+        input: z.object({ id: z.string() }),
+    })
+    getUser(id: string) {
+        return { id };
+    }
+
+    @AdminMutation({
+        input: z.object({ id: z.string() }),
+    })
+    deleteUser(id: string) {
+        return { id };
+    }
+
+    @AuditedQuery()
+    listAuditLog() {
+        return [];
+    }
+}
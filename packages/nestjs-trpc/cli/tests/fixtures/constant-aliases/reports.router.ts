@@ -0,0 +1,12 @@
+import { Router, Query } from 'nestjs-trpc';
+import { Section } from './routes';
+
+const alias = `${Section.Admin}Reports`;
+
+@Router({ alias })
+export class ReportsRouter {
+    @Query()
+    listReports() {
+        return [];
+    }
+}
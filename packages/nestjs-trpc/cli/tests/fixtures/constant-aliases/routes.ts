@@ -0,0 +1,7 @@
+export const ROUTES = {
+    users: 'users',
+} as const;
+
+export enum Section {
+    Admin = 'admin',
+}
@@ -0,0 +1,10 @@
+import { Router, Query } from 'nestjs-trpc';
+import { ROUTES } from './routes';
+
+@Router({ alias: ROUTES.users })
+export class UsersRouter {
+    @Query()
+    listUsers() {
+        return [];
+    }
+}
@@ -0,0 +1,12 @@
+import { Injectable } from '@nestjs/common';
+import { ContextOptions, TRPCContext } from 'nestjs-trpc';
+
+@Injectable()
+export class AppContext implements TRPCContext {
+    async create(opts: ContextOptions) {
+        return {
+            requestId: 'request-id',
+            authenticated: false,
+        };
+    }
+}
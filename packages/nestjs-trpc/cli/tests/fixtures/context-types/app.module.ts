@@ -0,0 +1,15 @@
+import { Module } from '@nestjs/common';
+import { TRPCModule } from 'nestjs-trpc';
+import { AppContext } from './app.context';
+import { LoggingMiddleware } from './middlewares/logging.middleware';
+
+@Module({
+    imports: [
+        TRPCModule.forRoot({
+            autoSchemaFile: './src/@generated',
+            context: AppContext,
+            globalMiddlewares: [LoggingMiddleware],
+        }),
+    ],
+})
+export class AppModule {}
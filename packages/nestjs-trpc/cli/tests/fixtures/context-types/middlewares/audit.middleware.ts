@@ -0,0 +1,9 @@
+import { Injectable } from '@nestjs/common';
+import { MiddlewareOptions, MiddlewareResponse, TRPCMiddleware } from 'nestjs-trpc';
+
+@Injectable()
+export class AuditMiddleware implements TRPCMiddleware {
+    async use(opts: MiddlewareOptions<object>): Promise<MiddlewareResponse> {
+        return opts.next();
+    }
+}
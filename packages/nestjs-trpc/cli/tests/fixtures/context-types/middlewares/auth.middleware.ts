@@ -0,0 +1,14 @@
+import { Injectable } from '@nestjs/common';
+import { MiddlewareOptions, MiddlewareResponse, TRPCMiddleware } from 'nestjs-trpc';
+
+@Injectable()
+export class AuthMiddleware implements TRPCMiddleware {
+    async use(opts: MiddlewareOptions<object>): Promise<MiddlewareResponse> {
+        return opts.next({
+            ctx: {
+                userId: 'user-id',
+                isAdmin: true,
+            },
+        });
+    }
+}
@@ -0,0 +1,17 @@
+import { Injectable, Logger } from '@nestjs/common';
+import { MiddlewareOptions, MiddlewareResponse, TRPCMiddleware } from 'nestjs-trpc';
+
+const logger = new Logger('trpc');
+
+@Injectable()
+export class LoggingMiddleware implements TRPCMiddleware {
+    async use(opts: MiddlewareOptions<object>): Promise<MiddlewareResponse> {
+        return opts.next({
+            ctx: {
+                startedAt: 0,
+                logger: logger,
+                timing: { phase: 'request' },
+            },
+        });
+    }
+}
@@ -0,0 +1,19 @@
+import { Router, Query, Mutation, UseMiddlewares, Ctx } from 'nestjs-trpc';
+import { z } from 'zod';
+import { AuthMiddleware } from './middlewares/auth.middleware';
+import { AuditMiddleware } from './middlewares/audit.middleware';
+
+@Router({ alias: 'users' })
+@UseMiddlewares(AuthMiddleware)
+export class UsersRouter {
+    @Query({ output: z.object({ id: z.string() }) })
+    me(@Ctx() ctx: { userId: string }) {
+        return { id: ctx.userId };
+    }
+
+    @UseMiddlewares(AuditMiddleware)
+    @Mutation({ input: z.object({ id: z.string() }) })
+    remove(@Ctx() ctx: { userId: string }) {
+        return true;
+    }
+}
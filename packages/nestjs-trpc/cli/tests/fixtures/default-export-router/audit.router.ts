@@ -0,0 +1,11 @@
+import { Router, Query } from 'nestjs-trpc';
+
+@Router({ alias: 'audit' })
+class AuditRouter {
+    @Query()
+    entries(): { id: string; action: string }[] {
+        return [];
+    }
+}
+
+export default AuditRouter;
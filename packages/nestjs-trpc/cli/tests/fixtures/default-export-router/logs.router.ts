@@ -0,0 +1,11 @@
+import { Router, Query } from 'nestjs-trpc';
+
+@Router({ alias: 'logs' })
+class LogsRouter {
+    @Query()
+    latest() {
+        return ['started'];
+    }
+}
+
+export { LogsRouter };
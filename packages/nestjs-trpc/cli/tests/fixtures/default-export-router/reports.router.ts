@@ -0,0 +1,9 @@
+import { Router, Query } from 'nestjs-trpc';
+
+@Router({ alias: 'reports' })
+export default class ReportsRouter {
+    @Query()
+    summary() {
+        return { total: 3, generatedAt: new Date() };
+    }
+}
@@ -0,0 +1,34 @@
+import { Query, Resolver } from '@nestjs/graphql';
+import { Router, Query as TrpcQuery } from 'nestjs-trpc';
+import * as trpc from 'nestjs-trpc';
+import { z } from 'zod';
+
+@Router({ alias: 'users' })
+export class UsersRouter {
+    @TrpcQuery({
+        input: z.object({ id: z.string() }),
+    })
+    getUser(id: string) {
+        return { id };
+    }
+
+    @trpc.Mutation({
+        input: z.object({ name: z.string() }),
+    })
+    createUser(name: string) {
+        return { name };
+    }
+
+    @Query(() => String)
+    graphqlOnly() {
+        return 'graphql';
+    }
+}
+
+@Resolver()
+export class UsersResolver {
+    @Query(() => String)
+    hello() {
+        return 'hello';
+    }
+}
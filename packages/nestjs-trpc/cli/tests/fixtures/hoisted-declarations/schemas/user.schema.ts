@@ -0,0 +1,14 @@
+import { z } from 'zod';
+
+const MAX_NAME_LENGTH = 64;
+
+enum Role {
+    Admin = 'ADMIN',
+    Member = 'MEMBER',
+}
+
+export default z.object({
+    name: z.string().max(MAX_NAME_LENGTH),
+    role: z.nativeEnum(Role),
+    fallbackRole: z.literal(Role.Member),
+});
@@ -0,0 +1,41 @@
+import { Router, Query, Mutation } from 'nestjs-trpc';
+import { z } from 'zod';
+import createUserSchema from './schemas/user.schema';
+
+enum Role {
+    Owner = 'OWNER',
+    Guest = 'GUEST',
+}
+
+enum Scope {
+    Own = 1,
+    Team = 2,
+}
+
+enum Permission {
+    Read = Scope.Own << 0,
+    Write = Scope.Own << 1,
+    All = Read | Write,
+}
+
+const MAX_TAGS = 10;
+const TAG_LIMITS = { max: MAX_TAGS, min: 1 };
+
+@Router({ alias: 'users' })
+export class UsersRouter {
+    @Mutation({ input: createUserSchema })
+    createUser(input: { name: string; role: string }) {
+        return input;
+    }
+
+    @Query({
+        input: z.object({
+            role: z.nativeEnum(Role),
+            permission: z.nativeEnum(Permission),
+            tags: z.array(z.string()).max(TAG_LIMITS.max).min(TAG_LIMITS.min),
+        }),
+    })
+    findUsers(input: { role: Role }) {
+        return [];
+    }
+}
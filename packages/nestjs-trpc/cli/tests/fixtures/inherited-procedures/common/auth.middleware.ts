@@ -0,0 +1,7 @@
+import { TRPCMiddleware, MiddlewareOptions } from 'nestjs-trpc';
+
+export class AuthMiddleware implements TRPCMiddleware {
+    use(opts: MiddlewareOptions) {
+        return opts.next({ ctx: { user: 'user' } });
+    }
+}
@@ -0,0 +1,23 @@
+import { Query, Mutation, UseMiddlewares } from 'nestjs-trpc';
+import { z } from 'zod';
+import { AuthMiddleware } from './auth.middleware';
+
+const idInput = z.object({ id: z.string() });
+
+@UseMiddlewares(AuthMiddleware)
+export abstract class BaseCrudRouter<TEntity> {
+    @Query()
+    list(): TEntity[] {
+        return [];
+    }
+
+    @Query({ input: idInput })
+    get(id: string): TEntity | undefined {
+        return undefined;
+    }
+
+    @Mutation({ input: idInput })
+    delete(id: string) {
+        return { id };
+    }
+}
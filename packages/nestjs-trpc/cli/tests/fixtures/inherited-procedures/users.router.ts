@@ -0,0 +1,20 @@
+import { Router, Query } from 'nestjs-trpc';
+import { z } from 'zod';
+import { BaseCrudRouter } from './common';
+
+interface User {
+    id: string;
+}
+
+@Router({ alias: 'users' })
+export class UsersRouter extends BaseCrudRouter<User> {
+    @Query({ input: z.object({ id: z.string(), withPosts: z.boolean() }) })
+    get(id: string): User {
+        return { id };
+    }
+
+    @Query()
+    me(): User {
+        return { id: 'me' };
+    }
+}
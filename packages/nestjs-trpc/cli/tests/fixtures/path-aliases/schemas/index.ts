@@ -0,0 +1 @@
+export { itemSchema } from './item.schema';
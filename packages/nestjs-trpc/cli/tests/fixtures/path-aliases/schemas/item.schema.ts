@@ -0,0 +1,7 @@
+import { z } from 'zod';
+import { idSchema } from 'common/id.schema';
+
+export const itemSchema = z.object({
+    id: idSchema,
+    name: z.string(),
+});
@@ -0,0 +1,4 @@
+export const Roles = {
+    Admin: 'admin',
+    Member: 'member',
+} as const;
@@ -0,0 +1,28 @@
+import { Router, Query, Mutation } from 'nestjs-trpc';
+import { z } from 'zod';
+import { Roles } from './roles';
+
+@Router({ alias: 'users' })
+export class UsersRouter {
+    @Query({
+        meta: { openapi: { method: 'GET', path: '/users/{id}' } },
+        input: z.object({ id: z.string() }),
+        output: z.object({ id: z.string(), name: z.string() }),
+    })
+    getUser() {
+        return { id: '1', name: 'Ada' };
+    }
+
+    @Mutation({
+        input: z.object({ id: z.string() }),
+        meta: { roles: [Roles.Admin] },
+    })
+    deleteUser() {
+        return true;
+    }
+
+    @Query({ output: z.array(z.string()) })
+    listNames() {
+        return [];
+    }
+}
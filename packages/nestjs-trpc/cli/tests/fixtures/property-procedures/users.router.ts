@@ -0,0 +1,13 @@
+import { Router, Query, Mutation } from 'nestjs-trpc';
+import { z } from 'zod';
+
+@Router({ alias: 'users' })
+export class UsersRouter {
+    @Query({ input: z.object({ id: z.string() }) })
+    getUser = async (input: { id: string }) => ({ id: input.id, name: 'Ada' });
+
+    @Mutation({ input: z.object({ id: z.string() }) })
+    'delete-user'(input: { id: string }) {
+        return { deleted: true };
+    }
+}
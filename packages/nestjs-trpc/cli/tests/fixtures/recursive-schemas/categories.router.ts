@@ -0,0 +1,28 @@
+import { Router, Query } from 'nestjs-trpc';
+import { z } from 'zod';
+import commentSchema from './schemas/comment.schema';
+
+const categorySchema = z.object({
+    name: z.string(),
+    parentId: z.string().nullable(),
+    tags: z.array(z.string()).optional(),
+    subcategories: z.lazy(() => categorySchema.array()),
+});
+
+@Router({ alias: 'categories' })
+export class CategoriesRouter {
+    @Query({ output: z.array(categorySchema) })
+    listCategories() {
+        return [];
+    }
+
+    @Query({ input: z.object({ name: z.string() }), output: categorySchema })
+    getCategory() {
+        return null;
+    }
+
+    @Query({ output: z.array(commentSchema) })
+    listComments() {
+        return [];
+    }
+}
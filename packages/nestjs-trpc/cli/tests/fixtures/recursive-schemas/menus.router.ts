@@ -0,0 +1,22 @@
+import { Router, Query } from 'nestjs-trpc';
+import { z } from 'zod';
+
+type MenuItem = {
+    label: string;
+    href?: string;
+    children: MenuItem[];
+};
+
+const menuItemSchema: z.ZodType<MenuItem> = z.object({
+    label: z.string(),
+    href: z.string().url().optional(),
+    children: z.array(z.lazy(() => menuItemSchema)),
+});
+
+@Router({ alias: 'menus' })
+export class MenusRouter {
+    @Query({ output: menuItemSchema })
+    getMenu() {
+        return { label: 'Home', children: [] };
+    }
+}
@@ -0,0 +1,15 @@
+import { z } from 'zod';
+
+enum Visibility {
+    Public = 'PUBLIC',
+    Hidden = 'HIDDEN',
+}
+
+const commentSchema = z.object({
+    id: z.string().uuid(),
+    body: z.string().min(1),
+    visibility: z.nativeEnum(Visibility),
+    replies: z.lazy(() => z.array(commentSchema)),
+});
+
+export default commentSchema;
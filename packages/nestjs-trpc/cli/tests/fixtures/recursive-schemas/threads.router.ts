@@ -0,0 +1,16 @@
+import { Router, Query } from 'nestjs-trpc';
+import { z } from 'zod';
+
+const threadSchema = z.object({
+    title: z.string().default('Untitled'),
+    pinned: z.boolean().catch(false),
+    replies: z.lazy(() => threadSchema.array()),
+});
+
+@Router({ alias: 'threads' })
+export class ThreadsRouter {
+    @Query({ input: threadSchema, output: threadSchema })
+    echoThread() {
+        return { title: 'Untitled', pinned: false, replies: [] };
+    }
+}
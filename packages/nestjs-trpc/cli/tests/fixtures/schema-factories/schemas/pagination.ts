@@ -0,0 +1,8 @@
+import { z } from 'zod';
+
+export function paginated(item: z.ZodTypeAny, pageSize = 20) {
+    return z.object({
+        items: z.array(item),
+        pageSize: z.number().default(pageSize),
+    });
+}
@@ -0,0 +1,28 @@
+import { Router, Query, Mutation } from 'nestjs-trpc';
+import { z } from 'zod';
+import { paginated } from './schemas/pagination';
+
+const userSchema = z.object({ id: z.string(), name: z.string() });
+
+const withTimestamps = <T extends z.ZodRawShape>(shape: T) =>
+    z.object({ ...shape, createdAt: z.date(), updatedAt: z.date() });
+
+function result(data: z.ZodTypeAny) {
+    return z.object({ data, ok: z.boolean() });
+}
+
+@Router({ alias: 'users' })
+export class UsersRouter {
+    @Query({ output: paginated(userSchema) })
+    listUsers() {
+        return { items: [], pageSize: 20 };
+    }
+
+    @Mutation({
+        input: withTimestamps({ name: z.string() }),
+        output: result(userSchema),
+    })
+    createUser(input: { name: string }) {
+        return { data: { id: '1', name: input.name }, ok: true };
+    }
+}
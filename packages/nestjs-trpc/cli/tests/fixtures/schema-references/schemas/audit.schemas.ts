@@ -0,0 +1,5 @@
+import { z } from 'zod';
+
+export class AuditSchemas {
+    static readonly entry = z.object({ action: z.string() });
+}
@@ -0,0 +1,5 @@
+import { z } from 'zod';
+
+export default class CommentSchemas {
+    static readonly create = z.object({ body: z.string().min(1) });
+}
@@ -0,0 +1 @@
+export * as userSchemas from './user.schema';
@@ -0,0 +1,5 @@
+import { z } from 'zod';
+
+const postSchema = z.object({ id: z.string(), title: z.string() });
+
+export default postSchema;
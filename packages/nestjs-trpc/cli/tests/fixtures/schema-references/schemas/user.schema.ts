@@ -0,0 +1,6 @@
+import { z } from 'zod';
+
+export const createUserSchema = z.object({ name: z.string(), email: z.string().email() });
+
+const userSchema = z.object({ id: z.string(), name: z.string() });
+export { userSchema as user };
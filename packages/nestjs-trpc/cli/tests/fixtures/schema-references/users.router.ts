@@ -0,0 +1,44 @@
+import { Router, Query, Mutation } from 'nestjs-trpc';
+import { z } from 'zod';
+import * as UserSchemas from './schemas/user.schema';
+import postSchema from './schemas/post.schema';
+import CommentSchemas from './schemas/comment.schemas';
+import { userSchemas } from './schemas';
+import { AuditSchemas } from './schemas/audit.schemas';
+
+const TagSchemas = {
+    create: z.object({ label: z.string() }),
+    nested: { remove: z.object({ id: z.number() }) },
+} as const;
+
+class PageSchemas {
+    static readonly query = z.object({ page: z.number().int() });
+}
+
+@Router({ alias: 'users' })
+export class UsersRouter {
+    @Mutation({ input: UserSchemas.createUserSchema, output: UserSchemas.user })
+    createUser(input: { name: string; email: string }) {
+        return { id: '1', name: input.name };
+    }
+
+    @Query({ input: userSchemas.createUserSchema, output: postSchema })
+    getPost(input: { name: string }) {
+        return { id: '1', title: input.name };
+    }
+
+    @Mutation({ input: CommentSchemas.create, output: z.array(TagSchemas.create) })
+    addComment(input: { body: string }) {
+        return [{ label: input.body }];
+    }
+
+    @Query({ input: PageSchemas.query, output: TagSchemas.nested.remove })
+    listTags(input: { page: number }) {
+        return { id: input.page };
+    }
+
+    @Query({ output: AuditSchemas.entry })
+    getAuditEntry() {
+        return { action: 'login' };
+    }
+}
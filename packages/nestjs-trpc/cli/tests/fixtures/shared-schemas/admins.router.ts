@@ -0,0 +1,13 @@
+import { Router, Query } from 'nestjs-trpc';
+import { z } from 'zod';
+import adminSchema from './schemas/user.schema';
+
+const auditSchema = z.object({ at: z.date() });
+
+@Router({ alias: 'admins' })
+export class AdminsRouter {
+    @Query({ output: adminSchema.extend({ audit: auditSchema }) })
+    getAdmin() {
+        return { id: '1', name: 'Ada', audit: { at: new Date() } };
+    }
+}
@@ -0,0 +1,8 @@
+import { z } from 'zod';
+
+const MAX_NAME_LENGTH = 32;
+
+export default z.object({
+    id: z.string(),
+    name: z.string().max(MAX_NAME_LENGTH),
+});
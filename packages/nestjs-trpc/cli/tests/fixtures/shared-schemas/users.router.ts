@@ -0,0 +1,16 @@
+import { Router, Query } from 'nestjs-trpc';
+import { z } from 'zod';
+import userSchema from './schemas/user.schema';
+
+@Router({ alias: 'users' })
+export class UsersRouter {
+    @Query({ output: userSchema })
+    getUser() {
+        return { id: '1', name: 'Ada' };
+    }
+
+    @Query({ output: z.array(userSchema) })
+    listUsers() {
+        return [];
+    }
+}
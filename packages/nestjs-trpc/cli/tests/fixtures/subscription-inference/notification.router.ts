@@ -0,0 +1,42 @@
+import { Router, Query, Subscription, Input } from 'nestjs-trpc';
+import { tracked } from '@trpc/server';
+import { Observable, interval, map } from 'rxjs';
+import { z } from 'zod';
+
+interface Notification {
+    id: string;
+    title: string;
+}
+
+@Router({ alias: 'notifications' })
+export class NotificationRouter {
+    private readonly notifications: Notification[] = [];
+
+    @Query()
+    list(): Notification[] {
+        return this.notifications;
+    }
+
+    @Subscription()
+    async *onNotification(): AsyncGenerator<Notification> {
+        for (const notification of this.notifications) {
+            yield notification;
+        }
+    }
+
+    @Subscription({
+        input: z.object({ lastEventId: z.string().nullish() }),
+    })
+    async *onNotificationTracked(@Input('lastEventId') lastEventId?: string | null) {
+        for (const notification of this.notifications) {
+            if (notification.id !== lastEventId) {
+                yield tracked(notification.id, notification);
+            }
+        }
+    }
+
+    @Subscription()
+    onTick(): Observable<{ count: number }> {
+        return interval(1000).pipe(map((count) => ({ count })));
+    }
+}
@@ -0,0 +1,18 @@
+import { Router, Query, Mutation } from 'nestjs-trpc';
+import * as v from 'valibot';
+import { postSchema } from './schemas/post.schema';
+
+const createPostSchema = v.object({ title: v.pipe(v.string(), v.minLength(1)) });
+
+@Router({ alias: 'posts' })
+export class PostsRouter {
+    @Query({ input: v.object({ id: v.string() }), output: postSchema })
+    getPost(input: { id: string }) {
+        return { id: input.id, title: 'Hello' };
+    }
+
+    @Mutation({ input: createPostSchema })
+    createPost(input: { title: string }) {
+        return { id: '1', title: input.title };
+    }
+}
@@ -0,0 +1,5 @@
+import * as v from 'valibot';
+
+const titleSchema = v.pipe(v.string(), v.minLength(1));
+
+export const postSchema = v.object({ id: v.string(), title: titleSchema });
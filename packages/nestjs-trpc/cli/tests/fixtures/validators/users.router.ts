@@ -0,0 +1,12 @@
+import { Router, Query } from 'nestjs-trpc';
+import { type } from 'arktype';
+
+const userSchema = type({ id: 'string', name: 'string' });
+
+@Router({ alias: 'users' })
+export class UsersRouter {
+    @Query({ input: type({ id: 'string' }), output: userSchema })
+    getUser(input: { id: string }) {
+        return { id: input.id, name: 'Ada' };
+    }
+}
@@ -2,8 +2,12 @@
 
 use insta::assert_snapshot;
 use nestjs_trpc::{
-    extract_trpc_options, resolve_transformer_import, run_generation, TransformerInfo, TsParser,
+    extract_trpc_options, generate_in_memory, resolve_transformer_import, run_generation,
+    run_generation_with_options, DecoratorDiagnostic, GenerationOptions, GeneratorError,
+    ProcedureDecoratorAlias, ProcedureType, TransformerInfo, TsParser, UnexportedRouterDiagnostic,
+    UntypedOutput,
 };
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 use tempfile::TempDir;
@@ -107,6 +111,665 @@ fn non_exported_router_falls_back_to_any() {
     );
 }
 
+#[test]
+fn non_exported_router_outputs_can_be_unknown() {
+    let fixture_path = fixtures_directory().join("non-exported-router");
+    let output_directory = TempDir::new().expect("Failed to create temp directory");
+    let options = GenerationOptions::new(&fixture_path, output_directory.path(), "**/*.router.ts")
+        .with_untyped_output(UntypedOutput::Unknown);
+
+    let output = generate_in_memory(&options).expect("Generation failed");
+    let server = &output.files[0].content;
+
+    assert!(
+        server.contains(".query(async () => \"PLACEHOLDER_DO_NOT_REMOVE\" as unknown)"),
+        "Untyped outputs should be `unknown` when configured:\n{server}"
+    );
+}
+
+#[test]
+fn non_exported_router_fails_generation_when_untyped_outputs_are_errors() {
+    let fixture_path = fixtures_directory().join("non-exported-router");
+    let output_directory = TempDir::new().expect("Failed to create temp directory");
+    let options = GenerationOptions::new(&fixture_path, output_directory.path(), "**/*.router.ts")
+        .with_untyped_output(UntypedOutput::Error);
+
+    let error = generate_in_memory(&options).expect_err("Generation should fail");
+    let diagnostic = error
+        .downcast_ref::<UnexportedRouterDiagnostic>()
+        .expect("Expected an unexported router diagnostic");
+
+    assert_eq!(diagnostic.class_name, "ItemsRouter");
+    assert!(diagnostic.help.contains("export class ItemsRouter"));
+}
+
+#[test]
+fn subscription_outputs_are_inferred_from_their_events() {
+    let output = run_generation_on_fixture("subscription-inference");
+
+    assert!(
+        output.contains("type SubscriptionEvent<T> = T extends AsyncIterable<infer Event>"),
+        "Inferred subscriptions should declare the event helper type:\n{output}"
+    );
+    for method in ["onNotification", "onNotificationTracked", "onTick"] {
+        let cast = format!(
+            ".subscription(async () => \"PLACEHOLDER_DO_NOT_REMOVE\" as unknown as AsyncIterable<SubscriptionEvent<Awaited<ReturnType<NotificationRouter[\"{method}\"]>>>>)"
+        );
+        assert!(
+            output.contains(&cast),
+            "{method} should yield the events of its generator or Observable:\n{output}"
+        );
+    }
+    assert!(
+        output.contains("as unknown as Awaited<ReturnType<NotificationRouter[\"list\"]>>)"),
+        "Queries should keep inferring their return type:\n{output}"
+    );
+}
+
+#[test]
+fn default_exported_routers_infer_outputs() {
+    let output = run_generation_on_fixture("default-export-router");
+
+    assert!(
+        output.contains(
+            "import type AuditRouter from \"<FIXTURES>/default-export-router/audit.router\";"
+        ) && output.contains(
+            "import type ReportsRouter from \"<FIXTURES>/default-export-router/reports.router\";"
+        ) && output.contains(
+            "import type { LogsRouter } from \"<FIXTURES>/default-export-router/logs.router\";"
+        ),
+        "Routers should be imported the way their files export them:\n{output}"
+    );
+    assert!(
+        output.contains("as unknown as Awaited<ReturnType<ReportsRouter[\"summary\"]>>")
+            && output.contains("as unknown as Awaited<ReturnType<AuditRouter[\"entries\"]>>")
+            && output.contains("as unknown as Awaited<ReturnType<LogsRouter[\"latest\"]>>"),
+        "Exported routers should have their outputs inferred:\n{output}"
+    );
+}
+
+#[test]
+fn graphql_decorators_are_not_procedures() {
+    let output = run_generation_on_fixture("graphql-hybrid");
+
+    assert!(output.contains("getUser: publicProcedure"), "{output}");
+    assert!(output.contains("createUser: publicProcedure"), "{output}");
+    assert!(
+        !output.contains("graphqlOnly") && !output.contains("hello"),
+        "`@Query` from @nestjs/graphql must not become a procedure:\n{output}"
+    );
+}
+
+#[test]
+fn composed_and_configured_procedure_decorators_are_procedures() {
+    let fixture_path = fixtures_directory().join("composed-decorators");
+    let output_directory = TempDir::new().expect("Failed to create temp directory");
+    let options = GenerationOptions::new(&fixture_path, output_directory.path(), "**/*.router.ts")
+        .with_procedure_decorators(BTreeMap::from([(
+            "AuditedQuery".to_string(),
+            ProcedureDecoratorAlias {
+                procedure_type: ProcedureType::Query,
+                middlewares: vec!["AuditMiddleware".to_string()],
+            },
+        )]));
+
+    let output = generate_in_memory(&options).expect("Generation failed");
+    let server = &output.files[0].content;
+
+    assert_eq!(output.result.procedure_count, 3, "{server}");
+    assert!(
+        server.contains("deleteUser: AdminMiddlewareProcedure\n      .input("),
+        "`applyDecorators` factories should keep their input and imply their middlewares:\n{server}"
+    );
+    assert!(
+        server.contains("listAuditLog: AuditMiddlewareProcedure\n      .query("),
+        "Configured decorators should map to their procedure type:\n{server}"
+    );
+}
+
+#[test]
+fn router_aliases_are_evaluated_from_constants() {
+    let fixture_path = fixtures_directory().join("constant-aliases");
+    let output_directory = TempDir::new().expect("Failed to create temp directory");
+    let options = GenerationOptions::new(&fixture_path, output_directory.path(), "**/*.router.ts");
+
+    let output = generate_in_memory(&options).expect("Generation failed");
+    let server = &output.files[0].content;
+
+    assert!(server.contains("users: t.router({"), "{server}");
+    assert!(server.contains("adminReports: t.router({"), "{server}");
+}
+
+#[test]
+fn unevaluable_router_alias_is_reported_at_the_alias() {
+    let source_directory = TempDir::new().expect("Failed to create temp directory");
+    let output_directory = TempDir::new().expect("Failed to create temp directory");
+    let source = "import { Router } from 'nestjs-trpc';\n\n\
+                  @Router({ alias: process.env.USERS_ALIAS })\n\
+                  export class UsersRouter {}\n";
+    fs::write(source_directory.path().join("users.router.ts"), source).unwrap();
+
+    let options = GenerationOptions::new(
+        source_directory.path(),
+        output_directory.path(),
+        "**/*.router.ts",
+    );
+    let error = generate_in_memory(&options).expect_err("Generation should fail");
+    let diagnostic = error
+        .downcast_ref::<DecoratorDiagnostic>()
+        .expect("Expected a decorator diagnostic");
+
+    assert_eq!(diagnostic.decorator, "Router");
+    assert!(
+        diagnostic.help.contains("UsersRouter"),
+        "{}",
+        diagnostic.help
+    );
+}
+
+#[test]
+fn procedure_and_sub_router_with_the_same_path_conflict() {
+    let source_directory = TempDir::new().expect("Failed to create temp directory");
+    let output_directory = TempDir::new().expect("Failed to create temp directory");
+    fs::write(
+        source_directory.path().join("admin.router.ts"),
+        "import { Router, Query } from 'nestjs-trpc';\n\n\
+         @Router({ alias: 'admin' })\n\
+         export class AdminRouter {\n  @Query()\n  users() {}\n}\n\n\
+         @Router({ alias: 'admin/users' })\n\
+         export class AdminUsersRouter {\n  @Query()\n  list() {}\n}\n",
+    )
+    .unwrap();
+
+    let options = GenerationOptions::new(
+        source_directory.path(),
+        output_directory.path(),
+        "**/*.router.ts",
+    );
+    let error = generate_in_memory(&options).expect_err("Generation should fail");
+
+    assert!(
+        matches!(
+            error.downcast_ref::<GeneratorError>(),
+            Some(GeneratorError::RouterPathConflict { path, .. }) if path == "admin.users"
+        ),
+        "{error:#}"
+    );
+}
+
+#[test]
+fn inherited_procedures_are_extracted_from_base_routers() {
+    let fixture_path = fixtures_directory().join("inherited-procedures");
+    let output_directory = TempDir::new().expect("Failed to create temp directory");
+    let options = GenerationOptions::new(&fixture_path, output_directory.path(), "**/*.router.ts");
+
+    let output = generate_in_memory(&options).expect("Generation failed");
+    let server = &output.files[0].content;
+
+    assert_eq!(output.result.procedure_count, 4, "{server}");
+    assert_eq!(server.matches("get: ").count(), 1, "{server}");
+    assert!(
+        server.contains("withPosts: z.boolean()"),
+        "Subclass overrides should win:\n{server}"
+    );
+    assert!(
+        server.contains(
+            "delete: AuthMiddlewareProcedure\n      .input(z.object({ id: z.string() }))"
+        ),
+        "Inherited schemas should be flattened from the base file:\n{server}"
+    );
+    assert!(
+        server.contains("Awaited<ReturnType<UsersRouter[\"list\"]>>"),
+        "Inherited procedures should infer outputs from the concrete router:\n{server}"
+    );
+}
+
+#[test]
+fn property_and_string_keyed_procedures_are_generated() {
+    let fixture_path = fixtures_directory().join("property-procedures");
+    let output_directory = TempDir::new().expect("Failed to create temp directory");
+    let options = GenerationOptions::new(&fixture_path, output_directory.path(), "**/*.router.ts");
+
+    let output = generate_in_memory(&options).expect("Generation failed");
+    let server = &output.files[0].content;
+
+    assert_eq!(output.result.procedure_count, 2, "{server}");
+    assert!(
+        server.contains("Awaited<ReturnType<UsersRouter[\"getUser\"]>>"),
+        "Arrow-function properties should infer their output:\n{server}"
+    );
+    assert!(
+        server.contains("\"delete-user\": publicProcedure"),
+        "Non-identifier keys should be quoted:\n{server}"
+    );
+}
+
+#[test]
+fn member_namespace_and_default_schema_references_are_flattened() {
+    let fixture_path = fixtures_directory().join("schema-references");
+    let output_directory = TempDir::new().expect("Failed to create temp directory");
+    let options = GenerationOptions::new(&fixture_path, output_directory.path(), "**/*.router.ts");
+
+    let output = generate_in_memory(&options).expect("Generation failed");
+    let server = &output.files[0].content;
+
+    assert_eq!(output.result.procedure_count, 5, "{server}");
+    assert!(
+        server.contains(".input(z.object({ name: z.string(), email: z.string().email() }))\n      .output(z.object({ id: z.string(), name: z.string() }))"),
+        "Namespace imports and aliased exports should be flattened:\n{server}"
+    );
+    assert!(
+        server.contains(".output(z.object({ id: z.string(), title: z.string() }))"),
+        "Default imports should be flattened:\n{server}"
+    );
+    assert!(
+        server.contains(".input(z.object({ body: z.string().min(1) }))"),
+        "Static members of a default-exported class should be flattened:\n{server}"
+    );
+    assert!(
+        server.contains(".output(z.array(z.object({ label: z.string() })))"),
+        "Local object members should be flattened:\n{server}"
+    );
+    assert!(
+        server.contains(".input(z.object({ page: z.number().int() }))\n      .output(z.object({ id: z.number() }))"),
+        "Local static class members and nested object members should be flattened:\n{server}"
+    );
+    assert!(
+        server.contains(".output(AuditSchemas.entry)")
+            && server.contains("import { AuditSchemas } from"),
+        "Importable classes should be imported and accessed as written:\n{server}"
+    );
+    for unresolved in [
+        "UserSchemas",
+        "userSchemas",
+        "postSchema",
+        "CommentSchemas",
+        "TagSchemas",
+        "PageSchemas",
+    ] {
+        assert!(
+            !server.contains(unresolved),
+            "`{unresolved}` should not be left in server.ts:\n{server}"
+        );
+    }
+}
+
+#[test]
+fn schema_factories_are_inlined() {
+    let fixture_path = fixtures_directory().join("schema-factories");
+    let output_directory = TempDir::new().expect("Failed to create temp directory");
+    let options = GenerationOptions::new(&fixture_path, output_directory.path(), "**/*.router.ts");
+
+    let output = generate_in_memory(&options).expect("Generation failed");
+    let server = &output.files[0].content;
+
+    assert_eq!(output.result.procedure_count, 2, "{server}");
+    assert!(
+        server.contains(
+            ".input(z.object({ ...{ name: z.string() }, createdAt: z.date(), updatedAt: z.date() }))"
+        ),
+        "Local arrow factories should be inlined:\n{server}"
+    );
+    assert!(
+        server.contains(".output(z.object({ data: userSchema_flat, ok: z.boolean() }))"),
+        "Local function declarations should be inlined with shorthand properties expanded:\n{server}"
+    );
+    assert!(
+        server.contains(".output(paginated(userSchema_flat))"),
+        "Exported factories should keep being imported:\n{server}"
+    );
+    assert!(
+        !server.contains("withTimestamps") && !server.contains("result("),
+        "Non-exported factories should not leak into server.ts:\n{server}"
+    );
+}
+
+#[test]
+fn non_exported_enums_are_hoisted_and_constants_inlined() {
+    let fixture_path = fixtures_directory().join("hoisted-declarations");
+    let output_directory = TempDir::new().expect("Failed to create temp directory");
+    let options = GenerationOptions::new(&fixture_path, output_directory.path(), "**/*.router.ts");
+
+    let output = generate_in_memory(&options).expect("Generation failed");
+    let server = &output.files[0].content;
+
+    assert!(
+        server.contains("name: z.string().max(64)")
+            && server.contains("z.array(z.string()).max(10).min(1)"),
+        "Non-exported constants should be inlined, including inside validator chains:\n{server}"
+    );
+    assert!(
+        server.contains("enum Role {\n    Admin = 'ADMIN',")
+            && server.contains("role: z.nativeEnum(Role),")
+            && server.contains("fallbackRole: z.literal(Role.Member)"),
+        "Enums from schema files should be hoisted under their own name:\n{server}"
+    );
+    assert!(
+        server.contains("enum Role_2 {\n    Owner = 'OWNER',")
+            && server.contains("role: z.nativeEnum(Role_2)"),
+        "Same-named enums from another file should be renamed:\n{server}"
+    );
+    let scope = server
+        .find("enum Scope {")
+        .expect("Scope should be hoisted");
+    let permission = server
+        .find("enum Permission {")
+        .expect("Permission should be hoisted");
+    assert!(
+        scope < permission && server.contains("Read = Scope.Own << 0,"),
+        "Enums used by hoisted enums should be hoisted before them:\n{server}"
+    );
+    assert_eq!(server.matches("enum Permission {").count(), 1, "{server}");
+    assert!(!server.contains("$hoisted$"), "{server}");
+}
+
+#[test]
+fn shared_schemas_are_declared_once() {
+    let fixture_path = fixtures_directory().join("shared-schemas");
+    let output_directory = TempDir::new().expect("Failed to create temp directory");
+    let options = GenerationOptions::new(&fixture_path, output_directory.path(), "**/*.router.ts");
+
+    let output = generate_in_memory(&options).expect("Generation failed");
+    let server = &output.files[0].content;
+
+    assert_eq!(
+        server.matches("name: z.string().max(32)").count(),
+        1,
+        "A schema used by several procedures should be flattened once:\n{server}"
+    );
+    assert!(
+        server.contains("const adminSchema_flat = z.object({"),
+        "Shared schemas should be declared as constants:\n{server}"
+    );
+    assert!(
+        server.contains(".output(adminSchema_flat.extend({ audit: z.object({ at: z.date() }) }))")
+            && server.contains(".output(adminSchema_flat)")
+            && server.contains(".output(z.array(adminSchema_flat))"),
+        "Routers importing the schema under different names should share it:\n{server}"
+    );
+    assert!(
+        !server.contains("auditSchema"),
+        "Schemas used once should stay inlined:\n{server}"
+    );
+}
+
+#[test]
+fn recursive_schemas_are_declared_with_their_type() {
+    let fixture_path = fixtures_directory().join("recursive-schemas");
+    let output_directory = TempDir::new().expect("Failed to create temp directory");
+    let options = GenerationOptions::new(&fixture_path, output_directory.path(), "**/*.router.ts");
+
+    let output = generate_in_memory(&options).expect("Generation failed");
+    let server = &output.files[0].content;
+
+    assert!(
+        server.contains("type Category = { name: string; parentId: string | null; tags?: Array<string> | undefined; subcategories: Array<Category> };")
+            && server.contains("const categorySchema_flat: z.ZodType<Category> = z.object({"),
+        "Recursive schemas should be annotated with a generated type:\n{server}"
+    );
+    assert!(
+        server.contains("subcategories: z.lazy(() => categorySchema_flat.array())"),
+        "Recursive schemas should refer to their declaration:\n{server}"
+    );
+    assert!(
+        server.contains("visibility: Visibility; replies: Array<Comment>"),
+        "Imported recursive schemas should use hoisted enums in their type:\n{server}"
+    );
+    assert!(
+        server.contains("const menuItemSchema_flat: z.ZodType<MenuItem> = z.object({")
+            && server.contains(".output(menuItemSchema_flat)"),
+        "Recursive schemas used once should still be declared:\n{server}"
+    );
+}
+
+#[test]
+fn recursive_schemas_with_defaults_are_declared_with_their_input_type() {
+    let source_directory = TempDir::new().expect("Failed to create temp directory");
+    let output_directory = TempDir::new().expect("Failed to create temp directory");
+    let zod_package = source_directory.path().join("node_modules/zod");
+    fs::create_dir_all(&zod_package).unwrap();
+    fs::write(
+        zod_package.join("package.json"),
+        r#"{ "version": "3.25.76" }"#,
+    )
+    .unwrap();
+    fs::copy(
+        fixtures_directory().join("recursive-schemas/threads.router.ts"),
+        source_directory.path().join("threads.router.ts"),
+    )
+    .unwrap();
+
+    let options = GenerationOptions::new(
+        source_directory.path(),
+        output_directory.path(),
+        "**/*.router.ts",
+    );
+    let output = generate_in_memory(&options).expect("Generation failed");
+    let server = &output.files[0].content;
+
+    assert!(
+        server.contains("type Thread = { title: Exclude<string, undefined>; pinned: Exclude<boolean, undefined>; replies: Array<Thread> };")
+            && server.contains("type ThreadInput = { title?: string | undefined; pinned?: unknown; replies: Array<ThreadInput> };"),
+        "Recursive schemas with defaults should declare their input type:\n{server}"
+    );
+    assert!(
+        server.contains(
+            "const threadSchema_flat: z.ZodType<Thread, z.ZodTypeDef, ThreadInput> = z.object({"
+        ),
+        "Zod 3 annotations should pass the input type after ZodTypeDef:\n{server}"
+    );
+}
+
+#[test]
+fn valibot_and_arktype_schemas_are_generated_with_their_imports() {
+    let fixture_path = fixtures_directory().join("validators");
+    let output_directory = TempDir::new().expect("Failed to create temp directory");
+    let options = GenerationOptions::new(&fixture_path, output_directory.path(), "**/*.router.ts");
+
+    let output = generate_in_memory(&options).expect("Generation failed");
+    let server = &output.files[0].content;
+
+    assert_eq!(output.result.procedure_count, 3, "{server}");
+    assert!(
+        server.contains("import * as v from \"valibot\";"),
+        "Valibot should be imported as a namespace:\n{server}"
+    );
+    assert!(
+        server.contains("import { type } from \"arktype\";"),
+        "ArkType should be imported by name:\n{server}"
+    );
+    assert!(
+        !server.contains("from \"zod\""),
+        "Zod should not be imported when no schema uses it:\n{server}"
+    );
+    assert!(
+        server.contains(".input(v.object({ title: v.pipe(v.string(), v.minLength(1)) }))"),
+        "Local Valibot schemas should be inlined:\n{server}"
+    );
+    assert!(
+        server.contains(".output(type({ id: 'string', name: 'string' }))"),
+        "Local ArkType schemas should be inlined:\n{server}"
+    );
+    assert!(
+        server.contains("import { postSchema } from \"../"),
+        "Exported schemas should still be imported:\n{server}"
+    );
+}
+
+#[test]
+fn zod_entry_point_is_mirrored_in_server_file() {
+    let source_directory = TempDir::new().expect("Failed to create temp directory");
+    let output_directory = TempDir::new().expect("Failed to create temp directory");
+    fs::write(
+        source_directory.path().join("users.router.ts"),
+        "import { Router, Query } from 'nestjs-trpc';\n\
+         import * as z from 'zod/mini';\n\n\
+         @Router({ alias: 'users' })\n\
+         export class UsersRouter {\n  @Query({ input: z.object({ id: z.string() }) })\n  getUser() {}\n}\n",
+    )
+    .unwrap();
+
+    let options = GenerationOptions::new(
+        source_directory.path(),
+        output_directory.path(),
+        "**/*.router.ts",
+    );
+    let output = generate_in_memory(&options).expect("Generation failed");
+    let server = &output.files[0].content;
+
+    assert!(
+        server.contains("import * as z from \"zod/mini\";"),
+        "{server}"
+    );
+    assert!(!server.contains("from \"zod\""), "{server}");
+}
+
+#[test]
+fn mixed_zod_entry_points_are_reported() {
+    let source_directory = TempDir::new().expect("Failed to create temp directory");
+    let output_directory = TempDir::new().expect("Failed to create temp directory");
+    let zod_package = source_directory.path().join("node_modules/zod");
+    fs::create_dir_all(&zod_package).unwrap();
+    fs::write(
+        zod_package.join("package.json"),
+        r#"{ "version": "3.25.76" }"#,
+    )
+    .unwrap();
+    for (name, entry_point) in [("Posts", "zod"), ("Users", "zod/v4")] {
+        fs::write(
+            source_directory
+                .path()
+                .join(format!("{}.router.ts", name.to_lowercase())),
+            format!(
+                "import {{ Router, Query }} from 'nestjs-trpc';\n\
+                 import {{ z }} from '{entry_point}';\n\n\
+                 @Router()\n\
+                 export class {name}Router {{\n  @Query({{ input: z.string() }})\n  get() {{}}\n}}\n"
+            ),
+        )
+        .unwrap();
+    }
+
+    let options = GenerationOptions::new(
+        source_directory.path(),
+        output_directory.path(),
+        "**/*.router.ts",
+    );
+    let error = generate_in_memory(&options).expect_err("Generation should fail");
+
+    assert!(
+        matches!(
+            error.downcast_ref::<GeneratorError>(),
+            Some(GeneratorError::MixedZodEntryPoints { first_entry_point, second_entry_point, .. })
+                if first_entry_point == "zod" && second_entry_point == "zod/v4"
+        ),
+        "{error:#}"
+    );
+}
+
+#[test]
+fn zod_entry_points_of_imported_schemas_are_not_checked() {
+    let source_directory = TempDir::new().expect("Failed to create temp directory");
+    let output_directory = TempDir::new().expect("Failed to create temp directory");
+    fs::write(
+        source_directory.path().join("legacy.schema.ts"),
+        "import { z } from 'zod/v3';
+
+export const legacySchema = z.object({ id: z.string() });
+",
+    )
+    .unwrap();
+    fs::write(
+        source_directory.path().join("users.router.ts"),
+        "import { Router, Query } from 'nestjs-trpc';
+         import { z } from 'zod/v4';
+         import { legacySchema } from './legacy.schema';
+
+         @Router({ alias: 'users' })
+         export class UsersRouter {
+  @Query({ input: z.object({ id: z.string() }), output: legacySchema })
+  getUser() {}
+}
+",
+    )
+    .unwrap();
+
+    let options = GenerationOptions::new(
+        source_directory.path(),
+        output_directory.path(),
+        "**/*.router.ts",
+    );
+    let output = generate_in_memory(&options).expect("Generation failed");
+    let server = &output.files[0].content;
+
+    assert!(server.contains("import { z } from \"zod/v4\";"), "{server}");
+    assert!(
+        server.contains("import { legacySchema } from \"../"),
+        "{server}"
+    );
+}
+
+#[test]
+fn zod_entry_points_of_inlined_schemas_are_checked() {
+    let source_directory = TempDir::new().expect("Failed to create temp directory");
+    let output_directory = TempDir::new().expect("Failed to create temp directory");
+    fs::write(
+        source_directory.path().join("user.schema.ts"),
+        "import * as z from 'zod/mini';\n\nexport default z.object({ id: z.string() });\n",
+    )
+    .unwrap();
+    fs::write(
+        source_directory.path().join("users.router.ts"),
+        "import { Router, Query } from 'nestjs-trpc';\n\
+         import { z } from 'zod/v4';\n\
+         import userSchema from './user.schema';\n\n\
+         @Router({ alias: 'users' })\n\
+         export class UsersRouter {\n  @Query({ input: z.string(), output: userSchema })\n  getUser() {}\n}\n",
+    )
+    .unwrap();
+
+    let options = GenerationOptions::new(
+        source_directory.path(),
+        output_directory.path(),
+        "**/*.router.ts",
+    );
+    let error = generate_in_memory(&options).expect_err("Generation should fail");
+
+    assert!(
+        matches!(
+            error.downcast_ref::<GeneratorError>(),
+            Some(GeneratorError::MixedZodEntryPoints { .. })
+        ),
+        "{error:#}"
+    );
+}
+
+#[test]
+fn parallel_generation_is_deterministic() {
+    let fixture_path =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("benches/fixtures/large-project");
+
+    let outputs: Vec<String> = (0..5)
+        .map(|_| {
+            let temporary_directory = TempDir::new().expect("Failed to create temp directory");
+            run_generation(
+                &fixture_path,
+                temporary_directory.path(),
+                "**/*.router.ts",
+                None,
+                false,
+            )
+            .expect("Generation failed");
+            fs::read_to_string(temporary_directory.path().join("server.ts")).unwrap()
+        })
+        .collect();
+
+    assert!(
+        outputs.windows(2).all(|pair| pair[0] == pair[1]),
+        "Generated output must be byte-for-byte identical across runs"
+    );
+}
+
 #[test]
 fn snapshot_path_aliases() {
     let output = run_generation_on_fixture("path-aliases");
@@ -219,6 +882,311 @@ fn run_generation_with_module_transformer(fixture_name: &str) -> String {
     normalize_paths(&content)
 }
 
+#[test]
+fn snapshot_procedure_meta() {
+    let output = run_generation_on_fixture("procedure-meta");
+    assert_snapshot!("procedure_meta", output);
+}
+
+#[test]
+fn configured_meta_type_replaces_the_default() {
+    let fixture_path = fixtures_directory().join("procedure-meta");
+    let output_directory = TempDir::new().expect("Failed to create temp directory");
+    let options = GenerationOptions::new(&fixture_path, output_directory.path(), "**/*.router.ts")
+        .with_meta_type(Some("{ roles?: string[]; openapi?: unknown }".to_string()));
+
+    let output = generate_in_memory(&options).expect("Generation failed");
+    let server = &output.files[0].content;
+
+    assert!(
+        server.contains("initTRPC.meta<{ roles?: string[]; openapi?: unknown }>().create()"),
+        "The configured meta type should be used:\n{server}"
+    );
+}
+
+fn run_generation_with_root_module(fixture_name: &str) -> TempDir {
+    let fixture_path = fixtures_directory().join(fixture_name);
+    let temporary_directory = TempDir::new().expect("Failed to create temp directory");
+
+    let options =
+        GenerationOptions::new(&fixture_path, temporary_directory.path(), "**/*.router.ts")
+            .with_root_module(Some(fixture_path.join("app.module.ts")));
+    run_generation_with_options(&options).expect("Generation failed");
+
+    temporary_directory
+}
+
+#[test]
+fn snapshot_types_file_with_context_and_middlewares() {
+    let output_directory = run_generation_with_root_module("context-types");
+
+    let types_file = output_directory.path().join("types.ts");
+    let content = fs::read_to_string(&types_file).expect("Failed to read generated types.ts");
+    assert_snapshot!("types_file_with_context_and_middlewares", content);
+}
+
+#[test]
+fn snapshot_middleware_procedure_builders() {
+    let output_directory = run_generation_with_root_module("context-types");
+
+    let server_file = output_directory.path().join("server.ts");
+    let content = fs::read_to_string(&server_file).expect("Failed to read generated server.ts");
+    assert_snapshot!("middleware_procedure_builders", normalize_paths(&content));
+}
+
+#[test]
+fn types_file_not_written_without_context_or_middlewares() {
+    let fixture_path = fixtures_directory().join("valid/simple-router");
+    let temporary_directory = TempDir::new().expect("Failed to create temp directory");
+    let output_path = temporary_directory.path();
+
+    run_generation(&fixture_path, output_path, "**/*.router.ts", None, false)
+        .expect("Generation failed");
+
+    assert!(output_path.join("server.ts").exists());
+    assert!(!output_path.join("types.ts").exists());
+}
+
+#[test]
+fn stale_types_file_is_removed_once_nothing_needs_it() {
+    let fixture_path = fixtures_directory().join("valid/simple-router");
+    let output_directory = run_generation_with_root_module("context-types");
+    let types_file = output_directory.path().join("types.ts");
+    assert!(types_file.exists());
+
+    let options = GenerationOptions::new(&fixture_path, output_directory.path(), "**/*.router.ts");
+    let output = generate_in_memory(&options).expect("Generation failed");
+    assert_eq!(output.removed_files, std::slice::from_ref(&types_file));
+    assert!(types_file.exists());
+
+    run_generation_with_options(&options).expect("Generation failed");
+    assert!(!types_file.exists());
+}
+
+#[test]
+fn hand_written_types_file_is_kept() {
+    let fixture_path = fixtures_directory().join("valid/simple-router");
+    let temporary_directory = TempDir::new().expect("Failed to create temp directory");
+    let types_file = temporary_directory.path().join("types.ts");
+    fs::write(&types_file, "export type Custom = string;\n").unwrap();
+
+    let options =
+        GenerationOptions::new(&fixture_path, temporary_directory.path(), "**/*.router.ts");
+    run_generation_with_options(&options).expect("Generation failed");
+
+    assert!(types_file.exists());
+}
+
+#[test]
+fn generate_in_memory_does_not_save_the_cache() {
+    let fixture_path = fixtures_directory().join("valid/simple-router");
+    let temporary_directory = TempDir::new().expect("Failed to create temp directory");
+    let cache_directory = temporary_directory.path().join("cache");
+
+    let options =
+        GenerationOptions::new(&fixture_path, temporary_directory.path(), "**/*.router.ts")
+            .with_cache_directory(Some(cache_directory.clone()));
+    generate_in_memory(&options).expect("Generation failed");
+    assert!(!cache_directory.exists());
+
+    run_generation_with_options(&options).expect("Generation failed");
+    assert!(cache_directory.exists());
+}
+
+#[test]
+fn generate_in_memory_matches_written_output_without_writing() {
+    let fixture_path = fixtures_directory().join("context-types");
+    let output_directory = run_generation_with_root_module("context-types");
+    let nested_output = output_directory.path().join("nested");
+
+    let options = GenerationOptions::new(&fixture_path, output_directory.path(), "**/*.router.ts")
+        .with_root_module(Some(fixture_path.join("app.module.ts")));
+    let output = generate_in_memory(&options).expect("Generation failed");
+
+    let file_names: Vec<_> = output
+        .files
+        .iter()
+        .map(|file| {
+            file.path
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect();
+    assert_eq!(file_names, ["server.ts", "types.ts"]);
+    for file in &output.files {
+        assert_eq!(fs::read_to_string(&file.path).unwrap(), file.content);
+    }
+
+    let nested_options = GenerationOptions {
+        output_path: nested_output.clone(),
+        ..options
+    };
+    generate_in_memory(&nested_options).expect("Generation failed");
+    assert!(!nested_output.exists());
+}
+
+fn write_workspace_file(root: &std::path::Path, name: &str, content: &str) {
+    let path = root.join(name);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn workspace_package_imports_resolve_through_node_modules() {
+    let workspace = TempDir::new().expect("Failed to create temp directory");
+    let root = workspace.path();
+
+    write_workspace_file(
+        root,
+        "packages/contracts/package.json",
+        r#"{ "name": "@acme/contracts", "exports": { ".": { "types": "./dist/index.d.ts", "default": "./dist/index.js" } } }"#,
+    );
+    write_workspace_file(
+        root,
+        "packages/contracts/src/index.ts",
+        "export * from './user.schema';\nexport * from './tenant.middleware';\n",
+    );
+    write_workspace_file(
+        root,
+        "packages/contracts/src/user.schema.ts",
+        "import { z } from 'zod';\nexport const userSchema = z.object({ id: z.string() });\n",
+    );
+    write_workspace_file(
+        root,
+        "packages/contracts/src/tenant.middleware.ts",
+        r"import { MiddlewareOptions, MiddlewareResponse, TRPCMiddleware } from 'nestjs-trpc';
+
+export class TenantMiddleware implements TRPCMiddleware {
+    async use(opts: MiddlewareOptions<object>): Promise<MiddlewareResponse> {
+        return opts.next({ ctx: { tenantId: 'tenant-id' } });
+    }
+}
+",
+    );
+    write_workspace_file(
+        root,
+        "apps/api/src/app.module.ts",
+        r"import { Module } from '@nestjs/common';
+import { TRPCModule } from 'nestjs-trpc';
+import { TenantMiddleware } from '@acme/contracts';
+
+@Module({
+    imports: [TRPCModule.forRoot({ globalMiddlewares: [TenantMiddleware] })],
+})
+export class AppModule {}
+",
+    );
+    write_workspace_file(
+        root,
+        "apps/api/src/users.router.ts",
+        r"import { Router, Query } from 'nestjs-trpc';
+import { userSchema } from '@acme/contracts';
+
+@Router({ alias: 'users' })
+export class UsersRouter {
+    @Query({ output: userSchema })
+    me() {
+        return {} as any;
+    }
+}
+",
+    );
+    fs::create_dir_all(root.join("apps/api/node_modules/@acme")).unwrap();
+    std::os::unix::fs::symlink(
+        root.join("packages/contracts"),
+        root.join("apps/api/node_modules/@acme/contracts"),
+    )
+    .unwrap();
+
+    let source_directory = root.join("apps/api/src");
+    let output_directory = TempDir::new().expect("Failed to create temp directory");
+    let options =
+        GenerationOptions::new(&source_directory, output_directory.path(), "**/*.router.ts")
+            .with_root_module(Some(source_directory.join("app.module.ts")));
+    run_generation_with_options(&options).expect("Generation failed");
+
+    let types = fs::read_to_string(output_directory.path().join("types.ts")).unwrap();
+    assert!(
+        types.contains("tenantId: string"),
+        "Middleware from the workspace package should be resolved:\n{types}"
+    );
+
+    let server = fs::read_to_string(output_directory.path().join("server.ts")).unwrap();
+    assert!(
+        server.contains(r#"import { userSchema } from "@acme/contracts";"#),
+        "Package imports should keep their bare specifier:\n{server}"
+    );
+    assert!(
+        !server.contains("packages/contracts"),
+        "Package imports must not point into the package source:\n{server}"
+    );
+}
+
+#[test]
+fn cached_generation_tracks_router_and_barrel_changes() {
+    let project = TempDir::new().expect("Failed to create temp directory");
+    let root = project.path();
+    let users_router = r"import { Router, Query } from 'nestjs-trpc';
+import { userSchema } from './schemas';
+
+@Router({ alias: 'users' })
+export class UsersRouter {
+    @Query({ output: userSchema })
+    me() {
+        return {} as any;
+    }
+}
+";
+    write_workspace_file(root, "src/users.router.ts", users_router);
+    write_workspace_file(
+        root,
+        "src/schemas/index.ts",
+        "export { userSchema } from './user.schema';\n",
+    );
+    write_workspace_file(
+        root,
+        "src/schemas/user.schema.ts",
+        "import { z } from 'zod';\nexport const userSchema = z.object({ id: z.string() });\n",
+    );
+
+    let output_directory = TempDir::new().expect("Failed to create temp directory");
+    let cache_directory = root.join("node_modules/.cache/nestjs-trpc");
+    let options = GenerationOptions::new(root, output_directory.path(), "**/*.router.ts")
+        .with_cache_directory(Some(cache_directory.clone()));
+    let generate = || {
+        run_generation_with_options(&options).expect("Generation failed");
+        fs::read_to_string(output_directory.path().join("server.ts")).unwrap()
+    };
+
+    let uncached = generate();
+    assert_eq!(fs::read_dir(&cache_directory).unwrap().count(), 1);
+    assert_eq!(generate(), uncached);
+
+    write_workspace_file(
+        root,
+        "src/users.router.ts",
+        &users_router.replace("me()", "profile()"),
+    );
+    let renamed = generate();
+    assert!(renamed.contains("profile:"), "{renamed}");
+
+    write_workspace_file(
+        root,
+        "src/schemas/account.schema.ts",
+        "import { z } from 'zod';\nexport const userSchema = z.object({ id: z.number() });\n",
+    );
+    write_workspace_file(
+        root,
+        "src/schemas/index.ts",
+        "export { userSchema } from './account.schema';\n",
+    );
+    let moved = generate();
+    assert!(moved.contains("schemas/account.schema"), "{moved}");
+}
+
 #[test]
 fn snapshot_transformer_from_library() {
     let output = run_generation_with_module_transformer("transformer-library");
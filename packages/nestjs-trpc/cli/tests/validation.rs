@@ -26,16 +26,33 @@ fn project_root() -> PathBuf {
         .to_path_buf()
 }
 
+/// Packages resolved from `node_modules` rather than stubbed, so their types are checked.
+const RESOLVED_MODULES: [&str; 3] = ["@trpc/server", "zod", "rxjs"];
+
 fn find_tsc_binary() -> Option<PathBuf> {
-    let workspace_tsc = project_root().join("node_modules/.bin/tsc");
-    if workspace_tsc.exists() {
-        return Some(workspace_tsc);
-    }
+    [project_root(), nestjs_trpc_package()]
+        .into_iter()
+        .map(|directory| directory.join("node_modules/.bin/tsc"))
+        .find(|tsc| tsc.exists())
+}
 
-    None
+/// The directory whose `node_modules` holds the packages `server.ts` imports: the package's own
+/// when it has them, otherwise the workspace root that Bun hoists them to.
+fn dependencies_directory() -> PathBuf {
+    let package = nestjs_trpc_package();
+    if package.join("node_modules/@trpc/server").exists() {
+        package
+    } else {
+        project_root()
+    }
 }
 
-fn build_tsconfig(nestjs_trpc_node_modules: &std::path::Path) -> String {
+fn build_tsconfig(dependencies_directory: &std::path::Path) -> String {
+    let node_modules = dependencies_directory.join("node_modules");
+    let paths: Vec<String> = RESOLVED_MODULES
+        .iter()
+        .map(|module| format!(r#""{module}": ["{}/{module}"]"#, node_modules.display()))
+        .collect();
     format!(
         r#"{{
     "compilerOptions": {{
@@ -46,19 +63,17 @@ fn build_tsconfig(nestjs_trpc_node_modules: &std::path::Path) -> String {
         "noEmit": true,
         "esModuleInterop": true,
         "moduleResolution": "node",
-        "typeRoots": ["{}/node_modules/@types"],
+        "typeRoots": ["{}/@types"],
         "baseUrl": ".",
         "paths": {{
-            "@trpc/server": ["{}/node_modules/@trpc/server"],
-            "zod": ["{}/node_modules/zod"]
+            {}
         }}
     }},
     "include": ["server.ts"],
     "files": ["stubs.d.ts"]
 }}"#,
-        nestjs_trpc_node_modules.display(),
-        nestjs_trpc_node_modules.display(),
-        nestjs_trpc_node_modules.display()
+        node_modules.display(),
+        paths.join(",\n            ")
     )
 }
 
@@ -79,10 +94,8 @@ fn create_stub_declarations(
         writeln!(declarations, "declare const {name}: any;").unwrap();
     }
 
-    let mut declared_modules: HashSet<String> = ["@trpc/server", "zod"]
-        .iter()
-        .map(|s| (*s).to_string())
-        .collect();
+    let mut declared_modules: HashSet<String> =
+        RESOLVED_MODULES.iter().map(|s| (*s).to_string()).collect();
     for module_specifier in &external_modules {
         writeln!(declarations, "declare module \"{module_specifier}\" {{").unwrap();
         let names = extract_import_names_for_module(generated_content, module_specifier);
@@ -151,7 +164,6 @@ fn collect_router_file_modules_recursive(
 fn extract_external_module_specifiers(content: &str) -> Vec<String> {
     use std::collections::HashSet;
 
-    let well_known = ["@trpc/server", "zod"];
     let mut modules = HashSet::new();
 
     for line in content.lines() {
@@ -176,7 +188,7 @@ fn extract_external_module_specifiers(content: &str) -> Vec<String> {
         let specifier = &after_quote[..end];
 
         let is_relative = specifier.starts_with('.') || specifier.starts_with('/');
-        let is_well_known = well_known.contains(&specifier);
+        let is_well_known = RESOLVED_MODULES.contains(&specifier);
         if !is_relative && !is_well_known {
             modules.insert(specifier.to_string());
         }
@@ -339,8 +351,7 @@ fn generate_and_validate_typescript(fixture_name: &str) {
 
     create_stub_declarations(output_path, &generated_content, &fixture_path);
 
-    let nestjs_trpc_node_modules = nestjs_trpc_package();
-    let tsconfig_content = build_tsconfig(&nestjs_trpc_node_modules);
+    let tsconfig_content = build_tsconfig(&dependencies_directory());
     let tsconfig_path = temporary_directory.path().join("tsconfig.json");
     fs::write(&tsconfig_path, &tsconfig_content).expect("Failed to write tsconfig");
 
@@ -410,6 +421,21 @@ fn validate_output_inference_compiles() {
     generate_and_validate_typescript("output-inference");
 }
 
+#[test]
+fn validate_subscription_inference_compiles() {
+    generate_and_validate_typescript("subscription-inference");
+}
+
+#[test]
+fn validate_recursive_schemas_compile() {
+    generate_and_validate_typescript("recursive-schemas");
+}
+
+#[test]
+fn validate_default_export_inference_compiles() {
+    generate_and_validate_typescript("default-export-router");
+}
+
 #[test]
 fn validation_catches_type_errors() {
     let temporary_directory = TempDir::new().expect("Failed to create temp directory");
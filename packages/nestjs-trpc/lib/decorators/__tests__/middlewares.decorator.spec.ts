@@ -28,6 +28,17 @@ describe('UseMiddlewares Decorator', () => {
     expect(metadata).toStrictEqual([TestMiddleware]);
   });
 
+  it('should add metadata to the property', () => {
+    class TestClass {
+      @UseMiddlewares(TestMiddleware)
+      testProperty = () => {};
+    }
+
+    const metadata = Reflect.getMetadata(MIDDLEWARES_KEY, TestClass.prototype, 'testProperty');
+    expect(metadata).toStrictEqual([TestMiddleware]);
+    expect(Reflect.getMetadata(MIDDLEWARES_KEY, TestClass.prototype)).toBeUndefined();
+  });
+
   it('should throw an error for invalid middleware on class', () => {
     expect(() => {
       @UseMiddlewares({} as any)
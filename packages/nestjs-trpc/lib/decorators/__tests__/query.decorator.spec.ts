@@ -1,6 +1,6 @@
 import 'reflect-metadata';
 import { Query } from '../query.decorator';
-import { PROCEDURE_METADATA_KEY, PROCEDURE_TYPE_KEY } from '../../trpc.constants';
+import { PROCEDURE_METADATA_KEY, PROCEDURE_PROPERTIES_KEY, PROCEDURE_TYPE_KEY } from '../../trpc.constants';
 import { ProcedureType } from '../../trpc.enum';
 import { z } from 'zod';
 
@@ -86,4 +86,17 @@ describe('Query Decorator', () => {
     const metadata = Reflect.getMetadata(PROCEDURE_METADATA_KEY, TestClass.prototype.testMethod);
     expect(metadata).toEqual({ meta });
   });
-});
\ No newline at end of file
+
+  it('should set procedure metadata on decorated properties', () => {
+    const inputSchema = z.string();
+
+    class TestClass {
+      @Query({ input: inputSchema })
+      testProperty = (input: string) => input;
+    }
+
+    expect(Reflect.getMetadata(PROCEDURE_TYPE_KEY, TestClass.prototype, 'testProperty')).toBe(ProcedureType.Query);
+    expect(Reflect.getMetadata(PROCEDURE_METADATA_KEY, TestClass.prototype, 'testProperty')).toEqual({ input: inputSchema });
+    expect(Reflect.getMetadata(PROCEDURE_PROPERTIES_KEY, TestClass.prototype)).toEqual(['testProperty']);
+  });
+});
@@ -14,7 +14,7 @@ import { validateEach } from '../utils/validate-each.util';
  * applied to every handler (method) in the router.
  *
  * When `@UseMiddlewares` is used at the individual handler level, the middleware
- * will apply only to that specific method.
+ * will apply only to that specific method or property.
  *
  * @param middlewares a single middleware instance or class, or a list of comma separated middleware instances
  * or classes.
@@ -25,7 +25,7 @@ import { validateEach } from '../utils/validate-each.util';
  */
 export function UseMiddlewares(
   ...middlewares: Array<Class<TRPCMiddleware> | Constructor<TRPCMiddleware>>
-): MethodDecorator & ClassDecorator {
+): MethodDecorator & PropertyDecorator & ClassDecorator {
   return (
     target: any,
     key?: string | symbol,
@@ -60,6 +60,10 @@ export function UseMiddlewares(
       '@UseMiddlewares',
       'middleware',
     );
+    if (key != null) {
+      Reflect.defineMetadata(MIDDLEWARES_KEY, [...middlewares], target, key);
+      return;
+    }
     Reflect.defineMetadata(MIDDLEWARES_KEY, [...middlewares], target);
     return target;
   };
@@ -76,7 +80,7 @@ export function UseMiddlewares(
  * applied to every handler (method) in the router.
  *
  * When `@Middlewares` is used at the individual handler level, the middleware
- * will apply only to that specific method.
+ * will apply only to that specific method or property.
  *
  * @param middlewares a single middleware instance or class, or a list of comma separated middleware instances
  * or classes.
@@ -87,7 +91,7 @@ export function UseMiddlewares(
  */
 export function Middlewares(
   ...middlewares: Array<Class<TRPCMiddleware> | Constructor<TRPCMiddleware>>
-): MethodDecorator & ClassDecorator {
+): MethodDecorator & PropertyDecorator & ClassDecorator {
   return (
     target: any,
     key?: string | symbol,
@@ -122,6 +126,10 @@ export function Middlewares(
       '@Middlewares',
       'middleware',
     );
+    if (key != null) {
+      Reflect.defineMetadata(MIDDLEWARES_KEY, [...middlewares], target, key);
+      return;
+    }
     Reflect.defineMetadata(MIDDLEWARES_KEY, [...middlewares], target);
     return target;
   };
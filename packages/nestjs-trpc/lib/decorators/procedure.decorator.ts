@@ -0,0 +1,48 @@
+import {
+  PROCEDURE_METADATA_KEY,
+  PROCEDURE_PROPERTIES_KEY,
+  PROCEDURE_TYPE_KEY,
+} from '../trpc.constants';
+import type { ProcedureType } from '../trpc.enum';
+import type { Parser } from '../interfaces/parser.interface';
+
+export interface ProcedureDecoratorArgs {
+  input?: Parser;
+  output?: Parser;
+  meta?: Record<string, unknown>;
+}
+
+/**
+ * Records the procedure metadata shared by `@Query`, `@Mutation` and `@Subscription`.
+ *
+ * Methods carry their metadata on the method itself. Properties such as
+ * `getUser = async (input) => {...}` only exist on instances, so their metadata is
+ * stored against the property key on the prototype, which also lists the
+ * decorated property keys for the `ProcedureFactory` to find.
+ */
+export function ProcedureDecorator(
+  type: ProcedureType,
+  args?: ProcedureDecoratorArgs,
+): MethodDecorator & PropertyDecorator {
+  return (
+    target: any,
+    key: string | symbol,
+    descriptor?: TypedPropertyDescriptor<any>,
+  ) => {
+    if (descriptor) {
+      Reflect.defineMetadata(PROCEDURE_TYPE_KEY, type, descriptor.value);
+      Reflect.defineMetadata(PROCEDURE_METADATA_KEY, args, descriptor.value);
+      return descriptor;
+    }
+
+    const properties: Array<string | symbol> =
+      Reflect.getMetadata(PROCEDURE_PROPERTIES_KEY, target) ?? [];
+    Reflect.defineMetadata(PROCEDURE_TYPE_KEY, type, target, key);
+    Reflect.defineMetadata(PROCEDURE_METADATA_KEY, args, target, key);
+    Reflect.defineMetadata(
+      PROCEDURE_PROPERTIES_KEY,
+      [...properties, key],
+      target,
+    );
+  };
+}
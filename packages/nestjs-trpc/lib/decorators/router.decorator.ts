@@ -12,6 +12,7 @@ import { ROUTER_METADATA_KEY } from '../trpc.constants';
  *
  * @param {object} args configuration object specifying:
  * - `alias` - string that defines a router alias. The alias is used both in the auto schema file generation, and for the actual api access.
+ *   Dotted or slash-separated aliases such as `admin.users` nest the router under `admin`.
  *
  * @see [Routers](https://nestjs-trpc.io/docs/routers)
  *
@@ -1,10 +1,9 @@
-import { applyDecorators, SetMetadata } from '@nestjs/common';
-import { PROCEDURE_METADATA_KEY, PROCEDURE_TYPE_KEY } from '../trpc.constants';
 import { ProcedureType } from '../trpc.enum';
-import type { Parser } from '../interfaces/parser.interface';
+import { ProcedureDecorator } from './procedure.decorator';
+import type { ProcedureDecoratorArgs } from './procedure.decorator';
 
 /**
- * Decorator that marks a router class method as a TRPC subscription procedure that can receive inbound
+ * Decorator that marks a router class method or property as a TRPC subscription procedure that can receive inbound
  * requests and produce a stream of responses via Server-Sent Events.
  *
  * A TRPC subscription procedure is responsible for real-time data streaming.
@@ -18,15 +17,8 @@ import type { Parser } from '../interfaces/parser.interface';
  *
  * @publicApi
  */
-export function Subscription(args?: {
-  input?: Parser;
-  output?: Parser;
-  meta?: Record<string, unknown>;
-}) {
-  return applyDecorators(
-    ...[
-      SetMetadata(PROCEDURE_TYPE_KEY, ProcedureType.Subscription),
-      SetMetadata(PROCEDURE_METADATA_KEY, args),
-    ],
-  );
+export function Subscription(
+  args?: ProcedureDecoratorArgs,
+): MethodDecorator & PropertyDecorator {
+  return ProcedureDecorator(ProcedureType.Subscription, args);
 }
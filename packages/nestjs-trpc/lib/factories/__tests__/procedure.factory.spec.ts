@@ -177,6 +177,76 @@ describe('ProcedureFactory', () => {
       expect(result).toHaveLength(1);
       expect(result[0].meta).toBeUndefined();
     });
+
+    it('should return property procedures', () => {
+      class AuthMiddleware implements TRPCMiddleware {
+        use(opts: any) {
+          return opts.next();
+        }
+      }
+
+      class UserRouter {
+        @Query({ input: z.object({ id: z.string() }) })
+        @UseMiddlewares(AuthMiddleware)
+        getUser = async (input: { id: string }) => ({ id: input.id });
+
+        helper = () => 'not a procedure';
+      }
+
+      const mockInstance = new UserRouter();
+      const mockPrototype = Object.getPrototypeOf(mockInstance);
+
+      // The scanner reports instance-own function properties as method names too.
+      metadataScanner.getAllMethodNames.mockImplementation(() => ['getUser', 'helper']);
+
+      const result = procedureFactory.getProcedures(mockInstance, mockPrototype);
+
+      expect(result).toHaveLength(1);
+      expect(result[0]).toMatchObject({
+        name: 'getUser',
+        type: ProcedureType.Query,
+        input: expect.any(Object),
+        middlewares: [AuthMiddleware],
+        implementation: mockInstance.getUser,
+        params: [
+          { type: ProcedureParamDecoratorType.Input, index: 0 },
+          { type: ProcedureParamDecoratorType.Options, index: 1 },
+        ],
+      });
+    });
+
+    it('should call property procedures with the input and options', async () => {
+      const t = initTRPC.context().create();
+
+      class UserRouter {
+        @Mutation({ input: z.object({ id: z.string() }) })
+        'delete-user' = async (input: { id: string }, opts: any) => ({
+          deleted: input.id,
+          path: opts.path,
+        });
+      }
+
+      const mockInstance = new UserRouter();
+      const mockPrototype = Object.getPrototypeOf(mockInstance);
+
+      metadataScanner.getAllMethodNames.mockImplementation(() => ['delete-user']);
+      (moduleRef.get as jest.Mock).mockReturnValue(mockInstance);
+
+      const procedures = procedureFactory.getProcedures(mockInstance, mockPrototype);
+      const result = procedureFactory.serializeProcedures(
+        procedures,
+        mockInstance,
+        'users',
+        t.procedure,
+        [],
+      );
+      const caller = t.createCallerFactory(t.router(result))({});
+
+      await expect(caller['delete-user']({ id: '1' })).resolves.toEqual({
+        deleted: '1',
+        path: 'delete-user',
+      });
+    });
   });
 
   describe('serializeProcedures', () => {
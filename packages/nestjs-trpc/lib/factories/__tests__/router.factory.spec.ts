@@ -216,5 +216,141 @@ describe('RouterFactory', () => {
         []
       );
     });
+
+    it('should nest routers with dotted or slash-separated aliases', () => {
+      @Reflect.metadata(ROUTER_METADATA_KEY, { alias: 'admin.users' })
+      class AdminUsersRouter {}
+
+      @Reflect.metadata(ROUTER_METADATA_KEY, { alias: 'admin/posts' })
+      class AdminPostsRouter {}
+
+      const mockModule = {
+        providers: new Map([
+          ['AdminUsersRouter', { name: 'AdminUsersRouter', instance: new AdminUsersRouter() }],
+          ['AdminPostsRouter', { name: 'AdminPostsRouter', instance: new AdminPostsRouter() }],
+        ]),
+      };
+      modulesContainer.set('TestModule', mockModule as any);
+
+      (procedureFactory.getProcedures as jest.Mock).mockReturnValue([]);
+      (procedureFactory.serializeProcedures as jest.Mock)
+        .mockReturnValueOnce({ list: procedure.query(() => 'users') })
+        .mockReturnValueOnce({ list: procedure.query(() => 'posts') });
+
+      const result = routerFactory.serializeRoutes(router, procedure as any);
+
+      expect(result.admin.users).toHaveProperty('list');
+      expect(result.admin.posts).toHaveProperty('list');
+      expect(procedureFactory.serializeProcedures).toHaveBeenCalledWith(
+        [],
+        expect.any(AdminUsersRouter),
+        'admin.users',
+        procedure,
+        [],
+      );
+    });
+
+    it('should reject a path that is both a procedure and a sub-router', () => {
+      @Reflect.metadata(ROUTER_METADATA_KEY, { alias: 'admin' })
+      class AdminRouter {}
+
+      @Reflect.metadata(ROUTER_METADATA_KEY, { alias: 'admin.users' })
+      class AdminUsersRouter {}
+
+      const mockModule = {
+        providers: new Map([
+          ['AdminRouter', { name: 'AdminRouter', instance: new AdminRouter() }],
+          ['AdminUsersRouter', { name: 'AdminUsersRouter', instance: new AdminUsersRouter() }],
+        ]),
+      };
+      modulesContainer.set('TestModule', mockModule as any);
+
+      (procedureFactory.getProcedures as jest.Mock).mockReturnValue([]);
+      (procedureFactory.serializeProcedures as jest.Mock)
+        .mockReturnValueOnce({ users: procedure.query(() => 'users') })
+        .mockReturnValueOnce({ list: procedure.query(() => 'list') });
+
+      expect(() => routerFactory.serializeRoutes(router, procedure as any)).toThrow(
+        'Router path "admin.users" is both a procedure and a sub-router.',
+      );
+    });
+
+    it('should reject routers merged under one alias that declare the same procedure', () => {
+      @Reflect.metadata(ROUTER_METADATA_KEY, { alias: 'users' })
+      class UsersRouter {}
+
+      @Reflect.metadata(ROUTER_METADATA_KEY, { alias: 'users' })
+      class MoreUsersRouter {}
+
+      const mockModule = {
+        providers: new Map([
+          ['UsersRouter', { name: 'UsersRouter', instance: new UsersRouter() }],
+          ['MoreUsersRouter', { name: 'MoreUsersRouter', instance: new MoreUsersRouter() }],
+        ]),
+      };
+      modulesContainer.set('TestModule', mockModule as any);
+
+      (procedureFactory.getProcedures as jest.Mock).mockReturnValue([]);
+      (procedureFactory.serializeProcedures as jest.Mock)
+        .mockReturnValueOnce({ list: procedure.query(() => 'users') })
+        .mockReturnValueOnce({ list: procedure.query(() => 'more users') });
+
+      expect(() => routerFactory.serializeRoutes(router, procedure as any)).toThrow(
+        'Procedure "users.list" is declared by more than one router.',
+      );
+    });
+
+    it('should key routers the way the generated AppRouter does', () => {
+      @Reflect.metadata(ROUTER_METADATA_KEY, { alias: 'user-profile' })
+      class UserProfileRouter {}
+
+      @Reflect.metadata(ROUTER_METADATA_KEY, {})
+      class AuditLogRouter {}
+
+      const mockModule = {
+        providers: new Map([
+          ['UserProfileRouter', { name: 'UserProfileRouter', instance: new UserProfileRouter() }],
+          ['AuditLogRouter', { name: 'AuditLogRouter', instance: new AuditLogRouter() }],
+        ]),
+      };
+      modulesContainer.set('TestModule', mockModule as any);
+
+      (procedureFactory.getProcedures as jest.Mock).mockReturnValue([]);
+      (procedureFactory.serializeProcedures as jest.Mock)
+        .mockReturnValueOnce({ get: procedure.query(() => 'profile') })
+        .mockReturnValueOnce({ list: procedure.query(() => 'audit') });
+
+      const result = routerFactory.serializeRoutes(router, procedure as any);
+
+      expect(result.userProfile).toHaveProperty('get');
+      expect(result.auditLogRouter).toHaveProperty('list');
+    });
+
+    it('should camel-case aliases that are not identifiers', () => {
+      @Reflect.metadata(ROUTER_METADATA_KEY, { alias: 'User Profile' })
+      class UserProfileRouter {}
+
+      @Reflect.metadata(ROUTER_METADATA_KEY, { alias: 'admin.user_settings' })
+      class UserSettingsRouter {}
+
+      const mockModule = {
+        providers: new Map([
+          ['UserProfileRouter', { name: 'UserProfileRouter', instance: new UserProfileRouter() }],
+          ['UserSettingsRouter', { name: 'UserSettingsRouter', instance: new UserSettingsRouter() }],
+        ]),
+      };
+      modulesContainer.set('TestModule', mockModule as any);
+
+      (procedureFactory.getProcedures as jest.Mock).mockReturnValue([]);
+      (procedureFactory.serializeProcedures as jest.Mock)
+        .mockReturnValueOnce({ get: procedure.query(() => 'profile') })
+        .mockReturnValueOnce({ list: procedure.query(() => 'settings') });
+
+      const result = routerFactory.serializeRoutes(router, procedure as any);
+
+      expect(result.userProfile).toHaveProperty('get');
+      expect(result.admin.userSettings).toHaveProperty('list');
+      expect(result['User Profile']).toBeUndefined();
+    });
   });
-});
\ No newline at end of file
+});
@@ -5,6 +5,7 @@ import {
   MIDDLEWARES_KEY,
   PROCEDURE_METADATA_KEY,
   PROCEDURE_PARAM_METADATA_KEY,
+  PROCEDURE_PROPERTIES_KEY,
   PROCEDURE_TYPE_KEY,
   TRPC_LOGGER,
 } from '../trpc.constants';
@@ -32,11 +33,24 @@ export class ProcedureFactory {
     instance: any,
     prototype: Record<string, (...args: Array<unknown>) => unknown>,
   ): Array<ProcedureFactoryMetadata> {
-    const methodNames = this.metadataScanner.getAllMethodNames(instance);
+    const propertyNames: Array<string> =
+      Reflect.getMetadata(PROCEDURE_PROPERTIES_KEY, prototype) ?? [];
+    const methodNames = this.metadataScanner
+      .getAllMethodNames(instance)
+      .filter(
+        (name) =>
+          !propertyNames.includes(name) &&
+          typeof prototype[name] === 'function',
+      );
 
-    return methodNames.map((name) =>
-      this.extractProcedureMetadata(name, prototype),
-    );
+    return [
+      ...methodNames.map((name) =>
+        this.extractProcedureMetadata(name, prototype),
+      ),
+      ...propertyNames.map((name) =>
+        this.extractPropertyProcedureMetadata(name, instance, prototype),
+      ),
+    ];
   }
 
   private extractProcedureParams(
@@ -71,6 +85,45 @@ export class ProcedureFactory {
     };
   }
 
+  /**
+   * Property procedures such as `getUser = async (input) => {...}` can't take parameter
+   * decorators, so they are called with the procedure input and then its options.
+   */
+  private extractPropertyProcedureMetadata(
+    name: string,
+    instance: Record<string, ProcedureImplementation>,
+    prototype: object,
+  ): ProcedureFactoryMetadata {
+    const implementation = instance[name];
+
+    if (typeof implementation !== 'function') {
+      throw new Error(
+        `Procedure property "${name}" of ${instance.constructor.name} must be a function.`,
+      );
+    }
+
+    const type = Reflect.getMetadata(PROCEDURE_TYPE_KEY, prototype, name);
+    const metadata = Reflect.getMetadata(PROCEDURE_METADATA_KEY, prototype, name);
+
+    const middlewares: Array<
+      Class<TRPCMiddleware> | Constructor<TRPCMiddleware>
+    > = Reflect.getMetadata(MIDDLEWARES_KEY, prototype, name) || [];
+
+    return {
+      input: metadata?.input,
+      output: metadata?.output,
+      meta: metadata?.meta,
+      middlewares,
+      type,
+      name,
+      implementation,
+      params: [
+        { type: ProcedureParamDecoratorType.Input, index: 0 },
+        { type: ProcedureParamDecoratorType.Options, index: 1 },
+      ],
+    };
+  }
+
   serializeProcedures(
     procedures: Array<ProcedureFactoryMetadata>,
     instance: any,
@@ -76,11 +76,12 @@ export class RouterFactory {
     procedure: TRPCPublicProcedure,
   ): Record<string, any> {
     const routers = this.getRouters();
-    const routerSchema = Object.create({});
+    const routerSchema: Record<string, any> = {};
 
     routers.forEach((route) => {
       const { instance, name, middlewares, alias } = route;
-      const camelCasedRouterName = camelCase(alias ?? name);
+      const routerPath = this.getRouterPath(alias, name);
+      const camelCasedRouterName = routerPath.join('.');
       const prototype = Object.getPrototypeOf(instance);
 
       const procedures = this.procedureFactory.getProcedures(
@@ -101,9 +102,58 @@ export class RouterFactory {
         middlewares,
       );
 
-      routerSchema[camelCasedRouterName] = routerProcedures;
+      this.assignRouterProcedures(routerSchema, routerPath, routerProcedures);
     });
 
     return routerSchema;
   }
+
+  /**
+   * Splits a dotted or slash-separated alias such as `admin.users` into nested router keys,
+   * falling back to the class name. Every key is camel-cased, so `user-profile` is keyed as
+   * `userProfile`, matching the `AppRouter` type the CLI generates.
+   */
+  private getRouterPath(alias: string | undefined, name: string): Array<string> {
+    const segments = (alias ?? '')
+      .split(/[./]/)
+      .map((segment) => camelCase(segment))
+      .filter((segment) => segment.length > 0);
+    return segments.length > 0 ? segments : [camelCase(name)];
+  }
+
+  private assignRouterProcedures(
+    routerSchema: Record<string, any>,
+    routerPath: Array<string>,
+    routerProcedures: Record<string, any>,
+  ): void {
+    const routerNode = routerPath.reduce((node, segment, index) => {
+      if (typeof node[segment] === 'function') {
+        this.throwRouterPathConflict(routerPath.slice(0, index + 1));
+      }
+      if (node[segment] == null) {
+        node[segment] = {};
+      }
+      return node[segment];
+    }, routerSchema);
+
+    for (const procedureName of Object.keys(routerProcedures)) {
+      const existing = routerNode[procedureName];
+      if (typeof existing === 'function') {
+        throw new Error(
+          `Procedure "${[...routerPath, procedureName].join('.')}" is declared by more than one router.`,
+        );
+      }
+      if (existing != null) {
+        this.throwRouterPathConflict([...routerPath, procedureName]);
+      }
+    }
+
+    Object.assign(routerNode, routerProcedures);
+  }
+
+  private throwRouterPathConflict(routerPath: Array<string>): never {
+    throw new Error(
+      `Router path "${routerPath.join('.')}" is both a procedure and a sub-router.`,
+    );
+  }
 }
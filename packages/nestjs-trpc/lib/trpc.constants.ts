@@ -7,6 +7,7 @@ export const ROUTER_METADATA_KEY = Symbol('trpc:router_type');
 
 export const PROCEDURE_TYPE_KEY = Symbol('trpc:procedure_type');
 export const PROCEDURE_METADATA_KEY = Symbol('trpc:procedure_metadata');
+export const PROCEDURE_PROPERTIES_KEY = Symbol('trpc:procedure_properties');
 
 export const PROCEDURE_PARAM_METADATA_KEY = Symbol(
   'trpc:procedure_param_metadata',